@@ -1,15 +1,46 @@
-use crate::services::{check_runtime, RuntimeInfo};
+use crate::models::{
+    ArtifactKind, ArtifactOptions, CargoOptions, CcOptions, Diagnostic, JsRuntime, LimitKind,
+    NodeOptions, Project, ProjectFile, PythonOptions, RunConfig, RunLimits, TemplateType,
+};
+use crate::services::artifacts::{
+    self, filter_asm, filter_javap, filter_llvm_ir, map_mir, validate_options, Artifact,
+    ArtifactError, ARTIFACT_NAME,
+};
+use crate::services::cargo_project::{extract_manifest, generate_manifest, parse_build_output};
+use crate::services::cc_compiler::{compiler_args, select_compiler, CcLanguage};
+use crate::services::compile_cache::{self, CacheKey, CompileCache};
+use crate::services::diagnostics::{
+    parse_gcc, parse_go, parse_javac, parse_mypy, parse_python, parse_rustc_json, parse_scala,
+    parse_tsc,
+};
+use crate::services::go_module::{GoModule, GO_MOD, MODULE_PATH};
+use crate::services::language_registry::{
+    template_language, LanguageDefinition, LanguageRegistry, LanguageRunner, LANGUAGES_DIR,
+};
+use crate::services::missing_modules::{MissingModule, PackageInstaller};
+use crate::services::node_env::{find_manifest, NodeEnv};
+use crate::services::process_group::{self, DEFAULT_GRACE_PERIOD, EXIT_GRACE_PERIOD};
+use crate::services::python_env::{find_requirements, PythonEnvError, Requirements, Venv};
+use crate::services::run_config::apply_run_config;
+use crate::services::runtime_overrides::{RuntimeOverrides, RUNTIMES_FILE};
+use crate::services::ts_strip::{check_args, check_result, output_name, NODE_FLAGS};
+use crate::services::version_managers::apply_pins;
+use crate::services::{
+    apply_rlimits, check_runtime, detect_exceeded, find_entry, is_waiting_for_stdin, limit_message,
+    step_limits, RuntimeInfo, Workspace,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
-use std::process::Stdio;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Output, Stdio};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tauri::menu::MenuItemKind;
 use tauri::{AppHandle, Emitter, Manager, State};
-use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::process::Command;
-use tokio::sync::Mutex;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{ChildStdin, Command};
+use tokio::sync::{mpsc, Mutex};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -18,6 +49,20 @@ pub struct ExecutionResult {
     pub stderr: String,
     pub exit_code: i32,
     pub duration_ms: u64,
+    /// Set when the program was stopped by one of its [`RunLimits`]
+    pub limit_exceeded: Option<LimitKind>,
+}
+
+/// Outcome of checking a project without running it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+    pub success: bool,
+    pub diagnostics: Vec<Diagnostic>,
+    /// What the checkers printed, which includes problems that don't parse as
+    /// diagnostics
+    pub output: String,
+    pub duration_ms: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,13 +70,39 @@ pub struct ExecutionResult {
 pub struct ExecutionOutput {
     pub line: String,
     pub stream: String,
+    /// Position of this chunk across both streams, starting at 1
+    pub seq: u64,
+    /// Unix time in milliseconds when the chunk was read
+    pub timestamp: i64,
+}
+
+/// Progress of preparing a run's environment (venv, installed packages)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EnvironmentState {
+    Creating,
+    Installing,
+    Ready,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvironmentStatus {
+    pub state: EnvironmentState,
+    pub message: String,
 }
 
 // --- Process Management ---
 
+struct RunningProcess {
+    child: tokio::process::Child,
+    stdin: Option<Arc<Mutex<ChildStdin>>>,
+}
+
 #[derive(Default, Clone)]
 pub struct RunningProcesses {
-    processes: Arc<Mutex<HashMap<String, tokio::process::Child>>>,
+    processes: Arc<Mutex<HashMap<String, RunningProcess>>>,
 }
 
 impl RunningProcesses {
@@ -41,24 +112,96 @@ impl RunningProcesses {
         }
     }
 
-    pub async fn insert(&self, window_id: String, child: tokio::process::Child) {
+    /// Track `child` as the window's process. A window runs one program at a
+    /// time: while it already has one, `child` is killed instead and `false`
+    /// returned, so the earlier process is never lost track of.
+    pub async fn insert(&self, window_id: String, mut child: tokio::process::Child) -> bool {
         let mut processes = self.processes.lock().await;
-        processes.insert(window_id, child);
+        if processes.contains_key(&window_id) {
+            drop(processes);
+            process_group::terminate(&mut child, Duration::ZERO).await;
+            return false;
+        }
+        let stdin = child.stdin.take().map(|s| Arc::new(Mutex::new(s)));
+        processes.insert(window_id, RunningProcess { child, stdin });
+        true
     }
 
     pub async fn remove(&self, window_id: &str) -> Option<tokio::process::Child> {
         let mut processes = self.processes.lock().await;
-        processes.remove(window_id)
+        processes.remove(window_id).map(|p| p.child)
     }
 
+    /// Stop a window's program and its process group
     pub async fn kill(&self, window_id: &str) -> bool {
+        self.terminate(window_id, DEFAULT_GRACE_PERIOD).await
+    }
+
+    /// Stop a window's program, giving it `grace` to exit before it is killed
+    pub async fn terminate(&self, window_id: &str, grace: Duration) -> bool {
         if let Some(mut child) = self.remove(window_id).await {
-            let _ = child.kill().await;
+            process_group::terminate(&mut child, grace).await;
             true
         } else {
             false
         }
     }
+
+    /// Stop every tracked program at once, used when the app exits. Each gets
+    /// only [`EXIT_GRACE_PERIOD`] so quitting isn't held up.
+    pub async fn kill_all(&self) {
+        let children: Vec<_> = {
+            let mut processes = self.processes.lock().await;
+            processes.drain().map(|(_, p)| p.child).collect()
+        };
+
+        let handles: Vec<_> = children
+            .into_iter()
+            .map(|mut child| {
+                tauri::async_runtime::spawn(async move {
+                    process_group::terminate(&mut child, EXIT_GRACE_PERIOD).await;
+                })
+            })
+            .collect();
+        for handle in handles {
+            let _ = handle.await;
+        }
+    }
+
+    /// Whether `pid` is still the window's program and its stdin is open
+    async fn accepts_input(&self, window_id: &str, pid: u32) -> bool {
+        let processes = self.processes.lock().await;
+        processes
+            .get(window_id)
+            .is_some_and(|p| p.child.id() == Some(pid) && p.stdin.is_some())
+    }
+
+    pub async fn write_stdin(&self, window_id: &str, data: &[u8]) -> Result<(), String> {
+        // Clone the handle so a child that is slow to read does not block the map
+        let stdin = {
+            let processes = self.processes.lock().await;
+            let process = processes
+                .get(window_id)
+                .ok_or_else(|| "No program is running".to_string())?;
+            process
+                .stdin
+                .clone()
+                .ok_or_else(|| "Program input is closed".to_string())?
+        };
+
+        let mut stdin = stdin.lock().await;
+        stdin.write_all(data).await.map_err(|e| e.to_string())?;
+        stdin.flush().await.map_err(|e| e.to_string())
+    }
+
+    /// Close the program's stdin so it sees end-of-file
+    pub async fn close_stdin(&self, window_id: &str) -> bool {
+        let mut processes = self.processes.lock().await;
+        processes
+            .get_mut(window_id)
+            .and_then(|p| p.stdin.take())
+            .is_some()
+    }
 }
 
 // --- Menu State Updates ---
@@ -66,7 +209,12 @@ impl RunningProcesses {
 fn update_stop_menu_state(app: &AppHandle, window_id: &str, enabled: bool) {
     // Use emit_to to send event only to the specific window
     let _ = app.emit_to(window_id, "execution:state-changed", enabled);
+    set_stop_menu_enabled(app, window_id, enabled);
+}
 
+/// Enable or disable the window's native Stop menu item, without telling the
+/// frontend a program is running
+fn set_stop_menu_enabled(app: &AppHandle, window_id: &str, enabled: bool) {
     if let Some(window) = app.get_webview_window(window_id) {
         if let Some(menu) = window.menu() {
             if let Ok(items) = menu.items() {
@@ -92,26 +240,39 @@ fn update_stop_menu_state(app: &AppHandle, window_id: &str, enabled: bool) {
 // --- Runtime Check Helper ---
 
 /// Check if a runtime is available, emit error if not
-fn check_runtime_available(runtime: &RuntimeInfo, window_id: &str, app: &AppHandle) -> bool {
-    let result = check_runtime(runtime);
+fn check_runtime_available(ctx: &RunContext, runtime: &RuntimeInfo) -> bool {
+    let result = check_runtime(runtime, &ctx.runtimes);
     if !result.available {
         if let Some(hint) = result.install_hint {
-            emit_completion(app, window_id, "", &hint, 1, 0);
+            emit_completion(&ctx.app, &ctx.window_id, "", &hint, 1, 0);
         }
         return false;
     }
     true
 }
 
+/// A missing runtime as a check's error, with the hint a run would show
+fn require_runtime(ctx: &RunContext, runtime: &RuntimeInfo) -> Result<(), String> {
+    let result = check_runtime(runtime, &ctx.runtimes);
+    if result.available {
+        return Ok(());
+    }
+    Err(result
+        .install_hint
+        .unwrap_or_else(|| format!("{} is not installed", runtime.name)))
+}
+
 // --- Public Commands ---
 
 #[tauri::command]
 pub async fn stop_execution(
     window_id: String,
+    grace_period_ms: Option<u64>,
     processes: State<'_, RunningProcesses>,
     app: AppHandle,
 ) -> Result<bool, String> {
-    let killed = processes.kill(&window_id).await;
+    let grace = grace_period_ms.map_or(DEFAULT_GRACE_PERIOD, Duration::from_millis);
+    let killed = processes.terminate(&window_id, grace).await;
     update_stop_menu_state(&app, &window_id, false);
     Ok(killed)
 }
@@ -125,364 +286,2606 @@ pub async fn kill_window_processes(
     Ok(())
 }
 
+#[tauri::command]
+pub async fn write_stdin(
+    window_id: String,
+    data: String,
+    processes: State<'_, RunningProcesses>,
+    app: AppHandle,
+) -> Result<(), String> {
+    processes.write_stdin(&window_id, data.as_bytes()).await?;
+    let _ = app.emit_to(&window_id, "execution:waiting-input", false);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn close_stdin(
+    window_id: String,
+    processes: State<'_, RunningProcesses>,
+) -> Result<bool, String> {
+    Ok(processes.close_stdin(&window_id).await)
+}
+
 #[tauri::command]
 pub async fn execute_python(
     code: String,
     window_id: String,
+    limits: Option<RunLimits>,
+    python: Option<PythonOptions>,
+    run: Option<RunConfig>,
     processes: State<'_, RunningProcesses>,
     app: AppHandle,
 ) -> Result<(), String> {
-    if !check_runtime_available(&RuntimeInfo::PYTHON, &window_id, &app) {
-        return Ok(());
-    }
-    execute_interpreted(&code, &window_id, "py", "python3", &[], &processes, &app).await
+    let mut ctx = RunContext::new(window_id, limits, &processes, app)?;
+    ctx.python = python.unwrap_or_default();
+    ctx.run = run.unwrap_or_default();
+    execute_snippet(&ctx, "python", code).await
 }
 
 #[tauri::command]
 pub async fn execute_node(
     code: String,
     window_id: String,
+    limits: Option<RunLimits>,
+    node: Option<NodeOptions>,
+    run: Option<RunConfig>,
     processes: State<'_, RunningProcesses>,
     app: AppHandle,
 ) -> Result<(), String> {
-    if !check_runtime_available(&RuntimeInfo::NODE, &window_id, &app) {
-        return Ok(());
-    }
-    execute_interpreted(&code, &window_id, "js", "node", &[], &processes, &app).await
+    let mut ctx = RunContext::new(window_id, limits, &processes, app)?;
+    ctx.node = node.unwrap_or_default();
+    ctx.run = run.unwrap_or_default();
+    execute_snippet(&ctx, "node", code).await
 }
 
 #[tauri::command]
 pub async fn execute_typescript(
     code: String,
     window_id: String,
+    limits: Option<RunLimits>,
+    node: Option<NodeOptions>,
+    run: Option<RunConfig>,
     processes: State<'_, RunningProcesses>,
     app: AppHandle,
 ) -> Result<(), String> {
-    if !check_runtime_available(&RuntimeInfo::NPX, &window_id, &app) {
-        return Ok(());
-    }
-    execute_interpreted(&code, &window_id, "ts", "npx", &["tsx"], &processes, &app).await
+    let mut ctx = RunContext::new(window_id, limits, &processes, app)?;
+    ctx.node = node.unwrap_or_default();
+    ctx.run = run.unwrap_or_default();
+    execute_snippet(&ctx, "typescript", code).await
 }
 
 #[tauri::command]
 pub async fn execute_rust(
     code: String,
     window_id: String,
+    limits: Option<RunLimits>,
+    cargo: Option<CargoOptions>,
+    run: Option<RunConfig>,
     processes: State<'_, RunningProcesses>,
     app: AppHandle,
 ) -> Result<(), String> {
-    if !check_runtime_available(&RuntimeInfo::RUST, &window_id, &app) {
-        return Ok(());
-    }
-    execute_compiled_rust(&code, &window_id, &processes, &app).await
+    let mut ctx = RunContext::new(window_id, limits, &processes, app)?;
+    ctx.cargo = cargo.unwrap_or_default();
+    ctx.run = run.unwrap_or_default();
+    execute_snippet(&ctx, "rust", code).await
 }
 
 #[tauri::command]
 pub async fn execute_java(
     code: String,
     window_id: String,
+    limits: Option<RunLimits>,
+    run: Option<RunConfig>,
     processes: State<'_, RunningProcesses>,
     app: AppHandle,
 ) -> Result<(), String> {
-    // Java needs both javac (compiler) and java (runtime)
-    if !check_runtime_available(&RuntimeInfo::JAVAC, &window_id, &app) {
-        return Ok(());
-    }
-    if !check_runtime_available(&RuntimeInfo::JAVA, &window_id, &app) {
-        return Ok(());
-    }
-    execute_compiled_java(&code, &window_id, &processes, &app).await
+    let mut ctx = RunContext::new(window_id, limits, &processes, app)?;
+    ctx.run = run.unwrap_or_default();
+    execute_snippet(&ctx, "java", code).await
 }
 
-// --- Generic Execution Helpers ---
-
-/// Execute an interpreted language (Python, Node, TypeScript)
-async fn execute_interpreted(
-    code: &str,
-    window_id: &str,
-    extension: &str,
-    command: &str,
-    extra_args: &[&str],
-    processes: &State<'_, RunningProcesses>,
-    app: &AppHandle,
+/// Run a snippet in any registered language: a built-in one, or one defined
+/// by a TOML file in the app data dir's `languages` directory
+#[tauri::command]
+pub async fn execute(
+    language: String,
+    code: String,
+    window_id: String,
+    limits: Option<RunLimits>,
+    run: Option<RunConfig>,
+    processes: State<'_, RunningProcesses>,
+    app: AppHandle,
 ) -> Result<(), String> {
-    let temp_dir = std::env::temp_dir();
-    let id = window_id.replace("editor-", "");
-    let file_path = temp_dir.join(format!("codecell_{}.{}", id, extension));
+    let mut ctx = RunContext::new(window_id, limits, &processes, app)?;
+    ctx.run = run.unwrap_or_default();
+    execute_snippet(&ctx, &language, code).await
+}
 
-    std::fs::write(&file_path, code).map_err(|e| e.to_string())?;
+/// Every language `execute` can run
+#[tauri::command]
+pub fn list_languages(app: AppHandle) -> Vec<LanguageDefinition> {
+    language_registry(&app).languages().to_vec()
+}
 
-    let mut cmd = Command::new(command);
-    for arg in extra_args {
-        cmd.arg(arg);
-    }
-    cmd.arg(&file_path);
+/// Run a multi-file project.
+///
+/// Every project file is written into a fresh working directory and
+/// `entry_file` (the first file when omitted) is run from there.
+#[tauri::command]
+pub async fn execute_project(
+    project: Project,
+    entry_file: Option<String>,
+    window_id: String,
+    limits: Option<RunLimits>,
+    processes: State<'_, RunningProcesses>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let run = ProjectRun::new(
+        project,
+        entry_file.as_deref(),
+        window_id,
+        limits,
+        &processes,
+        app,
+    )?;
+    execute_files(&run.ctx, &run.language, &run.files, &run.entry).await
+}
 
-    let mut child = cmd
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .kill_on_drop(true)
-        .spawn()
-        .map_err(|e| format!("Failed to execute {}: {}", command, e))?;
+/// Check a project for errors without running it.
+///
+/// The project is compiled or type-checked the way a run would build it:
+/// `cargo check` or `rustc --emit=metadata`, javac, `tsc --noEmit`,
+/// `py_compile` followed by mypy when it's installed, and the language's
+/// `check` command for the rest. Checkers run under the run's limits, and
+/// Stop ends them.
+#[tauri::command]
+pub async fn check_project(
+    project: Project,
+    entry_file: Option<String>,
+    window_id: String,
+    limits: Option<RunLimits>,
+    processes: State<'_, RunningProcesses>,
+    app: AppHandle,
+) -> Result<CheckResult, String> {
+    let run = ProjectRun::new(
+        project,
+        entry_file.as_deref(),
+        window_id,
+        limits,
+        &processes,
+        app,
+    )?;
+    // Stop ends the check's compiler or checker
+    set_stop_menu_enabled(&run.ctx.app, &run.ctx.window_id, true);
+    let checked = check_files(&run.ctx, &run.language, &run.files, &run.entry).await;
+    set_stop_menu_enabled(&run.ctx.app, &run.ctx.window_id, false);
+    checked
+}
 
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
+/// Show what the compiler makes of a project: assembly, LLVM IR or MIR for
+/// Rust, bytecode for Java and assembly for C and C++.
+///
+/// Lines of the output come with the source line they were generated from,
+/// wherever the compiler's debug info records one. Compilers run under the
+/// run's limits, and Stop ends them.
+#[tauri::command]
+pub async fn emit_artifact(
+    project: Project,
+    entry_file: Option<String>,
+    options: ArtifactOptions,
+    window_id: String,
+    limits: Option<RunLimits>,
+    processes: State<'_, RunningProcesses>,
+    app: AppHandle,
+) -> Result<Artifact, String> {
+    validate_options(&options).map_err(|e| e.to_string())?;
+    let run = ProjectRun::new(
+        project,
+        entry_file.as_deref(),
+        window_id,
+        limits,
+        &processes,
+        app,
+    )?;
+    emit_files(&run.ctx, &run.language, &run.files, &run.entry, &options).await
+}
 
-    processes.insert(window_id.to_string(), child).await;
-    update_stop_menu_state(app, window_id, true);
+// --- Generic Execution Helpers ---
 
-    spawn_output_streamer(
-        stdout,
-        stderr,
-        window_id.to_string(),
-        app.clone(),
-        processes.inner().clone(),
-        vec![file_path],
-    );
+/// Everything a run needs besides the code itself
+struct RunContext {
+    window_id: String,
+    /// Keys per-project state such as Python venvs
+    project_id: String,
+    limits: RunLimits,
+    cargo: CargoOptions,
+    python: PythonOptions,
+    node: NodeOptions,
+    cc: CcOptions,
+    run: RunConfig,
+    /// The user's runtime paths, arguments and environment, read fresh for
+    /// every run
+    runtimes: RuntimeOverrides,
+    processes: RunningProcesses,
+    app: AppHandle,
+}
 
-    Ok(())
+impl RunContext {
+    fn new(
+        window_id: String,
+        limits: Option<RunLimits>,
+        processes: &State<'_, RunningProcesses>,
+        app: AppHandle,
+    ) -> Result<Self, String> {
+        let runtimes = RuntimeOverrides::load(&app_data_path(&app, RUNTIMES_FILE))
+            .map_err(|e| e.to_string())?;
+        Ok(Self {
+            project_id: window_id.replace("editor-", ""),
+            window_id,
+            limits: limits.unwrap_or_default(),
+            cargo: CargoOptions::default(),
+            python: PythonOptions::default(),
+            node: NodeOptions::default(),
+            cc: CcOptions::default(),
+            run: RunConfig::default(),
+            runtimes,
+            processes: processes.inner().clone(),
+            app,
+        })
+    }
 }
 
-/// Execute Rust (compile then run)
-async fn execute_compiled_rust(
-    code: &str,
-    window_id: &str,
-    processes: &State<'_, RunningProcesses>,
-    app: &AppHandle,
-) -> Result<(), String> {
-    let start = Instant::now();
-    let temp_dir = std::env::temp_dir();
-    let id = window_id.replace("editor-", "");
-    let source_path = temp_dir.join(format!("codecell_{}.rs", id));
-    let binary_path = temp_dir.join(format!("codecell_{}_bin", id));
+/// A project to run or check, with its options and pinned toolchains applied
+/// to the context
+struct ProjectRun {
+    ctx: RunContext,
+    language: LanguageDefinition,
+    files: Vec<ProjectFile>,
+    entry: String,
+}
 
-    std::fs::write(&source_path, code).map_err(|e| e.to_string())?;
+impl ProjectRun {
+    fn new(
+        project: Project,
+        entry_file: Option<&str>,
+        window_id: String,
+        limits: Option<RunLimits>,
+        processes: &State<'_, RunningProcesses>,
+        app: AppHandle,
+    ) -> Result<Self, String> {
+        let entry = find_entry(&project.files, entry_file)
+            .map_err(|e| e.to_string())?
+            .name
+            .clone();
+        let language_id = project
+            .language
+            .as_deref()
+            .or_else(|| template_language(&project.template))
+            .ok_or("Web projects run in the preview pane")?;
+        let language = language_registry(&app)
+            .get(language_id)
+            .map_err(|e| e.to_string())?
+            .clone();
+
+        let mut ctx = RunContext::new(window_id, limits, processes, app)?;
+        ctx.project_id = project.id;
+        ctx.cargo = project.cargo.unwrap_or_default();
+        ctx.python = project.python.unwrap_or_default();
+        ctx.node = project.node.unwrap_or_default();
+        ctx.cc = project.cc.unwrap_or_default();
+        ctx.run = project.run.unwrap_or_default();
+        let pins = project.toolchains.unwrap_or_default();
+        apply_pins(&pins, &mut ctx.runtimes).map_err(|e| e.to_string())?;
+        Ok(Self {
+            ctx,
+            language,
+            files: project.files,
+            entry,
+        })
+    }
+}
 
-    // Compile
-    let compile_output = Command::new("rustc")
-        .arg(&source_path)
-        .arg("-o")
-        .arg(&binary_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
-        .await
-        .map_err(|e| format!("Failed to compile Rust: {}", e))?;
+/// Built-in languages plus the user's definitions, read fresh for every
+/// run so edited definitions apply without a restart
+pub(crate) fn language_registry(app: &AppHandle) -> LanguageRegistry {
+    LanguageRegistry::load(&app_data_path(app, LANGUAGES_DIR))
+}
 
-    if !compile_output.status.success() {
-        let _ = std::fs::remove_file(&source_path);
-        emit_completion(
-            app,
-            window_id,
-            "",
-            &String::from_utf8_lossy(&compile_output.stderr),
-            compile_output.status.code().unwrap_or(-1),
-            start.elapsed().as_millis() as u64,
-        );
+async fn execute_snippet(ctx: &RunContext, language: &str, code: String) -> Result<(), String> {
+    let registry = language_registry(&ctx.app);
+    let language = registry.get(language).map_err(|e| e.to_string())?;
+    let file_name = match language.runner {
+        // javac requires the file to be named after its public class
+        LanguageRunner::Template(TemplateType::Java) => format!(
+            "{}.java",
+            extract_java_class_name(&code).unwrap_or_else(|| "Main".to_string())
+        ),
+        _ => language.entry_file(),
+    };
+    let files = [ProjectFile {
+        name: file_name.clone(),
+        content: code,
+        language: String::new(),
+    }];
+    execute_files(ctx, language, &files, &file_name).await
+}
+
+/// Write the files into a new workspace and run `entry` with the language's toolchain
+async fn execute_files(
+    ctx: &RunContext,
+    language: &LanguageDefinition,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<(), String> {
+    let js_runtime = alternative_js_runtime(ctx, language);
+    let runtimes = match &js_runtime {
+        Some(runtime) => std::slice::from_ref(runtime),
+        None => language.runtimes.as_slice(),
+    };
+    // Stops at the first missing runtime, which reports itself
+    if !runtimes
+        .iter()
+        .all(|runtime| check_runtime_available(ctx, runtime))
+    {
         return Ok(());
     }
 
-    // Run
-    let mut child = Command::new(&binary_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .kill_on_drop(true)
-        .spawn()
-        .map_err(|e| format!("Failed to run Rust binary: {}", e))?;
+    let workspace_parent = match &language.runner {
+        LanguageRunner::Template(TemplateType::Node | TemplateType::Typescript) => {
+            match prepare_node_packages(ctx, files).await? {
+                Some(dir) => dir,
+                None => return Ok(()),
+            }
+        }
+        _ => std::env::temp_dir(),
+    };
+
+    let workspace =
+        Workspace::create_in(&workspace_parent, &ctx.window_id).map_err(|e| e.to_string())?;
+    let entry_path = match workspace
+        .write_files(files)
+        .and_then(|()| workspace.resolve(entry))
+    {
+        Ok(path) => path,
+        Err(e) => {
+            workspace.remove();
+            return Err(e.to_string());
+        }
+    };
 
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
+    let template = match &language.runner {
+        LanguageRunner::Commands => {
+            return execute_commands(ctx, language, workspace, files, entry).await;
+        }
+        LanguageRunner::Template(template) => template,
+    };
+    match template {
+        // -u: unbuffered stdout, so prints interleave correctly with tracebacks
+        TemplateType::Python => {
+            let python = match python_interpreter(ctx, &workspace, files, entry).await {
+                Ok(Some(python)) => python,
+                Ok(None) => {
+                    workspace.remove();
+                    return Ok(());
+                }
+                Err(e) => {
+                    workspace.remove();
+                    return Err(e);
+                }
+            };
+            let python = ctx.runtimes.command_with("python3", &python);
+            execute_script(ctx, workspace, &entry_path, python, "python3", &["-u"]).await
+        }
+        TemplateType::Node | TemplateType::Typescript if js_runtime.is_some() => {
+            execute_deno_or_bun(ctx, workspace, &entry_path, files, template).await
+        }
+        TemplateType::Node => execute_interpreted(ctx, workspace, &entry_path, "node", &[]).await,
+        TemplateType::Typescript => {
+            typecheck_typescript(ctx, &workspace, files).await;
+            execute_typescript_on_node(ctx, workspace, &entry_path, files, entry).await
+        }
+        TemplateType::Rust => execute_compiled_rust(ctx, workspace, &entry_path, files).await,
+        TemplateType::Java => execute_compiled_java(ctx, workspace, &entry_path, files).await,
+        TemplateType::Go => execute_compiled_go(ctx, workspace, &entry_path, files).await,
+        TemplateType::C => execute_compiled_cc(ctx, workspace, files, CcLanguage::C).await,
+        TemplateType::Cpp => execute_compiled_cc(ctx, workspace, files, CcLanguage::Cpp).await,
+        TemplateType::Ruby => execute_interpreted(ctx, workspace, &entry_path, "ruby", &[]).await,
+        TemplateType::Php => execute_interpreted(ctx, workspace, &entry_path, "php", &[]).await,
+        // Perl and Lua block-buffer stdout into a pipe, which would hold back
+        // prompts printed before reading input
+        TemplateType::Perl => {
+            let autoflush = "-MIO::Handle;STDOUT->autoflush(1)";
+            execute_interpreted(ctx, workspace, &entry_path, "perl", &[autoflush]).await
+        }
+        TemplateType::Lua => {
+            let unbuffered = ["-e", "io.stdout:setvbuf('no')"];
+            execute_interpreted(ctx, workspace, &entry_path, "lua", &unbuffered).await
+        }
+        TemplateType::Bash => execute_interpreted(ctx, workspace, &entry_path, "bash", &[]).await,
+        TemplateType::Kotlin => execute_compiled_kotlin(ctx, workspace, &entry_path, files).await,
+        TemplateType::Scala => execute_compiled_scala(ctx, workspace, &entry_path, files).await,
+        // Classes from the other project files resolve from the workspace
+        TemplateType::Groovy => {
+            let classpath = workspace.path().to_string_lossy().into_owned();
+            execute_interpreted(ctx, workspace, &entry_path, "groovy", &["-cp", &classpath]).await
+        }
+        TemplateType::Web => {
+            workspace.remove();
+            Err("Web projects run in the preview pane".to_string())
+        }
+    }
+}
 
-    processes.insert(window_id.to_string(), child).await;
-    update_stop_menu_state(app, window_id, true);
+/// Execute JavaScript or TypeScript with Deno or Bun, which run TypeScript
+/// directly, without tsx
+async fn execute_deno_or_bun(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+    template: &TemplateType,
+) -> Result<(), String> {
+    if matches!(template, TemplateType::Typescript) {
+        typecheck_typescript(ctx, &workspace, files).await;
+    }
+    if ctx.node.runtime == JsRuntime::Bun {
+        return execute_interpreted(ctx, workspace, entry_path, "bun", &[]).await;
+    }
 
-    spawn_output_streamer(
-        stdout,
-        stderr,
-        window_id.to_string(),
-        app.clone(),
-        processes.inner().clone(),
-        vec![source_path, binary_path],
-    );
+    let mut args = vec!["run".to_string()];
+    args.extend(deno_permission_flags(ctx.node.deno_permissions.as_deref()));
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    execute_interpreted(ctx, workspace, entry_path, "deno", &args).await
+}
 
-    Ok(())
+/// The runtime replacing Node for JavaScript and TypeScript, when the project
+/// picked Deno or Bun
+fn alternative_js_runtime(ctx: &RunContext, language: &LanguageDefinition) -> Option<RuntimeInfo> {
+    if !matches!(
+        language.runner,
+        LanguageRunner::Template(TemplateType::Node | TemplateType::Typescript)
+    ) {
+        return None;
+    }
+    match ctx.node.runtime {
+        JsRuntime::Node => None,
+        JsRuntime::Deno => Some(RuntimeInfo::DENO),
+        JsRuntime::Bun => Some(RuntimeInfo::BUN),
+    }
 }
 
-/// Execute Java (compile then run)
-async fn execute_compiled_java(
-    code: &str,
-    window_id: &str,
-    processes: &State<'_, RunningProcesses>,
-    app: &AppHandle,
-) -> Result<(), String> {
+/// Deno's `--allow-*` flags. Without a list everything is allowed, as it is
+/// for Node.
+fn deno_permission_flags(permissions: Option<&[String]>) -> Vec<String> {
+    match permissions {
+        None => vec!["--allow-all".to_string()],
+        Some(permissions) => permissions
+            .iter()
+            .map(|permission| format!("--allow-{permission}"))
+            .collect(),
+    }
+}
+
+/// Pick the interpreter for a Python run: the user's `python3`, or the
+/// project's venv when it declares requirements.
+///
+/// Setup progress is shown in the window. Returns `Ok(None)` when setup
+/// failed; the error has already been reported to the window.
+async fn python_interpreter(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<Option<String>, String> {
     let start = Instant::now();
-    let class_name = extract_java_class_name(code).unwrap_or_else(|| "Main".to_string());
-    let id = window_id.replace("editor-", "");
-    let temp_dir = std::env::temp_dir().join(format!("codecell_java_{}", id));
+    let report = |state, message| emit_environment(&ctx.app, &ctx.window_id, state, message);
+    let prepared = prepare_python(ctx, workspace, files, entry, report).await;
+    report_setup(
+        ctx,
+        prepared,
+        start,
+        "Failed to set up the virtual environment",
+    )
+}
 
-    std::fs::create_dir_all(&temp_dir).map_err(|e| e.to_string())?;
+/// Why a project's venv or npm packages couldn't be set up
+enum SetupFailure {
+    /// The setup command failed; its output says why
+    Command(Output),
+    Error(String),
+}
 
-    let source_path = temp_dir.join(format!("{}.java", class_name));
-    std::fs::write(&source_path, code).map_err(|e| e.to_string())?;
+/// Report a failed setup to the window the way a run's failure is reported.
+/// Returns `Ok(None)` when `result` failed with the setup command's output.
+fn report_setup<T>(
+    ctx: &RunContext,
+    result: Result<T, SetupFailure>,
+    start: Instant,
+    message: &str,
+) -> Result<Option<T>, String> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(SetupFailure::Command(output)) => Ok(environment_failed(ctx, &output, start, message)),
+        Err(SetupFailure::Error(message)) => Err(message),
+    }
+}
 
-    // Compile
-    let compile_output = Command::new("javac")
-        .arg(&source_path)
-        .current_dir(&temp_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .output()
+/// A failed setup as a check's result
+fn setup_checked(failure: SetupFailure) -> Result<CheckResult, String> {
+    match failure {
+        SetupFailure::Command(output) => Ok(checked(&output, |_| Vec::new())),
+        SetupFailure::Error(message) => Err(message),
+    }
+}
+
+/// The interpreter for a Python project, creating its venv on first use
+/// and reinstalling whenever the requirements change.
+///
+/// `report` is told about each setup step; nothing is emitted to the window
+/// otherwise, so checks can share it with runs.
+async fn prepare_python(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    entry: &str,
+    report: impl Fn(EnvironmentState, String),
+) -> Result<String, SetupFailure> {
+    let requirements = match python_setup(
+        &ctx.runtimes,
+        files,
+        entry,
+        ctx.python.wheelhouse.as_deref(),
+    ) {
+        Ok(PythonSetup::Venv(requirements)) => requirements,
+        Ok(PythonSetup::System(python)) => return Ok(python),
+        Err(e) => return Err(SetupFailure::Error(e.to_string())),
+    };
+
+    let venv = Venv::new(&app_data_path(&ctx.app, "venvs"), &ctx.project_id);
+    let python = venv.python().to_string_lossy().into_owned();
+    let base_python = ctx.runtimes.program("python3");
+
+    // A venv made by another interpreter (the project pinned a new one) is
+    // recreated, which also drops its packages
+    if !venv.exists() || !venv.created_with(&base_python) {
+        report(
+            EnvironmentState::Creating,
+            "Creating virtual environment".to_string(),
+        );
+        let output = run_step(
+            ctx,
+            ctx.runtimes
+                .command("python3")
+                .args(["-m", "venv", "--clear"])
+                .arg(venv.path()),
+        )
         .await
-        .map_err(|e| format!("Failed to compile Java: {}", e))?;
+        .map_err(|e| SetupFailure::Error(format!("Failed to create virtual environment: {}", e)))?;
+        if !output.status.success() {
+            return Err(SetupFailure::Command(output));
+        }
+    }
 
-    if !compile_output.status.success() {
-        let _ = std::fs::remove_dir_all(&temp_dir);
-        emit_completion(
-            app,
-            window_id,
-            "",
-            &String::from_utf8_lossy(&compile_output.stderr),
-            compile_output.status.code().unwrap_or(-1),
-            start.elapsed().as_millis() as u64,
+    if !venv.is_current(&requirements) {
+        report(
+            EnvironmentState::Installing,
+            format!("Installing {}", describe_requirements(&requirements)),
         );
-        return Ok(());
+        let output = pip_install(ctx, workspace, &python, &requirements)
+            .await
+            .map_err(|e| SetupFailure::Error(format!("Failed to run pip: {}", e)))?;
+        if !output.status.success() {
+            return Err(SetupFailure::Command(output));
+        }
+        venv.mark_installed(&requirements)
+            .map_err(|e| SetupFailure::Error(e.to_string()))?;
     }
 
-    // Run
-    let mut child = Command::new("java")
-        .arg(&class_name)
-        .current_dir(&temp_dir)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .kill_on_drop(true)
-        .spawn()
-        .map_err(|e| format!("Failed to run Java: {}", e))?;
+    report(
+        EnvironmentState::Ready,
+        format!("Using virtual environment {}", venv.path().display()),
+    );
+    Ok(python)
+}
 
-    let stdout = child.stdout.take();
-    let stderr = child.stderr.take();
+/// How a Python run gets its interpreter
+#[derive(Debug, PartialEq)]
+enum PythonSetup {
+    /// No requirements: `python3`, or the executable it's overridden with
+    System(String),
+    Venv(Requirements),
+}
 
-    processes.insert(window_id.to_string(), child).await;
-    update_stop_menu_state(app, window_id, true);
+fn python_setup(
+    runtimes: &RuntimeOverrides,
+    files: &[ProjectFile],
+    entry: &str,
+    wheelhouse: Option<&str>,
+) -> Result<PythonSetup, PythonEnvError> {
+    let system = || PythonSetup::System(runtimes.program("python3").to_string_lossy().into_owned());
+    let Some(entry_file) = files.iter().find(|f| f.name == entry) else {
+        return Ok(system());
+    };
+    Ok(match find_requirements(files, entry_file, wheelhouse)? {
+        Some(requirements) => PythonSetup::Venv(requirements),
+        None => system(),
+    })
+}
 
-    spawn_output_streamer_with_dir(
-        stdout,
-        stderr,
-        window_id.to_string(),
-        app.clone(),
-        processes.inner().clone(),
-        temp_dir,
-    );
+async fn pip_install(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    python: &str,
+    requirements: &Requirements,
+) -> std::io::Result<Output> {
+    let mut pip = ctx.runtimes.command_with("python3", python);
+    pip.args(["-m", "pip", "install", "--disable-pip-version-check"]);
+    if let Some(wheelhouse) = &ctx.python.wheelhouse {
+        pip.arg("--no-index").arg("--find-links").arg(wheelhouse);
+    }
+    if let Some(file) = &requirements.file {
+        pip.arg("-r").arg(file);
+    }
+    pip.args(&requirements.inline);
 
-    Ok(())
+    // Run from the workspace so `-r`/`-e` paths in requirements files resolve
+    run_step(ctx, pip.current_dir(workspace.path())).await
 }
 
-// --- Output Streaming ---
+fn describe_requirements(requirements: &Requirements) -> String {
+    let mut parts = Vec::new();
+    if let Some(file) = &requirements.file {
+        parts.push(file.clone());
+    }
+    if !requirements.inline.is_empty() {
+        parts.push(requirements.inline.join(", "));
+    }
+    parts.join(", ")
+}
 
-fn spawn_output_streamer(
-    stdout: Option<tokio::process::ChildStdout>,
-    stderr: Option<tokio::process::ChildStderr>,
-    window_id: String,
-    app: AppHandle,
-    processes: RunningProcesses,
-    cleanup_files: Vec<PathBuf>,
-) {
-    let start = Instant::now();
+/// Report type errors when the project has the TypeScript compiler installed.
+///
+/// Types are erased without being checked, so this only adds diagnostics;
+/// the program runs regardless.
+async fn typecheck_typescript(ctx: &RunContext, workspace: &Workspace, files: &[ProjectFile]) {
+    let Some(tsc) = local_node_bin(workspace, "tsc") else {
+        return;
+    };
+
+    let mut cmd = Command::new(tsc);
+    cmd.args(["--noEmit", "--pretty", "false"]);
+    add_tsc_inputs(&mut cmd, files);
+
+    if let Ok(output) = run_step(ctx, cmd.current_dir(workspace.path())).await {
+        let diagnostics = parse_tsc(&String::from_utf8_lossy(&output.stdout));
+        emit_diagnostics(&ctx.app, &ctx.window_id, &diagnostics);
+    }
+}
 
-    tauri::async_runtime::spawn(async move {
-        let (stdout_output, stderr_output) = stream_outputs(stdout, stderr, &window_id, &app).await;
+/// The project's tsconfig, or else its TypeScript files, as tsc arguments
+fn add_tsc_inputs(cmd: &mut Command, files: &[ProjectFile]) {
+    if files.iter().any(|f| f.name == "tsconfig.json") {
+        cmd.arg("-p").arg(".");
+    } else {
+        cmd.args(source_files(files, &["ts", "tsx", "mts"]));
+    }
+}
 
-        let exit_code = wait_for_process(&processes, &window_id).await;
+/// An executable from the `node_modules` of the project's packages
+fn local_node_bin(workspace: &Workspace, name: &str) -> Option<PathBuf> {
+    let file_name = if cfg!(windows) {
+        format!("{name}.cmd")
+    } else {
+        name.to_string()
+    };
+    workspace
+        .path()
+        .ancestors()
+        .map(|dir| dir.join("node_modules").join(".bin").join(&file_name))
+        .find(|path| path.exists())
+}
 
-        // Cleanup files
-        for file in cleanup_files {
-            let _ = std::fs::remove_file(&file);
+/// Execute TypeScript on Node, with its types erased by Node itself.
+///
+/// Projects Node can't strip (JSX, syntax its stripper rejects, or a Node
+/// older than 22.13) run with the project's tsx or tsc instead, or with
+/// `npx tsx` as a last resort.
+async fn execute_typescript_on_node(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<(), String> {
+    let reason = match node_strips_types(ctx, &workspace, files).await {
+        Ok(()) => {
+            return execute_interpreted(ctx, workspace, entry_path, "node", NODE_FLAGS).await;
         }
+        Err(e) => e,
+    };
 
-        emit_completion(
-            &app,
-            &window_id,
-            &stdout_output,
-            &stderr_output,
-            exit_code,
-            start.elapsed().as_millis() as u64,
-        );
+    if let Some(tsx) = local_node_bin(&workspace, "tsx") {
+        let tsx = tsx.to_string_lossy().into_owned();
+        return execute_interpreted(ctx, workspace, entry_path, &tsx, &[]).await;
+    }
+    if let Some(tsc) = local_node_bin(&workspace, "tsc") {
+        return execute_tsc_output(ctx, workspace, &tsc, files, entry).await;
+    }
+    if ctx.runtimes.is_installed("npx") {
+        return execute_interpreted(ctx, workspace, entry_path, "npx", &["tsx"]).await;
+    }
+    workspace.remove();
+    let message = format!("{reason}. Add tsx to the project's dependencies to run it.");
+    emit_completion(&ctx.app, &ctx.window_id, "", &message, -1, 0);
+    Ok(())
+}
 
-        update_stop_menu_state(&app, &window_id, false);
-    });
+/// Whether Node can strip the types from every TypeScript file of the
+/// project, checked before any of it runs
+async fn node_strips_types(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+) -> Result<(), String> {
+    let args = check_args(files).map_err(|e| e.to_string())?;
+    let mut node = ctx.runtimes.command("node");
+    let output = run_step(ctx, node.args(args).current_dir(workspace.path()))
+        .await
+        .map_err(|e| format!("Failed to run node: {}", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    check_result(output.status.code(), &stdout).map_err(|e| e.to_string())
 }
 
-fn spawn_output_streamer_with_dir(
-    stdout: Option<tokio::process::ChildStdout>,
-    stderr: Option<tokio::process::ChildStderr>,
-    window_id: String,
-    app: AppHandle,
-    processes: RunningProcesses,
-    cleanup_dir: PathBuf,
+/// Compile the project with its tsc, then run the entry's output on Node.
+///
+/// Type errors were already reported by [`typecheck_typescript`]; tsc emits
+/// JavaScript in spite of them.
+async fn execute_tsc_output(
+    ctx: &RunContext,
+    workspace: Workspace,
+    tsc: &Path,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<(), String> {
+    const OUT_DIR: &str = "codecell_tsc";
+    let start = Instant::now();
+    let mut cmd = Command::new(tsc);
+    cmd.args([
+        "--outDir",
+        OUT_DIR,
+        "--rootDir",
+        ".",
+        "--sourceMap",
+        "--pretty",
+        "false",
+    ]);
+    if !files.iter().any(|f| f.name == "tsconfig.json") {
+        cmd.args([
+            "--module",
+            "nodenext",
+            "--target",
+            "es2022",
+            "--skipLibCheck",
+        ]);
+    }
+    add_tsc_inputs(&mut cmd, files);
+
+    let output = match run_step(ctx, cmd.current_dir(workspace.path())).await {
+        Ok(output) => output,
+        Err(e) => {
+            workspace.remove();
+            return Err(format!("Failed to run tsc: {}", e));
+        }
+    };
+    let script = workspace
+        .path()
+        .join(OUT_DIR)
+        .join(output_name(entry).unwrap_or_else(|| entry.to_string()));
+    if !script.exists() {
+        workspace.remove();
+        emit_completion(
+            &ctx.app,
+            &ctx.window_id,
+            "",
+            &String::from_utf8_lossy(&output.stdout),
+            output.status.code().unwrap_or(-1),
+            start.elapsed().as_millis() as u64,
+        );
+        return Ok(());
+    }
+
+    let source_maps = ["--enable-source-maps"];
+    execute_interpreted(ctx, workspace, &script, "node", &source_maps).await
+}
+
+/// Install the project's npm packages, if it declares any, and pick the
+/// directory its workspace should be created in.
+///
+/// Runs with packages happen inside the project's install directory so that
+/// `require`/`import` find its `node_modules`; everything else runs from the
+/// temp directory. Returns `Ok(None)` when the install failed; the error has
+/// already been reported to the window.
+async fn prepare_node_packages(
+    ctx: &RunContext,
+    files: &[ProjectFile],
+) -> Result<Option<PathBuf>, String> {
+    let start = Instant::now();
+    let report = |state, message| emit_environment(&ctx.app, &ctx.window_id, state, message);
+    let installed = install_node_packages(ctx, files, report).await;
+    report_setup(ctx, installed, start, "Failed to install npm packages")
+}
+
+/// The directory for the project's workspace, with its npm packages
+/// installed; `report` is told about each setup step
+async fn install_node_packages(
+    ctx: &RunContext,
+    files: &[ProjectFile],
+    report: impl Fn(EnvironmentState, String),
+) -> Result<PathBuf, SetupFailure> {
+    let manifest = match find_manifest(files, &ctx.node.dependencies) {
+        Ok(Some(manifest)) => manifest,
+        Ok(None) => return Ok(std::env::temp_dir()),
+        Err(e) => return Err(SetupFailure::Error(e.to_string())),
+    };
+    require_runtime(ctx, &RuntimeInfo::NPM).map_err(SetupFailure::Error)?;
+
+    let env = NodeEnv::new(&app_data_path(&ctx.app, "node-envs"), &ctx.project_id);
+
+    if !env.is_current(&manifest) {
+        report(
+            EnvironmentState::Installing,
+            "Installing npm packages".to_string(),
+        );
+        env.write_manifest(&manifest)
+            .map_err(|e| SetupFailure::Error(e.to_string()))?;
+
+        let mut npm = ctx.runtimes.command("npm");
+        npm.args(["install", "--no-audit", "--no-fund"])
+            .arg(if ctx.node.offline {
+                "--offline"
+            } else {
+                "--prefer-offline"
+            });
+        let output = run_step(ctx, npm.current_dir(env.path()))
+            .await
+            .map_err(|e| SetupFailure::Error(format!("Failed to run npm: {}", e)))?;
+        if !output.status.success() {
+            return Err(SetupFailure::Command(output));
+        }
+        env.mark_installed(&manifest)
+            .map_err(|e| SetupFailure::Error(e.to_string()))?;
+    }
+
+    report(
+        EnvironmentState::Ready,
+        format!("Using npm packages from {}", env.path().display()),
+    );
+    Ok(env.path().to_path_buf())
+}
+
+/// Report a failed environment setup step with the tool's output
+fn environment_failed<T>(
+    ctx: &RunContext,
+    output: &Output,
+    start: Instant,
+    message: &str,
+) -> Option<T> {
+    emit_environment(
+        &ctx.app,
+        &ctx.window_id,
+        EnvironmentState::Failed,
+        message.to_string(),
+    );
+    emit_completion(
+        &ctx.app,
+        &ctx.window_id,
+        &String::from_utf8_lossy(&output.stdout),
+        &String::from_utf8_lossy(&output.stderr),
+        output.status.code().unwrap_or(-1),
+        start.elapsed().as_millis() as u64,
+    );
+    None
+}
+
+/// Run a language from its definition's commands: compile (cached like the
+/// built-in compilers), then run
+async fn execute_commands(
+    ctx: &RunContext,
+    language: &LanguageDefinition,
+    workspace: Workspace,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<(), String> {
+    let Some(run) = &language.run else {
+        workspace.remove();
+        return Err(format!("{} has no run command", language.name));
+    };
+    if let Some(compile) = &language.compile {
+        let start = Instant::now();
+        let (program, args) = language.command_line(compile, workspace.path(), entry, files);
+        let mut compiler = ctx.runtimes.command(&program.to_string_lossy());
+        compiler.args(args);
+
+        let compile_output =
+            match compile_cached(ctx, &workspace, files, compiler, "--version", |ws| {
+                ws.generated_files(files)
+            })
+            .await
+            {
+                Ok(output) => output,
+                Err(e) => {
+                    workspace.remove();
+                    return Err(format!(
+                        "Failed to run {} compiler {}: {e}",
+                        language.name,
+                        program.display()
+                    ));
+                }
+            };
+
+        if !compile_output.status.success() {
+            workspace.remove();
+            emit_completion(
+                &ctx.app,
+                &ctx.window_id,
+                &String::from_utf8_lossy(&compile_output.stdout),
+                &String::from_utf8_lossy(&compile_output.stderr),
+                compile_output.status.code().unwrap_or(-1),
+                start.elapsed().as_millis() as u64,
+            );
+            return Ok(());
+        }
+    }
+
+    let (program, args) = language.command_line(run, workspace.path(), entry, files);
+    let mut cmd = ctx.runtimes.command(&program.to_string_lossy());
+    cmd.args(args);
+    spawn_program(ctx, cmd, &language.name, None, workspace).await
+}
+
+/// Execute an interpreted language (Python, Node, TypeScript and the scripting languages)
+async fn execute_interpreted(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    command: &str,
+    extra_args: &[&str],
+) -> Result<(), String> {
+    let cmd = ctx.runtimes.command(command);
+    execute_script(ctx, workspace, entry_path, cmd, command, extra_args).await
+}
+
+/// Run `entry_path` with an interpreter command that already has its
+/// default arguments; `command` is the runtime it runs, e.g. `python3`
+async fn execute_script(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    mut cmd: Command,
+    command: &str,
+    extra_args: &[&str],
+) -> Result<(), String> {
+    for arg in extra_args {
+        cmd.arg(arg);
+    }
+    cmd.arg(entry_path);
+
+    let installer = PackageInstaller::for_command(command, cmd.as_std().get_program());
+    spawn_program(ctx, cmd, command, installer, workspace).await
+}
+
+/// Execute Rust (compile then run).
+///
+/// Projects with a `Cargo.toml`, and snippets that embed a manifest, are
+/// built with Cargo; everything else is compiled with rustc directly.
+async fn execute_compiled_rust(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+) -> Result<(), String> {
+    match prepare_cargo_package(&workspace, entry_path) {
+        Ok(true) => return execute_cargo(ctx, workspace).await,
+        Ok(false) => {}
+        Err(e) => {
+            workspace.remove();
+            emit_completion(&ctx.app, &ctx.window_id, "", &e, -1, 0);
+            return Ok(());
+        }
+    }
+
+    let start = Instant::now();
+    let binary_path = workspace.path().join("codecell_bin");
+
+    // Compile; rustc finds `mod` files relative to the entry file
+    let mut rustc = ctx.runtimes.command("rustc");
+    rustc
+        .arg(
+            entry_path
+                .strip_prefix(workspace.path())
+                .unwrap_or(entry_path),
+        )
+        .arg("-o")
+        .arg("codecell_bin")
+        .arg("--error-format=json");
+    let compile_output = match compile_cached(ctx, &workspace, files, rustc, "-vV", |_| {
+        vec![binary_path.clone()]
+    })
+    .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            workspace.remove();
+            return Err(format!("Failed to compile Rust: {}", e));
+        }
+    };
+
+    let parsed = parse_rustc_json(&String::from_utf8_lossy(&compile_output.stderr));
+    emit_diagnostics(&ctx.app, &ctx.window_id, &parsed.diagnostics);
+
+    if !compile_output.status.success() {
+        workspace.remove();
+        emit_completion(
+            &ctx.app,
+            &ctx.window_id,
+            "",
+            &parsed.rendered,
+            compile_output.status.code().unwrap_or(-1),
+            start.elapsed().as_millis() as u64,
+        );
+        return Ok(());
+    }
+
+    // Run
+    spawn_program(
+        ctx,
+        Command::new(&binary_path),
+        "Rust binary",
+        None,
+        workspace,
+    )
+    .await
+}
+
+/// Make the workspace a Cargo package when the entry file embeds a manifest.
+/// Returns whether the workspace is a Cargo package, either way.
+fn prepare_cargo_package(workspace: &Workspace, entry_path: &Path) -> Result<bool, String> {
+    if workspace.path().join("Cargo.toml").exists() {
+        return Ok(true);
+    }
+
+    let source = std::fs::read_to_string(entry_path).unwrap_or_default();
+    let Some(embedded) = extract_manifest(&source) else {
+        return Ok(false);
+    };
+    let bin_path = entry_path
+        .strip_prefix(workspace.path())
+        .unwrap_or(entry_path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let manifest = generate_manifest(&embedded.manifest, &bin_path).map_err(|e| e.to_string())?;
+    std::fs::write(workspace.path().join("Cargo.toml"), manifest)
+        .and_then(|()| std::fs::write(entry_path, embedded.source))
+        .map_err(|e| format!("Failed to write Cargo manifest: {}", e))?;
+    Ok(true)
+}
+
+/// Build the workspace's Cargo package, then run its binary
+async fn execute_cargo(ctx: &RunContext, workspace: Workspace) -> Result<(), String> {
+    if !check_runtime_available(ctx, &RuntimeInfo::CARGO) {
+        workspace.remove();
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let build_output = match run_step(
+        ctx,
+        cargo_command(ctx, "build").current_dir(workspace.path()),
+    )
+    .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            workspace.remove();
+            return Err(format!("Failed to run cargo: {}", e));
+        }
+    };
+
+    let build = parse_build_output(&String::from_utf8_lossy(&build_output.stdout));
+    emit_diagnostics(&ctx.app, &ctx.window_id, &build.diagnostics);
+    let executable = match build.executable {
+        Some(executable) if build_output.status.success() => executable,
+        _ => {
+            workspace.remove();
+            // Compiler messages come as JSON on stdout; cargo's own errors
+            // (bad manifest, unresolved dependency) on stderr
+            let stderr = format!(
+                "{}{}",
+                build.rendered,
+                String::from_utf8_lossy(&build_output.stderr)
+            );
+            emit_completion(
+                &ctx.app,
+                &ctx.window_id,
+                "",
+                &stderr,
+                build_output.status.code().unwrap_or(-1),
+                start.elapsed().as_millis() as u64,
+            );
+            return Ok(());
+        }
+    };
+
+    // Run a private copy: the target directory is shared with other windows,
+    // whose builds could replace the binary mid-run
+    let binary_path = workspace
+        .path()
+        .join(format!("codecell_bin{}", std::env::consts::EXE_SUFFIX));
+    if let Err(e) = std::fs::copy(&executable, &binary_path) {
+        workspace.remove();
+        return Err(format!("Failed to copy Cargo binary: {}", e));
+    }
+
+    spawn_program(
+        ctx,
+        Command::new(&binary_path),
+        "Cargo binary",
+        None,
+        workspace,
+    )
+    .await
+}
+
+/// `cargo <subcommand>` reporting JSON messages, with the shared target
+/// directory and the project's Cargo options
+fn cargo_command(ctx: &RunContext, subcommand: &str) -> Command {
+    let mut cargo = ctx.runtimes.command("cargo");
+    cargo
+        .args([subcommand, "--quiet", "--message-format=json"])
+        .arg("--target-dir")
+        .arg(cargo_target_dir(&ctx.app));
+    if ctx.cargo.offline {
+        cargo.arg("--offline");
+    }
+    cargo
+}
+
+/// Target directory shared by every Cargo run, so dependencies build only once
+fn cargo_target_dir(app: &AppHandle) -> PathBuf {
+    app_cache_path(app, "cargo-target")
+}
+
+fn app_cache_path(app: &AppHandle, name: &str) -> PathBuf {
+    app.path()
+        .app_cache_dir()
+        .unwrap_or_else(|_| std::env::temp_dir())
+        .join(name)
+}
+
+pub(crate) fn app_data_path(app: &AppHandle, name: &str) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| std::env::temp_dir())
+        .join(name)
+}
+
+/// Run a compiler inside the workspace, unless the compile cache already
+/// holds its artifacts for these sources, this compiler version and these
+/// arguments.
+///
+/// On a cache hit the compiler's original stderr is returned with a success
+/// status, so warnings are reported the same either way. After a successful
+/// compile the files picked by `artifacts` are stored in the cache, so
+/// arguments must be relative to the workspace for later runs to produce the
+/// same key.
+async fn compile_cached(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    mut compiler: Command,
+    version_arg: &str,
+    artifacts: impl FnOnce(&Workspace) -> Vec<PathBuf>,
+) -> std::io::Result<Output> {
+    let cache = CompileCache::new(
+        app_cache_path(&ctx.app, "compile-cache"),
+        compile_cache::DEFAULT_MAX_BYTES,
+    );
+    let key = compile_cache_key(ctx, &compiler, version_arg, files).await;
+    if let Some(key) = &key {
+        if let Ok(Some(stderr)) = cache.restore(key, workspace.path()) {
+            return Ok(Output {
+                status: ExitStatus::default(),
+                stdout: Vec::new(),
+                stderr,
+            });
+        }
+    }
+
+    let output = run_step(ctx, compiler.current_dir(workspace.path())).await?;
+
+    if let Some(key) = key.filter(|_| output.status.success()) {
+        // A failed store only costs a recompile next time
+        let _ = cache.store(
+            &key,
+            workspace.path(),
+            &artifacts(workspace),
+            &output.stderr,
+        );
+    }
+    Ok(output)
+}
+
+/// Hash the compiler version, its arguments and every project file.
+///
+/// Returns `None` when the compiler version can't be determined, which
+/// disables caching for the run.
+async fn compile_cache_key(
+    ctx: &RunContext,
+    compiler: &Command,
+    version_arg: &str,
+    files: &[ProjectFile],
+) -> Option<String> {
+    let compiler = compiler.as_std();
+    let envs: Vec<_> = compiler
+        .get_envs()
+        .filter_map(|(name, value)| Some((name, value?)))
+        .collect();
+    let version = compiler_version(ctx, compiler, version_arg, &envs).await?;
+
+    let mut key = CacheKey::new(&compiler.get_program().to_string_lossy());
+    key.add(&version);
+    for arg in compiler.get_args() {
+        key.add(arg.to_string_lossy().as_bytes());
+    }
+    for (name, value) in envs {
+        key.add(name.to_string_lossy().as_bytes())
+            .add(value.to_string_lossy().as_bytes());
+    }
+    for file in files {
+        key.add(file.name.as_bytes()).add(file.content.as_bytes());
+    }
+    Some(key.finish())
+}
+
+/// The compiler's version output, asked once per session for each compiler
+/// binary
+async fn compiler_version(
+    ctx: &RunContext,
+    compiler: &std::process::Command,
+    version_arg: &str,
+    envs: &[(&OsStr, &OsStr)],
+) -> Option<Vec<u8>> {
+    let path_var = envs
+        .iter()
+        .find(|(name, _)| *name == "PATH")
+        .map(|(_, value)| value.to_os_string())
+        .or_else(|| std::env::var_os("PATH"));
+    let parts = std::iter::once(version_arg.as_bytes()).chain(
+        envs.iter()
+            .flat_map(|(name, value)| [name.as_encoded_bytes(), value.as_encoded_bytes()]),
+    );
+    let key = compile_cache::version_key(
+        Path::new(compiler.get_program()),
+        path_var.as_deref(),
+        parts,
+    );
+    if let Some(version) = key.as_deref().and_then(compile_cache::cached_version) {
+        return Some(version);
+    }
+
+    let output = run_step(
+        ctx,
+        Command::new(compiler.get_program())
+            .arg(version_arg)
+            .envs(envs.iter().copied()),
+    )
+    .await
+    .ok()
+    .filter(|o| o.status.success())?;
+    // Older javac versions print their version on stderr
+    let mut version = output.stdout;
+    version.extend(output.stderr);
+    if let Some(key) = key {
+        compile_cache::remember_version(key, version.clone());
+    }
+    Some(version)
+}
+
+/// Execute Java (compile then run)
+async fn execute_compiled_java(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+) -> Result<(), String> {
+    let entry_code = std::fs::read_to_string(entry_path).unwrap_or_default();
+    let class_name = java_main_class(&entry_code, entry_path);
+
+    // Compile every source file so the entry class can use the others
+    let mut javac = ctx.runtimes.command("javac");
+    javac
+        .arg("-d")
+        .arg(".")
+        .args(source_files(files, &["java"]));
+
+    let mut java = ctx.runtimes.command("java");
+    java.arg("-cp").arg(workspace.path()).arg(&class_name);
+
+    let toolchain = JvmToolchain {
+        name: "Java",
+        compiler: javac,
+        parse_diagnostics: parse_javac,
+        launcher: java,
+    };
+    execute_jvm(ctx, workspace, files, toolchain).await
+}
+
+/// Execute Kotlin: `.kts` entries run as scripts, anything else is compiled
+/// and its `main` launched
+async fn execute_compiled_kotlin(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+) -> Result<(), String> {
+    if entry_path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("kts"))
+    {
+        return execute_interpreted(ctx, workspace, entry_path, "kotlinc", &["-script"]).await;
+    }
+
+    let entry_code = std::fs::read_to_string(entry_path).unwrap_or_default();
+    let class_name = kotlin_main_class(&entry_code, entry_path);
+
+    let mut kotlinc = ctx.runtimes.command("kotlinc");
+    kotlinc
+        .args(source_files(files, &["kt"]))
+        .arg("-d")
+        .arg(".");
+
+    let mut kotlin = ctx.runtimes.command("kotlin");
+    kotlin.arg("-cp").arg(workspace.path()).arg(&class_name);
+
+    // kotlinc reports diagnostics in GCC's format
+    let toolchain = JvmToolchain {
+        name: "Kotlin",
+        compiler: kotlinc,
+        parse_diagnostics: parse_gcc,
+        launcher: kotlin,
+    };
+    execute_jvm(ctx, workspace, files, toolchain).await
+}
+
+/// Execute Scala with Scala CLI, or with scalac when that's all there is
+async fn execute_compiled_scala(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+) -> Result<(), String> {
+    let sources: Vec<&str> = source_files(files, &["scala", "sc"]).collect();
+
+    if ctx.runtimes.is_installed(&RuntimeInfo::SCALA_CLI.command) {
+        // Scala CLI finds the main class (or runs `.sc` scripts) itself; `--`
+        // keeps program arguments from being read as more sources
+        let mut cmd = ctx.runtimes.command("scala-cli");
+        cmd.args(["run", "--quiet"]).args(&sources).arg("--");
+        return spawn_program(ctx, cmd, "Scala", None, workspace).await;
+    }
+    if !ctx.runtimes.is_installed(&RuntimeInfo::SCALAC.command) {
+        check_runtime_available(ctx, &RuntimeInfo::SCALA_CLI);
+        workspace.remove();
+        return Ok(());
+    }
+
+    let entry_code = std::fs::read_to_string(entry_path).unwrap_or_default();
+    let class_name = scala_main_class(&entry_code);
+
+    let mut scalac = ctx.runtimes.command("scalac");
+    scalac.arg("-d").arg(".").args(&sources);
+
+    let mut scala = ctx.runtimes.command("scala");
+    scala
+        .arg("-classpath")
+        .arg(workspace.path())
+        .arg(&class_name);
+
+    let toolchain = JvmToolchain {
+        name: "Scala",
+        compiler: scalac,
+        parse_diagnostics: |_| Vec::new(),
+        launcher: scala,
+    };
+    execute_jvm(ctx, workspace, files, toolchain).await
+}
+
+/// A JVM language's compiler, which writes classes into the workspace, and
+/// the launcher for the compiled main class
+struct JvmToolchain {
+    name: &'static str,
+    compiler: Command,
+    parse_diagnostics: fn(&str) -> Vec<Diagnostic>,
+    launcher: Command,
+}
+
+/// Compile with a JVM toolchain, then run the main class
+async fn execute_jvm(
+    ctx: &RunContext,
+    workspace: Workspace,
+    files: &[ProjectFile],
+    toolchain: JvmToolchain,
+) -> Result<(), String> {
+    let start = Instant::now();
+    let JvmToolchain {
+        name,
+        compiler,
+        parse_diagnostics,
+        launcher,
+    } = toolchain;
+
+    let compile_output = match compile_cached(ctx, &workspace, files, compiler, "-version", |ws| {
+        ws.generated_files(files)
+    })
+    .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            workspace.remove();
+            return Err(format!("Failed to compile {}: {}", name, e));
+        }
+    };
+
+    let stderr = String::from_utf8_lossy(&compile_output.stderr);
+    emit_diagnostics(&ctx.app, &ctx.window_id, &parse_diagnostics(&stderr));
+
+    if !compile_output.status.success() {
+        workspace.remove();
+        emit_completion(
+            &ctx.app,
+            &ctx.window_id,
+            "",
+            &stderr,
+            compile_output.status.code().unwrap_or(-1),
+            start.elapsed().as_millis() as u64,
+        );
+        return Ok(());
+    }
+
+    // Run
+    spawn_program(ctx, launcher, name, None, workspace).await
+}
+
+/// Names of the project files with one of `extensions`
+fn source_files<'a>(
+    files: &'a [ProjectFile],
+    extensions: &'a [&str],
+) -> impl Iterator<Item = &'a str> {
+    files
+        .iter()
+        .filter(|f| {
+            Path::new(&f.name)
+                .extension()
+                .is_some_and(|ext| extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)))
+        })
+        .map(|f| f.name.as_str())
+}
+
+/// Execute Go (compile then run).
+///
+/// Building and running separately is what `go run` does, but keeps compiler
+/// errors out of the program's output and resource limits off the compiler.
+/// Every build shares one `GOCACHE`, and `-mod=mod` lets imports of
+/// third-party modules resolve without a `go get`.
+async fn execute_compiled_go(
+    ctx: &RunContext,
+    workspace: Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+) -> Result<(), String> {
+    let start = Instant::now();
+    let module = match prepare_go_module(ctx, &workspace, files).await {
+        Ok(GoModuleSetup::Ready(module)) => module,
+        Ok(GoModuleSetup::InitFailed(output)) => {
+            workspace.remove();
+            emit_completion(
+                &ctx.app,
+                &ctx.window_id,
+                "",
+                &String::from_utf8_lossy(&output.stderr),
+                output.status.code().unwrap_or(-1),
+                start.elapsed().as_millis() as u64,
+            );
+            return Ok(());
+        }
+        Err(e) => {
+            workspace.remove();
+            return Err(e);
+        }
+    };
+
+    let binary_path = workspace
+        .path()
+        .join(format!("codecell_bin{}", std::env::consts::EXE_SUFFIX));
+    let build_output = match go_build(ctx, &workspace, entry_path, &binary_path).await {
+        Ok(output) => output,
+        Err(e) => {
+            workspace.remove();
+            return Err(format!("Failed to compile Go: {}", e));
+        }
+    };
+
+    let stderr = String::from_utf8_lossy(&build_output.stderr);
+    emit_diagnostics(&ctx.app, &ctx.window_id, &parse_go(&stderr));
+
+    if !build_output.status.success() {
+        workspace.remove();
+        emit_completion(
+            &ctx.app,
+            &ctx.window_id,
+            "",
+            &stderr,
+            build_output.status.code().unwrap_or(-1),
+            start.elapsed().as_millis() as u64,
+        );
+        return Ok(());
+    }
+
+    if let Some(module) = module {
+        // A failed save only means resolving the imports again next run
+        let _ = module.save(workspace.path());
+    }
+
+    // Run
+    spawn_program(
+        ctx,
+        Command::new(&binary_path),
+        "Go binary",
+        None,
+        workspace,
+    )
+    .await
+}
+
+/// Outcome of putting a Go workspace's go.mod in place
+enum GoModuleSetup {
+    /// The module to save after a successful build, when it was generated
+    Ready(Option<GoModule>),
+    InitFailed(Output),
+}
+
+/// Restore the go.mod saved for the project, or create one with `go mod
+/// init`. Projects that ship a go.mod manage it themselves.
+async fn prepare_go_module(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+) -> Result<GoModuleSetup, String> {
+    if files.iter().any(|f| f.name == GO_MOD) {
+        return Ok(GoModuleSetup::Ready(None));
+    }
+
+    let module = GoModule::new(&app_data_path(&ctx.app, "go-modules"), &ctx.project_id);
+    let restored = module
+        .restore(workspace.path())
+        .map_err(|e| format!("Failed to restore Go module: {}", e))?;
+    if !restored {
+        let output = run_step(
+            ctx,
+            ctx.runtimes
+                .command("go")
+                .args(["mod", "init", MODULE_PATH])
+                .env("GOCACHE", app_cache_path(&ctx.app, "go-build"))
+                .current_dir(workspace.path()),
+        )
+        .await
+        .map_err(|e| format!("Failed to run go: {}", e))?;
+        if !output.status.success() {
+            return Ok(GoModuleSetup::InitFailed(output));
+        }
+    }
+    Ok(GoModuleSetup::Ready(Some(module)))
+}
+
+/// Build the entry file's package, which includes its sibling files
+async fn go_build(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    entry_path: &Path,
+    binary_path: &Path,
+) -> std::io::Result<Output> {
+    let package = match entry_path
+        .parent()
+        .and_then(|dir| dir.strip_prefix(workspace.path()).ok())
+    {
+        Some(dir) if !dir.as_os_str().is_empty() => format!("./{}", dir.display()),
+        _ => ".".to_string(),
+    };
+    run_step(
+        ctx,
+        ctx.runtimes
+            .command("go")
+            .args(["build", "-mod=mod", "-o"])
+            .arg(binary_path)
+            .arg(&package)
+            .env("GOCACHE", app_cache_path(&ctx.app, "go-build"))
+            .current_dir(workspace.path()),
+    )
+    .await
+}
+
+/// Execute C or C++ (compile every source file, then run)
+async fn execute_compiled_cc(
+    ctx: &RunContext,
+    workspace: Workspace,
+    files: &[ProjectFile],
+    language: CcLanguage,
+) -> Result<(), String> {
+    let compiler = select_compiler(ctx.cc.compiler, |compiler| {
+        ctx.runtimes
+            .is_installed(&language.runtime(compiler).command)
+    });
+    let runtime = language.runtime(compiler);
+    if !check_runtime_available(ctx, &runtime) {
+        workspace.remove();
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let binary_name = format!("codecell_bin{}", std::env::consts::EXE_SUFFIX);
+    let binary_path = workspace.path().join(&binary_name);
+    let args = match compiler_args(language, &ctx.cc, files, &binary_name) {
+        Ok(args) => args,
+        Err(e) => {
+            workspace.remove();
+            return Err(e.to_string());
+        }
+    };
+    let mut cc = ctx.runtimes.command(&runtime.command);
+    cc.args(args);
+
+    let compile_output = match compile_cached(ctx, &workspace, files, cc, "--version", |_| {
+        vec![binary_path.clone()]
+    })
+    .await
+    {
+        Ok(output) => output,
+        Err(e) => {
+            workspace.remove();
+            return Err(format!("Failed to compile with {}: {}", runtime.command, e));
+        }
+    };
+
+    let stderr = String::from_utf8_lossy(&compile_output.stderr);
+    emit_diagnostics(&ctx.app, &ctx.window_id, &parse_gcc(&stderr));
+
+    if !compile_output.status.success() {
+        workspace.remove();
+        emit_completion(
+            &ctx.app,
+            &ctx.window_id,
+            "",
+            &stderr,
+            compile_output.status.code().unwrap_or(-1),
+            start.elapsed().as_millis() as u64,
+        );
+        return Ok(());
+    }
+
+    // Run
+    spawn_program(
+        ctx,
+        Command::new(&binary_path),
+        &runtime.name,
+        None,
+        workspace,
+    )
+    .await
+}
+
+/// Why a run or check didn't start while the window's last one is going
+const ALREADY_RUNNING: &str = "Another program is running in this window; stop it first";
+
+/// Spawn a prepared command inside the workspace, register it for the window
+/// and stream its output. `installer` suggests a package when the program
+/// fails on a missing import.
+async fn spawn_program(
+    ctx: &RunContext,
+    mut cmd: Command,
+    name: &str,
+    installer: Option<PackageInstaller>,
+    workspace: Workspace,
+) -> Result<(), String> {
+    apply_rlimits(&mut cmd, &ctx.limits);
+    process_group::isolate(&mut cmd);
+    cmd.current_dir(workspace.path());
+    if let Err(e) = apply_run_config(&mut cmd, &ctx.run, &workspace) {
+        workspace.remove();
+        return Err(e.to_string());
+    }
+
+    let mut child = match cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            workspace.remove();
+            return Err(format!("Failed to execute {}: {}", name, e));
+        }
+    };
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let pid = child.id();
+    if !ctx.processes.insert(ctx.window_id.clone(), child).await {
+        workspace.remove();
+        return Err(ALREADY_RUNNING.to_string());
+    }
+    update_stop_menu_state(&ctx.app, &ctx.window_id, true);
+
+    if let Some(pid) = pid {
+        spawn_input_watcher(
+            pid,
+            ctx.window_id.clone(),
+            ctx.app.clone(),
+            ctx.processes.clone(),
+        );
+    }
+
+    spawn_output_streamer(stdout, stderr, ctx, workspace, installer);
+
+    Ok(())
+}
+
+/// Run a step that prepares the program (an install, a compile) to
+/// completion and collect its output.
+///
+/// Steps get the run's timeout but not the program's CPU and memory caps
+/// (see [`step_limits`]), and are the window's process while they last, so
+/// Stop ends them the way it ends the program.
+async fn run_step(ctx: &RunContext, cmd: &mut Command) -> std::io::Result<Output> {
+    let limits = step_limits(&ctx.limits);
+    apply_rlimits(cmd, &limits);
+    process_group::isolate(cmd);
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    if !ctx.processes.insert(ctx.window_id.clone(), child).await {
+        return Err(std::io::Error::other(ALREADY_RUNNING));
+    }
+
+    let finished = async {
+        let (stdout, stderr) = tokio::join!(read_to_end(stdout), read_to_end(stderr));
+        let status = wait_for_process(&ctx.processes, &ctx.window_id).await;
+        (status, stdout, stderr)
+    };
+    let (status, stdout, stderr) = match limits.timeout_ms {
+        Some(ms) => {
+            let Ok(finished) = tokio::time::timeout(Duration::from_millis(ms), finished).await
+            else {
+                ctx.processes.kill(&ctx.window_id).await;
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("exceeded the {ms} ms time limit"),
+                ));
+            };
+            finished
+        }
+        None => finished.await,
+    };
+    // Without a status the step was stopped and its process reaped by Stop
+    let status =
+        status.ok_or_else(|| std::io::Error::new(std::io::ErrorKind::Interrupted, "stopped"))?;
+    Ok(Output {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+async fn read_to_end<R: AsyncRead + Unpin>(reader: Option<R>) -> Vec<u8> {
+    let mut buffer = Vec::new();
+    if let Some(mut reader) = reader {
+        let _ = reader.read_to_end(&mut buffer).await;
+    }
+    buffer
+}
+
+// --- Check Mode ---
+
+/// Write the files into a new workspace and check them with the language's
+/// toolchain. Nothing from the project is run.
+async fn check_files(
+    ctx: &RunContext,
+    language: &LanguageDefinition,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<CheckResult, String> {
+    let start = Instant::now();
+    for runtime in &language.runtimes {
+        require_runtime(ctx, runtime)?;
+    }
+
+    // tsc resolves the types of the project's npm packages
+    let workspace_parent = match &language.runner {
+        LanguageRunner::Template(TemplateType::Typescript) => {
+            match install_node_packages(ctx, files, |_, _| {}).await {
+                Ok(dir) => dir,
+                Err(failure) => return setup_checked(failure),
+            }
+        }
+        _ => std::env::temp_dir(),
+    };
+    let workspace =
+        Workspace::create_in(&workspace_parent, &ctx.window_id).map_err(|e| e.to_string())?;
+    let checked = match workspace
+        .write_files(files)
+        .and_then(|()| workspace.resolve(entry))
+    {
+        Ok(entry_path) => {
+            check_workspace(ctx, language, &workspace, files, entry, &entry_path).await
+        }
+        Err(e) => Err(e.to_string()),
+    };
+    workspace.remove();
+
+    let mut result = checked?;
+    result.duration_ms = start.elapsed().as_millis() as u64;
+    Ok(result)
+}
+
+async fn check_workspace(
+    ctx: &RunContext,
+    language: &LanguageDefinition,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    entry: &str,
+    entry_path: &Path,
+) -> Result<CheckResult, String> {
+    let LanguageRunner::Template(template) = &language.runner else {
+        return check_command(ctx, language, workspace, files, entry).await;
+    };
+    match template {
+        TemplateType::Python => check_python(ctx, workspace, files, entry).await,
+        TemplateType::Typescript => check_typescript(ctx, workspace, files).await,
+        TemplateType::Rust => check_rust(ctx, workspace, entry_path).await,
+        TemplateType::Java => {
+            let mut javac = ctx.runtimes.command("javac");
+            javac
+                .arg("-d")
+                .arg(".")
+                .args(source_files(files, &["java"]));
+            run_checker(ctx, javac, workspace, parse_javac).await
+        }
+        TemplateType::Kotlin => {
+            if entry_path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("kts"))
+            {
+                return Err("Kotlin scripts can only be checked by running them".to_string());
+            }
+            let mut kotlinc = ctx.runtimes.command("kotlinc");
+            kotlinc
+                .args(source_files(files, &["kt"]))
+                .arg("-d")
+                .arg(".");
+            run_checker(ctx, kotlinc, workspace, parse_gcc).await
+        }
+        TemplateType::Scala => {
+            let sources: Vec<&str> = source_files(files, &["scala", "sc"]).collect();
+            let mut compiler = if ctx.runtimes.is_installed(&RuntimeInfo::SCALA_CLI.command) {
+                let mut cmd = ctx.runtimes.command("scala-cli");
+                cmd.args(["compile", "--quiet"]);
+                cmd
+            } else if ctx.runtimes.is_installed(&RuntimeInfo::SCALAC.command) {
+                let mut cmd = ctx.runtimes.command("scalac");
+                cmd.arg("-d").arg(".");
+                cmd
+            } else {
+                // Reported as Scala CLI missing, as runs do
+                let hint = check_runtime(&RuntimeInfo::SCALA_CLI, &ctx.runtimes).install_hint;
+                return Err(hint.unwrap_or_default());
+            };
+            compiler.args(&sources);
+            run_checker(ctx, compiler, workspace, parse_scala).await
+        }
+        TemplateType::Go => check_go(ctx, workspace, entry_path, files).await,
+        TemplateType::C => check_cc(ctx, workspace, files, CcLanguage::C).await,
+        TemplateType::Cpp => check_cc(ctx, workspace, files, CcLanguage::Cpp).await,
+        TemplateType::Web => Err("Web projects run in the preview pane".to_string()),
+        // Interpreters' syntax checks need nothing beyond the command
+        TemplateType::Node
+        | TemplateType::Ruby
+        | TemplateType::Php
+        | TemplateType::Perl
+        | TemplateType::Lua
+        | TemplateType::Bash
+        | TemplateType::Groovy => check_command(ctx, language, workspace, files, entry).await,
+    }
+}
+
+/// Check with the language's `check` command, or its `compile` command when
+/// it has none. Diagnostics are read in GCC's format, which many compilers
+/// and linters share.
+async fn check_command(
+    ctx: &RunContext,
+    language: &LanguageDefinition,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<CheckResult, String> {
+    let command = language
+        .check
+        .as_ref()
+        .or(language.compile.as_ref())
+        .ok_or_else(|| format!("{} can only be checked by running it", language.name))?;
+    let (program, args) = language.command_line(command, workspace.path(), entry, files);
+    let program = program.to_string_lossy();
+    if !ctx.runtimes.is_installed(&program) {
+        return Err(format!("Error: {} is not installed\n", program));
+    }
+
+    let mut checker = ctx.runtimes.command(&program);
+    checker.args(args);
+    run_checker(ctx, checker, workspace, parse_gcc).await
+}
+
+/// Check Python files for syntax errors with `py_compile`, then for type
+/// errors with mypy when the interpreter (or the project's venv) has it
+async fn check_python(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    entry: &str,
+) -> Result<CheckResult, String> {
+    // Setup progress is a run's business; a check only reports its result
+    let python = match prepare_python(ctx, workspace, files, entry, |_, _| {}).await {
+        Ok(python) => python,
+        Err(failure) => return setup_checked(failure),
+    };
+    let sources: Vec<&str> = source_files(files, &["py"]).collect();
+
+    let mut py_compile = ctx.runtimes.command_with("python3", &python);
+    py_compile.args(["-m", "py_compile"]).args(&sources);
+    let compiled = run_checker(ctx, py_compile, workspace, |output| {
+        parse_python(output, files)
+    })
+    .await?;
+    if !compiled.success {
+        return Ok(compiled);
+    }
+
+    let mut mypy_version = ctx.runtimes.command_with("python3", &python);
+    let has_mypy = run_step(ctx, mypy_version.args(["-m", "mypy", "--version"]))
+        .await
+        .is_ok_and(|output| output.status.success());
+    if !has_mypy {
+        return Ok(compiled);
+    }
+
+    let mut mypy = ctx.runtimes.command_with("python3", &python);
+    mypy.args([
+        "-m",
+        "mypy",
+        "--no-error-summary",
+        "--show-column-numbers",
+        "--no-color-output",
+    ])
+    .arg("--cache-dir")
+    .arg(app_cache_path(&ctx.app, "mypy"))
+    .args(&sources);
+    run_checker(ctx, mypy, workspace, parse_mypy).await
+}
+
+/// Type-check TypeScript with the project's tsc, or else one on the PATH
+async fn check_typescript(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+) -> Result<CheckResult, String> {
+    let mut tsc = match local_node_bin(workspace, "tsc") {
+        Some(tsc) => Command::new(tsc),
+        None if ctx.runtimes.is_installed("tsc") => ctx.runtimes.command("tsc"),
+        None => {
+            return Err(
+                "Checking TypeScript needs tsc. Add typescript to the project's dependencies."
+                    .to_string(),
+            )
+        }
+    };
+    tsc.args(["--noEmit", "--pretty", "false"]);
+    add_tsc_inputs(&mut tsc, files);
+    run_checker(ctx, tsc, workspace, parse_tsc).await
+}
+
+/// Check Rust with `cargo check` for Cargo packages, or else with rustc
+/// stopping once the crate is analyzed
+async fn check_rust(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    entry_path: &Path,
+) -> Result<CheckResult, String> {
+    if prepare_cargo_package(workspace, entry_path)? {
+        require_runtime(ctx, &RuntimeInfo::CARGO)?;
+        let output = run_step(
+            ctx,
+            cargo_command(ctx, "check").current_dir(workspace.path()),
+        )
+        .await
+        .map_err(|e| format!("Failed to run cargo: {}", e))?;
+        let build = parse_build_output(&String::from_utf8_lossy(&output.stdout));
+        return Ok(CheckResult {
+            success: output.status.success(),
+            diagnostics: build.diagnostics,
+            output: format!(
+                "{}{}",
+                build.rendered,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            duration_ms: 0,
+        });
+    }
+
+    let mut rustc = ctx.runtimes.command("rustc");
+    rustc
+        .arg(
+            entry_path
+                .strip_prefix(workspace.path())
+                .unwrap_or(entry_path),
+        )
+        .args(["--emit=metadata", "--error-format=json", "-o"])
+        .arg("codecell_check.rmeta");
+    let output = run_step(ctx, rustc.current_dir(workspace.path()))
+        .await
+        .map_err(|e| format!("Failed to run rustc: {}", e))?;
+    let parsed = parse_rustc_json(&String::from_utf8_lossy(&output.stderr));
+    Ok(CheckResult {
+        success: output.status.success(),
+        diagnostics: parsed.diagnostics,
+        output: parsed.rendered,
+        duration_ms: 0,
+    })
+}
+
+/// Check Go by building the entry's package; the binary is thrown away
+async fn check_go(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+) -> Result<CheckResult, String> {
+    let module = match prepare_go_module(ctx, workspace, files).await? {
+        GoModuleSetup::Ready(module) => module,
+        GoModuleSetup::InitFailed(output) => return Ok(checked(&output, |_| Vec::new())),
+    };
+
+    let binary_path = workspace
+        .path()
+        .join(format!("codecell_bin{}", std::env::consts::EXE_SUFFIX));
+    let output = go_build(ctx, workspace, entry_path, &binary_path)
+        .await
+        .map_err(|e| format!("Failed to compile Go: {}", e))?;
+    if let Some(module) = module.filter(|_| output.status.success()) {
+        let _ = module.save(workspace.path());
+    }
+    Ok(checked(&output, parse_go))
+}
+
+/// Check C or C++ with the compiler a run would pick, stopping after syntax
+/// and semantic analysis
+async fn check_cc(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    language: CcLanguage,
+) -> Result<CheckResult, String> {
+    let compiler = select_compiler(ctx.cc.compiler, |compiler| {
+        ctx.runtimes
+            .is_installed(&language.runtime(compiler).command)
+    });
+    let runtime = language.runtime(compiler);
+    require_runtime(ctx, &runtime)?;
+
+    let binary_name = format!("codecell_bin{}", std::env::consts::EXE_SUFFIX);
+    let args = compiler_args(language, &ctx.cc, files, &binary_name).map_err(|e| e.to_string())?;
+    let mut cc = ctx.runtimes.command(&runtime.command);
+    cc.arg("-fsyntax-only").args(args);
+    run_checker(ctx, cc, workspace, parse_gcc).await
+}
+
+/// Run a checker in the workspace with no input
+async fn run_checker(
+    ctx: &RunContext,
+    mut checker: Command,
+    workspace: &Workspace,
+    parse_diagnostics: impl FnOnce(&str) -> Vec<Diagnostic>,
+) -> Result<CheckResult, String> {
+    let program = checker
+        .as_std()
+        .get_program()
+        .to_string_lossy()
+        .into_owned();
+    let output = run_step(ctx, checker.current_dir(workspace.path()))
+        .await
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    Ok(checked(&output, parse_diagnostics))
+}
+
+/// A check's result from its tool's output; diagnostics are parsed from
+/// stdout and stderr together
+fn checked(
+    output: &Output,
+    parse_diagnostics: impl FnOnce(&str) -> Vec<Diagnostic>,
+) -> CheckResult {
+    let text = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    CheckResult {
+        success: output.status.success(),
+        diagnostics: parse_diagnostics(&text),
+        output: text,
+        duration_ms: 0,
+    }
+}
+
+// --- Artifacts ---
+
+/// Write the files into a new workspace and compile them for `options`
+async fn emit_files(
+    ctx: &RunContext,
+    language: &LanguageDefinition,
+    files: &[ProjectFile],
+    entry: &str,
+    options: &ArtifactOptions,
+) -> Result<Artifact, String> {
+    let template = match &language.runner {
+        LanguageRunner::Template(template) => Some(template),
+        LanguageRunner::Commands => None,
+    };
+    let is_supported = matches!(
+        (template, options.kind),
+        (
+            Some(TemplateType::Rust),
+            ArtifactKind::Asm | ArtifactKind::LlvmIr | ArtifactKind::Mir
+        ) | (Some(TemplateType::Java), ArtifactKind::Bytecode)
+            | (Some(TemplateType::C | TemplateType::Cpp), ArtifactKind::Asm)
+    );
+    if !is_supported {
+        return Err(
+            ArtifactError::Unsupported(language.name.clone(), options.kind.name()).to_string(),
+        );
+    }
+    for runtime in &language.runtimes {
+        require_runtime(ctx, runtime)?;
+    }
+
+    let workspace =
+        Workspace::create_in(&std::env::temp_dir(), &ctx.window_id).map_err(|e| e.to_string())?;
+    let emitted = match workspace
+        .write_files(files)
+        .and_then(|()| workspace.resolve(entry))
+    {
+        Ok(entry_path) => match template {
+            Some(TemplateType::Rust) => {
+                emit_rust(ctx, &workspace, &entry_path, files, options).await
+            }
+            Some(TemplateType::Java) => emit_java(ctx, &workspace, files).await,
+            Some(TemplateType::Cpp) => {
+                emit_cc(ctx, &workspace, files, entry, options, CcLanguage::Cpp).await
+            }
+            _ => emit_cc(ctx, &workspace, files, entry, options, CcLanguage::C).await,
+        },
+        Err(e) => Err(e.to_string()),
+    };
+    workspace.remove();
+    emitted
+}
+
+/// Emit Rust assembly, LLVM IR or MIR with `cargo rustc` for Cargo packages,
+/// or else with rustc
+async fn emit_rust(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    entry_path: &Path,
+    files: &[ProjectFile],
+    options: &ArtifactOptions,
+) -> Result<Artifact, String> {
+    let output_path = artifact_path(workspace, options.kind);
+    let args = artifacts::rustc_args(options, &output_path).map_err(|e| e.to_string())?;
+
+    let (output, errors) = if prepare_cargo_package(workspace, entry_path)? {
+        require_runtime(ctx, &RuntimeInfo::CARGO)?;
+        let mut cargo = cargo_command(ctx, "rustc");
+        cargo.arg("--").args(&args).current_dir(workspace.path());
+        let output = run_step(ctx, &mut cargo)
+            .await
+            .map_err(|e| format!("Failed to run cargo: {}", e))?;
+        let build = parse_build_output(&String::from_utf8_lossy(&output.stdout));
+        let errors = format!(
+            "{}{}",
+            build.rendered,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        (output, errors)
+    } else {
+        let mut rustc = ctx.runtimes.command("rustc");
+        rustc
+            .arg(
+                entry_path
+                    .strip_prefix(workspace.path())
+                    .unwrap_or(entry_path),
+            )
+            .arg("--error-format=json")
+            .args(&args)
+            .current_dir(workspace.path());
+        let output = run_step(ctx, &mut rustc)
+            .await
+            .map_err(|e| format!("Failed to run rustc: {}", e))?;
+        let errors = parse_rustc_json(&String::from_utf8_lossy(&output.stderr)).rendered;
+        (output, errors)
+    };
+    if !output.status.success() {
+        return Err(errors);
+    }
+
+    let text = read_artifact(&output_path, options.kind)?;
+    let root = workspace_root(workspace);
+    Ok(match options.kind {
+        ArtifactKind::LlvmIr => filter_llvm_ir(&text, &root, files),
+        ArtifactKind::Mir => map_mir(&text, files),
+        _ => filter_asm(&text, &root, files),
+    })
+}
+
+/// Compile Java with debug info and disassemble every class it produced
+async fn emit_java(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+) -> Result<Artifact, String> {
+    let mut javac = ctx.runtimes.command("javac");
+    javac
+        .args(["-g", "-d", "."])
+        .args(source_files(files, &["java"]));
+    let compiled = run_checker(ctx, javac, workspace, |_| Vec::new()).await?;
+    if !compiled.success {
+        return Err(compiled.output);
+    }
+
+    let mut classes: Vec<String> = workspace
+        .files_with_extension("class")
+        .iter()
+        .filter_map(|path| {
+            let class = path.strip_prefix(workspace.path()).ok()?.with_extension("");
+            Some(class.to_string_lossy().replace(['/', '\\'], "."))
+        })
+        .collect();
+    classes.sort();
+
+    // javap ships beside javac, which a pin or override may have moved
+    let javap_path = match ctx.runtimes.program("javac").parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => {
+            dir.join(format!("javap{}", std::env::consts::EXE_SUFFIX))
+        }
+        _ => PathBuf::from("javap"),
+    };
+    let mut disassembler = ctx.runtimes.command_with("javap", javap_path);
+    disassembler
+        .args(["-c", "-l", "-p", "-cp", "."])
+        .args(&classes);
+    let listing = run_checker(ctx, disassembler, workspace, |_| Vec::new()).await?;
+    if !listing.success {
+        return Err(listing.output);
+    }
+    Ok(filter_javap(&listing.output, files))
+}
+
+/// Compile the entry file of a C or C++ project to assembly with the
+/// compiler a run would pick. The options' optimization level replaces the
+/// project's.
+async fn emit_cc(
+    ctx: &RunContext,
+    workspace: &Workspace,
+    files: &[ProjectFile],
+    entry: &str,
+    options: &ArtifactOptions,
+    language: CcLanguage,
+) -> Result<Artifact, String> {
+    let compiler = select_compiler(ctx.cc.compiler, |compiler| {
+        ctx.runtimes
+            .is_installed(&language.runtime(compiler).command)
+    });
+    let runtime = language.runtime(compiler);
+    require_runtime(ctx, &runtime)?;
+
+    let mut cc_options = ctx.cc.clone();
+    if let Some(level) = &options.opt_level {
+        cc_options.optimization = Some(level.clone());
+    }
+    // `-S` writes one file per source, so only the entry is compiled
+    let sources: Vec<ProjectFile> = files.iter().filter(|f| f.name == entry).cloned().collect();
+    let output_path = artifact_path(workspace, options.kind);
+    let output_name = output_path.to_string_lossy();
+    let args =
+        compiler_args(language, &cc_options, &sources, &output_name).map_err(|e| e.to_string())?;
+    let mut cc = ctx.runtimes.command(&runtime.command);
+    cc.args(artifacts::cc_args(options)).args(args);
+    let assembled = run_checker(ctx, cc, workspace, |_| Vec::new()).await?;
+    if !assembled.success {
+        return Err(assembled.output);
+    }
+
+    let text = read_artifact(&output_path, options.kind)?;
+    Ok(filter_asm(&text, &workspace_root(workspace), files))
+}
+
+fn artifact_path(workspace: &Workspace, kind: ArtifactKind) -> PathBuf {
+    workspace
+        .path()
+        .join(format!("{ARTIFACT_NAME}.{}", artifacts::extension(kind)))
+}
+
+fn read_artifact(path: &Path, kind: ArtifactKind) -> Result<String, String> {
+    std::fs::read_to_string(path).map_err(|e| format!("Failed to read the {}: {}", kind.name(), e))
+}
+
+/// The workspace as compilers record it in debug info, with symlinks such
+/// as macOS's `/var` resolved
+fn workspace_root(workspace: &Workspace) -> PathBuf {
+    workspace
+        .path()
+        .canonicalize()
+        .unwrap_or_else(|_| workspace.path().to_path_buf())
+}
+
+// --- Input Detection ---
+
+const INPUT_POLL_INTERVAL: Duration = Duration::from_millis(150);
+
+/// Poll the running program and emit `execution:waiting-input` whenever it
+/// starts or stops blocking on stdin
+fn spawn_input_watcher(pid: u32, window_id: String, app: AppHandle, processes: RunningProcesses) {
+    tauri::async_runtime::spawn(async move {
+        let mut waiting = false;
+
+        // Stop once the program exits, another run replaces it or its input
+        // is closed
+        while processes.accepts_input(&window_id, pid).await {
+            // Scanning /proc is blocking file I/O
+            let scan = tauri::async_runtime::spawn_blocking(move || is_waiting_for_stdin(pid));
+            let Some(now_waiting) = scan.await.ok().flatten() else {
+                return;
+            };
+            if now_waiting != waiting {
+                waiting = now_waiting;
+                let _ = app.emit_to(&window_id, "execution:waiting-input", waiting);
+            }
+            tokio::time::sleep(INPUT_POLL_INTERVAL).await;
+        }
+
+        if waiting {
+            let _ = app.emit_to(&window_id, "execution:waiting-input", false);
+        }
+    });
+}
+
+// --- Output Streaming ---
+
+/// Output collected over a run, kept outside the streaming future so a
+/// timeout does not lose what was already read
+#[derive(Default)]
+struct CollectedOutput {
+    stdout: String,
+    stderr: String,
+    bytes: u64,
+    seq: u64,
+}
+
+/// Stream the program's output until it exits, then report the result and,
+/// when it failed on an import `installer` could fix, the missing package
+fn spawn_output_streamer(
+    stdout: Option<tokio::process::ChildStdout>,
+    stderr: Option<tokio::process::ChildStderr>,
+    ctx: &RunContext,
+    workspace: Workspace,
+    installer: Option<PackageInstaller>,
 ) {
     let start = Instant::now();
+    let window_id = ctx.window_id.clone();
+    let app = ctx.app.clone();
+    let processes = ctx.processes.clone();
+    let limits = ctx.limits.clone();
 
     tauri::async_runtime::spawn(async move {
-        let (stdout_output, stderr_output) = stream_outputs(stdout, stderr, &window_id, &app).await;
+        let mut output = CollectedOutput::default();
+        let streaming = stream_outputs(
+            stdout,
+            stderr,
+            &window_id,
+            &app,
+            &mut output,
+            limits.max_output_bytes,
+        );
 
-        let exit_code = wait_for_process(&processes, &window_id).await;
+        let mut limit_exceeded = match limits.timeout_ms {
+            Some(ms) => tokio::time::timeout(Duration::from_millis(ms), streaming)
+                .await
+                .unwrap_or(Some(LimitKind::Timeout)),
+            None => streaming.await,
+        };
 
-        // Cleanup directory
-        let _ = std::fs::remove_dir_all(&cleanup_dir);
+        let exit_code = if limit_exceeded.is_some() {
+            processes.kill(&window_id).await;
+            -1
+        } else {
+            match wait_for_process(&processes, &window_id).await {
+                Some(status) => {
+                    limit_exceeded = detect_exceeded(&status, &output.stderr, &limits);
+                    status.code().unwrap_or(-1)
+                }
+                None => -1,
+            }
+        };
 
-        emit_completion(
+        workspace.remove();
+
+        if let Some(kind) = limit_exceeded {
+            output.stderr.push_str(&limit_message(kind, &limits));
+        } else if exit_code != 0 {
+            if let Some(missing) = installer.and_then(|i| i.diagnose(&output.stderr)) {
+                emit_missing_module(&app, &window_id, missing);
+            }
+        }
+
+        emit_result(
             &app,
             &window_id,
-            &stdout_output,
-            &stderr_output,
-            exit_code,
-            start.elapsed().as_millis() as u64,
+            ExecutionResult {
+                stdout: output.stdout,
+                stderr: output.stderr,
+                exit_code,
+                duration_ms: start.elapsed().as_millis() as u64,
+                limit_exceeded,
+            },
         );
 
         update_stop_menu_state(&app, &window_id, false);
     });
 }
 
+/// Read stdout and stderr at the same time and forward every chunk in the
+/// order it arrived.
+///
+/// Returns early with [`LimitKind::Output`] once the program writes more than
+/// `max_bytes`.
 async fn stream_outputs(
     stdout: Option<tokio::process::ChildStdout>,
     stderr: Option<tokio::process::ChildStderr>,
     window_id: &str,
     app: &AppHandle,
-) -> (String, String) {
-    let mut stdout_output = String::new();
-    let mut stderr_output = String::new();
+    output: &mut CollectedOutput,
+    max_bytes: Option<u64>,
+) -> Option<LimitKind> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
 
     if let Some(stdout) = stdout {
-        let mut reader = BufReader::new(stdout);
-        let mut line = String::new();
-        while reader.read_line(&mut line).await.unwrap_or(0) > 0 {
-            stdout_output.push_str(&line);
-            emit_output(app, window_id, &line, "stdout");
-            line.clear();
-        }
+        spawn_stream_reader(stdout, "stdout", tx.clone());
     }
-
     if let Some(stderr) = stderr {
-        let mut reader = BufReader::new(stderr);
-        let mut line = String::new();
-        while reader.read_line(&mut line).await.unwrap_or(0) > 0 {
-            stderr_output.push_str(&line);
-            emit_output(app, window_id, &line, "stderr");
-            line.clear();
+        spawn_stream_reader(stderr, "stderr", tx.clone());
+    }
+    drop(tx);
+
+    while let Some((stream, line, timestamp)) = rx.recv().await {
+        output.bytes += line.len() as u64;
+        if max_bytes.is_some_and(|max| output.bytes > max) {
+            return Some(LimitKind::Output);
         }
+
+        output.seq += 1;
+        if stream == "stdout" {
+            output.stdout.push_str(&line);
+        } else {
+            output.stderr.push_str(&line);
+        }
+        emit_output(app, window_id, &line, stream, output.seq, timestamp);
     }
 
-    (stdout_output, stderr_output)
+    None
 }
 
-async fn wait_for_process(processes: &RunningProcesses, window_id: &str) -> i32 {
-    if let Some(mut child) = processes.remove(window_id).await {
-        child.wait().await.ok().and_then(|s| s.code()).unwrap_or(-1)
-    } else {
-        -1
+type OutputChunk = (&'static str, String, i64);
+
+fn spawn_stream_reader<R>(reader: R, stream: &'static str, tx: mpsc::UnboundedSender<OutputChunk>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut reader = BufReader::new(reader);
+        let mut pending = Vec::new();
+        while let Some(chunk) = read_output_chunk(&mut reader, &mut pending).await {
+            let timestamp = chrono::Utc::now().timestamp_millis();
+            if tx.send((stream, chunk, timestamp)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Read the next line, or whatever part of a line is available when the
+/// program has not written a newline yet (e.g. an `input()` prompt)
+async fn read_output_chunk<R>(reader: &mut R, pending: &mut Vec<u8>) -> Option<String>
+where
+    R: AsyncBufRead + Unpin,
+{
+    loop {
+        let available = reader.fill_buf().await.unwrap_or_default();
+
+        if available.is_empty() {
+            if pending.is_empty() {
+                return None;
+            }
+            return Some(String::from_utf8_lossy(&std::mem::take(pending)).into_owned());
+        }
+
+        if let Some(newline) = available.iter().position(|&b| b == b'\n') {
+            pending.extend_from_slice(&available[..=newline]);
+            reader.consume(newline + 1);
+            return Some(String::from_utf8_lossy(&std::mem::take(pending)).into_owned());
+        }
+
+        let len = available.len();
+        pending.extend_from_slice(available);
+        reader.consume(len);
+
+        if let Some(chunk) = take_complete_utf8(pending) {
+            return Some(chunk);
+        }
+    }
+}
+
+/// Split off the longest prefix of `pending` that does not end inside a
+/// multi-byte character
+fn take_complete_utf8(pending: &mut Vec<u8>) -> Option<String> {
+    let complete = match std::str::from_utf8(pending) {
+        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+        _ => pending.len(),
+    };
+    if complete == 0 {
+        return None;
     }
+
+    let rest = pending.split_off(complete);
+    let chunk = String::from_utf8_lossy(pending).into_owned();
+    *pending = rest;
+    Some(chunk)
+}
+
+async fn wait_for_process(processes: &RunningProcesses, window_id: &str) -> Option<ExitStatus> {
+    let mut child = processes.remove(window_id).await?;
+    child.wait().await.ok()
 }
 
-fn emit_output(app: &AppHandle, window_id: &str, line: &str, stream: &str) {
+fn emit_output(
+    app: &AppHandle,
+    window_id: &str,
+    line: &str,
+    stream: &str,
+    seq: u64,
+    timestamp: i64,
+) {
     // Use emit_to to send event only to the specific window
     let _ = app.emit_to(
         window_id,
@@ -490,10 +2893,28 @@ fn emit_output(app: &AppHandle, window_id: &str, line: &str, stream: &str) {
         ExecutionOutput {
             line: line.to_string(),
             stream: stream.to_string(),
+            seq,
+            timestamp,
         },
     );
 }
 
+fn emit_diagnostics(app: &AppHandle, window_id: &str, diagnostics: &[Diagnostic]) {
+    let _ = app.emit_to(window_id, "execution:diagnostics", diagnostics);
+}
+
+fn emit_environment(app: &AppHandle, window_id: &str, state: EnvironmentState, message: String) {
+    let _ = app.emit_to(
+        window_id,
+        "execution:environment",
+        EnvironmentStatus { state, message },
+    );
+}
+
+fn emit_missing_module(app: &AppHandle, window_id: &str, missing: MissingModule) {
+    let _ = app.emit_to(window_id, "execution:missing-module", missing);
+}
+
 fn emit_completion(
     app: &AppHandle,
     window_id: &str,
@@ -502,21 +2923,119 @@ fn emit_completion(
     exit_code: i32,
     duration_ms: u64,
 ) {
-    // Use emit_to to send event only to the specific window
-    let _ = app.emit_to(
+    emit_result(
+        app,
         window_id,
-        "execution:completed",
         ExecutionResult {
             stdout: stdout.to_string(),
             stderr: stderr.to_string(),
             exit_code,
             duration_ms,
+            limit_exceeded: None,
         },
     );
 }
 
+fn emit_result(app: &AppHandle, window_id: &str, result: ExecutionResult) {
+    // Use emit_to to send event only to the specific window
+    let _ = app.emit_to(window_id, "execution:completed", result);
+}
+
 // --- Utilities ---
 
+/// Fully qualified name of the class to launch from a Java entry file
+fn java_main_class(code: &str, entry_path: &Path) -> String {
+    let class_name = extract_java_class_name(code).unwrap_or_else(|| {
+        entry_path
+            .file_stem()
+            .map_or_else(|| "Main".to_string(), |s| s.to_string_lossy().into_owned())
+    });
+
+    match extract_java_package(code) {
+        Some(package) => format!("{}.{}", package, class_name),
+        None => class_name,
+    }
+}
+
+/// The class holding a Kotlin file's top-level `main`: `MainKt` for
+/// `main.kt`, unless renamed with `@file:JvmName`
+fn kotlin_main_class(code: &str, entry_path: &Path) -> String {
+    let jvm_name = code
+        .lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("@file:JvmName(\""))
+        .and_then(|rest| rest.split('"').next())
+        .map(str::to_string);
+    let class_name = jvm_name.unwrap_or_else(|| {
+        let stem = entry_path
+            .file_stem()
+            .map_or_else(|| "main".to_string(), |s| s.to_string_lossy().into_owned());
+        let mut chars = stem.chars();
+        let capitalized: String = chars
+            .next()
+            .map(|c| c.to_ascii_uppercase())
+            .into_iter()
+            .chain(chars)
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        format!("{capitalized}Kt")
+    });
+
+    // Kotlin and Scala package declarations read like Java's, minus the `;`
+    match extract_java_package(code) {
+        Some(package) => format!("{package}.{class_name}"),
+        None => class_name,
+    }
+}
+
+/// The entry point of a Scala file: a Scala 3 `@main` method, or the object
+/// that defines `main` or extends `App`
+fn scala_main_class(code: &str) -> String {
+    let mut object = None;
+    let mut main = None;
+    for line in code.lines().map(str::trim) {
+        if let Some(rest) = line.strip_prefix("@main def ") {
+            main = Some(identifier(rest));
+            break;
+        }
+        if let Some(rest) = line.strip_prefix("object ") {
+            let name = identifier(rest);
+            if rest.contains("extends App") {
+                main = Some(name);
+                break;
+            }
+            object = Some(name);
+        }
+        if line.starts_with("def main(") && object.is_some() {
+            main = object.take();
+            break;
+        }
+    }
+    let class_name = main
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "Main".to_string());
+
+    match extract_java_package(code) {
+        Some(package) => format!("{package}.{class_name}"),
+        None => class_name,
+    }
+}
+
+fn identifier(text: &str) -> String {
+    text.chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .collect()
+}
+
+fn extract_java_package(code: &str) -> Option<String> {
+    code.lines()
+        .map(str::trim)
+        .find_map(|line| line.strip_prefix("package "))
+        .and_then(|rest| rest.split(';').next())
+        .map(|package| package.trim().to_string())
+        .filter(|package| !package.is_empty())
+}
+
 fn extract_java_class_name(code: &str) -> Option<String> {
     for line in code.lines() {
         let trimmed = line.trim();
@@ -533,3 +3052,112 @@ fn extract_java_class_name(code: &str) -> Option<String> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_second_process_is_refused() {
+        tauri::async_runtime::block_on(async {
+            let processes = RunningProcesses::new();
+            let sleep = || {
+                let mut cmd = Command::new("sleep");
+                cmd.arg("30").kill_on_drop(true);
+                process_group::isolate(&mut cmd);
+                cmd.spawn().unwrap()
+            };
+            assert!(processes.insert("w".to_string(), sleep()).await);
+            assert!(!processes.insert("w".to_string(), sleep()).await);
+            assert!(processes.insert("other".to_string(), sleep()).await);
+
+            // The first process is still the one Stop ends
+            assert!(processes.kill("w").await);
+            assert!(!processes.kill("w").await);
+            processes.kill_all().await;
+        });
+    }
+
+    #[test]
+    fn test_java_main_class() {
+        let code = "package demo.app;\n\npublic class Greeter {\n}\n";
+        assert_eq!(
+            java_main_class(code, Path::new("demo/app/Greeter.java")),
+            "demo.app.Greeter"
+        );
+        assert_eq!(
+            java_main_class("class Helper {}", Path::new("Helper.java")),
+            "Helper"
+        );
+    }
+
+    #[test]
+    fn test_python_setup_uses_override() {
+        let runtimes: RuntimeOverrides =
+            toml::from_str("[python3]\npath = \"/opt/pypy3/bin/pypy3\"\n").unwrap();
+        let files = [ProjectFile {
+            name: "main.py".to_string(),
+            content: "print('hi')\n".to_string(),
+            language: "python".to_string(),
+        }];
+        assert_eq!(
+            python_setup(&runtimes, &files, "main.py", None).unwrap(),
+            PythonSetup::System("/opt/pypy3/bin/pypy3".to_string())
+        );
+        assert_eq!(
+            python_setup(&RuntimeOverrides::default(), &files, "main.py", None).unwrap(),
+            PythonSetup::System("python3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_jvm_main_classes() {
+        assert_eq!(
+            kotlin_main_class("fun main() {}", Path::new("main.kt")),
+            "MainKt"
+        );
+        assert_eq!(
+            kotlin_main_class(
+                "@file:JvmName(\"App\")\npackage demo\n\nfun main() {}",
+                Path::new("demo/app-main.kt")
+            ),
+            "demo.App"
+        );
+        assert_eq!(
+            kotlin_main_class("package demo\nfun main() {}", Path::new("app-main.kt")),
+            "demo.App_mainKt"
+        );
+
+        let code = "package demo\n\nobject Util {\n  def twice(n: Int) = n * 2\n}\n\nobject Main {\n  def main(args: Array[String]): Unit = {}\n}\n";
+        assert_eq!(scala_main_class(code), "demo.Main");
+        assert_eq!(scala_main_class("object Hello extends App {\n}"), "Hello");
+        assert_eq!(
+            scala_main_class("@main def greet(): Unit =\n  println(1)"),
+            "greet"
+        );
+    }
+
+    #[test]
+    fn test_deno_permission_flags() {
+        assert_eq!(deno_permission_flags(None), ["--allow-all"]);
+        let permissions = ["read".to_string(), "net=api.example.com".to_string()];
+        assert_eq!(
+            deno_permission_flags(Some(&permissions)),
+            ["--allow-read", "--allow-net=api.example.com"]
+        );
+    }
+
+    #[test]
+    fn test_take_complete_utf8() {
+        let mut pending = b"Name: ".to_vec();
+        assert_eq!(take_complete_utf8(&mut pending).as_deref(), Some("Name: "));
+        assert!(pending.is_empty());
+
+        // "é" is 0xC3 0xA9; keep the dangling lead byte until the rest arrives
+        let mut pending = vec![b'a', 0xC3];
+        assert_eq!(take_complete_utf8(&mut pending).as_deref(), Some("a"));
+        assert_eq!(pending, vec![0xC3]);
+        assert_eq!(take_complete_utf8(&mut pending), None);
+    }
+}
@@ -1,5 +1,10 @@
+use super::execution::{app_data_path, language_registry};
+use crate::services::runtime_inventory::{RuntimeCache, RuntimeInventory};
+use crate::services::runtime_overrides::{RuntimeOverrides, RUNTIMES_FILE};
+use crate::services::version_managers::{discover_toolchains, ManagedToolchain};
 use font_kit::source::SystemSource;
 use std::collections::HashSet;
+use tauri::{AppHandle, Manager, State};
 
 /// Get list of monospace font families installed on the system
 #[tauri::command]
@@ -48,3 +53,52 @@ pub fn get_system_fonts() -> Vec<String> {
     fonts.sort();
     fonts
 }
+
+/// Every known runtime with its path and version, from the last scan
+#[tauri::command]
+pub async fn list_runtimes(
+    cache: State<'_, RuntimeCache>,
+    app: AppHandle,
+) -> Result<RuntimeInventory, String> {
+    let overrides = runtime_overrides(&app)?;
+    Ok(cache
+        .get(language_registry(&app).languages(), &overrides)
+        .await)
+}
+
+/// Scan the runtimes again, e.g. after installing one
+#[tauri::command]
+pub async fn refresh_runtimes(
+    cache: State<'_, RuntimeCache>,
+    app: AppHandle,
+) -> Result<RuntimeInventory, String> {
+    let overrides = runtime_overrides(&app)?;
+    Ok(cache
+        .refresh(language_registry(&app).languages(), &overrides)
+        .await)
+}
+
+/// Toolchains installed by pyenv, conda, nvm, fnm, sdkman and rustup, which
+/// projects can pin
+#[tauri::command]
+pub fn list_toolchains() -> Vec<ManagedToolchain> {
+    discover_toolchains()
+}
+
+/// Start the first scan at startup so `list_runtimes` rarely has to wait
+pub fn scan_runtimes_in_background(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let registry = language_registry(&app);
+        // A broken settings file is reported when the runtimes are listed
+        if let Ok(overrides) = runtime_overrides(&app) {
+            app.state::<RuntimeCache>()
+                .get(registry.languages(), &overrides)
+                .await;
+        }
+    });
+}
+
+fn runtime_overrides(app: &AppHandle) -> Result<RuntimeOverrides, String> {
+    RuntimeOverrides::load(&app_data_path(app, RUNTIMES_FILE)).map_err(|e| e.to_string())
+}
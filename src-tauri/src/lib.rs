@@ -3,11 +3,12 @@ mod models;
 mod services;
 
 use commands::{AppState, RunningProcesses};
+use services::runtime_inventory::RuntimeCache;
 use services::ProjectManager;
 use std::sync::Mutex;
 use tauri::{
     menu::{Menu, MenuItemBuilder, PredefinedMenuItem, SubmenuBuilder},
-    Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent,
+    Emitter, Manager, RunEvent, WebviewUrl, WebviewWindowBuilder, WindowEvent,
 };
 use tauri_plugin_shell::ShellExt;
 
@@ -28,6 +29,17 @@ pub fn build_menu(app: &tauri::AppHandle, is_web_editor: bool) -> tauri::Result<
     let new_python = MenuItemBuilder::with_id("new_python", "Python").build(app)?;
     let new_rust = MenuItemBuilder::with_id("new_rust", "Rust").build(app)?;
     let new_java = MenuItemBuilder::with_id("new_java", "Java").build(app)?;
+    let new_kotlin = MenuItemBuilder::with_id("new_kotlin", "Kotlin").build(app)?;
+    let new_scala = MenuItemBuilder::with_id("new_scala", "Scala").build(app)?;
+    let new_groovy = MenuItemBuilder::with_id("new_groovy", "Groovy").build(app)?;
+    let new_go = MenuItemBuilder::with_id("new_go", "Go").build(app)?;
+    let new_c = MenuItemBuilder::with_id("new_c", "C").build(app)?;
+    let new_cpp = MenuItemBuilder::with_id("new_cpp", "C++").build(app)?;
+    let new_ruby = MenuItemBuilder::with_id("new_ruby", "Ruby").build(app)?;
+    let new_php = MenuItemBuilder::with_id("new_php", "PHP").build(app)?;
+    let new_perl = MenuItemBuilder::with_id("new_perl", "Perl").build(app)?;
+    let new_lua = MenuItemBuilder::with_id("new_lua", "Lua").build(app)?;
+    let new_bash = MenuItemBuilder::with_id("new_bash", "Bash").build(app)?;
 
     let mut template_builder = SubmenuBuilder::new(app, "New from Template")
         .items(&[
@@ -37,6 +49,17 @@ pub fn build_menu(app: &tauri::AppHandle, is_web_editor: bool) -> tauri::Result<
             &new_python,
             &new_rust,
             &new_java,
+            &new_kotlin,
+            &new_scala,
+            &new_groovy,
+            &new_go,
+            &new_c,
+            &new_cpp,
+            &new_ruby,
+            &new_php,
+            &new_perl,
+            &new_lua,
+            &new_bash,
         ]);
 
     // Add custom templates if available
@@ -164,6 +187,10 @@ pub fn build_menu(app: &tauri::AppHandle, is_web_editor: bool) -> tauri::Result<
         .accelerator("CmdOrCtrl+Enter")
         .build(app)?;
 
+    let check_code = MenuItemBuilder::with_id("check_code", "Check Code")
+        .accelerator("CmdOrCtrl+Shift+Enter")
+        .build(app)?;
+
     let stop_code = MenuItemBuilder::with_id("stop_code", "Stop")
         .accelerator("CmdOrCtrl+.")
         .enabled(false) // Start disabled, enable when code is running
@@ -171,6 +198,7 @@ pub fn build_menu(app: &tauri::AppHandle, is_web_editor: bool) -> tauri::Result<
 
     let run_menu = SubmenuBuilder::new(app, "Run")
         .item(&run_code)
+        .item(&check_code)
         .item(&stop_code)
         .build()?;
 
@@ -217,6 +245,8 @@ pub fn run() {
 
             app.manage(state);
             app.manage(RunningProcesses::new());
+            app.manage(RuntimeCache::default());
+            commands::scan_runtimes_in_background(app.handle());
 
             // Hide menu on launcher window (editor windows get menus when created)
             if let Some(launcher) = app.get_webview_window("launcher") {
@@ -265,6 +295,17 @@ pub fn run() {
                     "new_python" => emit_to_editors("menu:new-template", Some("python")),
                     "new_rust" => emit_to_editors("menu:new-template", Some("rust")),
                     "new_java" => emit_to_editors("menu:new-template", Some("java")),
+                    "new_kotlin" => emit_to_editors("menu:new-template", Some("kotlin")),
+                    "new_scala" => emit_to_editors("menu:new-template", Some("scala")),
+                    "new_groovy" => emit_to_editors("menu:new-template", Some("groovy")),
+                    "new_go" => emit_to_editors("menu:new-template", Some("go")),
+                    "new_c" => emit_to_editors("menu:new-template", Some("c")),
+                    "new_cpp" => emit_to_editors("menu:new-template", Some("cpp")),
+                    "new_ruby" => emit_to_editors("menu:new-template", Some("ruby")),
+                    "new_php" => emit_to_editors("menu:new-template", Some("php")),
+                    "new_perl" => emit_to_editors("menu:new-template", Some("perl")),
+                    "new_lua" => emit_to_editors("menu:new-template", Some("lua")),
+                    "new_bash" => emit_to_editors("menu:new-template", Some("bash")),
 
                     // Window-specific actions - emit to editor windows
                     "open" => emit_to_editors("menu:open", None),
@@ -274,6 +315,7 @@ pub fn run() {
                     "toggle_preview" => emit_to_editors("menu:toggle-preview", None),
                     "toggle_output" => emit_to_editors("menu:toggle-output", None),
                     "run_code" => emit_to_editors("menu:run-code", None),
+                    "check_code" => emit_to_editors("menu:check-code", None),
                     "stop_code" => emit_to_editors("menu:stop-code", None),
 
                     // Help menu actions
@@ -360,9 +402,19 @@ pub fn run() {
             commands::execute_rust,
             commands::execute_java,
             commands::execute_typescript,
+            commands::execute_project,
+            commands::check_project,
+            commands::emit_artifact,
+            commands::execute,
+            commands::list_languages,
             commands::stop_execution,
+            commands::write_stdin,
+            commands::close_stdin,
             commands::kill_window_processes,
             commands::get_system_fonts,
+            commands::list_runtimes,
+            commands::refresh_runtimes,
+            commands::list_toolchains,
         ])
         .on_window_event(|window, event| {
             if let WindowEvent::CloseRequested { .. } = event {
@@ -408,6 +460,14 @@ pub fn run() {
                 }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Don't leave programs (or their workers) running after the app quits
+            if let RunEvent::Exit = event {
+                if let Some(processes) = app.try_state::<RunningProcesses>() {
+                    tauri::async_runtime::block_on(processes.kill_all());
+                }
+            }
+        });
 }
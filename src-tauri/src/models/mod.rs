@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -9,6 +10,17 @@ pub enum TemplateType {
     Rust,
     Java,
     Typescript,
+    Go,
+    C,
+    Cpp,
+    Ruby,
+    Php,
+    Perl,
+    Lua,
+    Bash,
+    Kotlin,
+    Scala,
+    Groovy,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,6 +49,24 @@ pub struct Project {
     pub created_at: String,
     pub updated_at: String,
     pub saved_path: Option<String>,
+    #[serde(default)]
+    pub cargo: Option<CargoOptions>,
+    #[serde(default)]
+    pub python: Option<PythonOptions>,
+    #[serde(default)]
+    pub node: Option<NodeOptions>,
+    #[serde(default)]
+    pub cc: Option<CcOptions>,
+    #[serde(default)]
+    pub run: Option<RunConfig>,
+    /// Toolchains from version managers to run with instead of the ones on
+    /// `PATH`
+    #[serde(default)]
+    pub toolchains: Option<Vec<ToolchainPin>>,
+    /// Registry language to run with instead of the template's, for
+    /// languages defined outside the app
+    #[serde(default)]
+    pub language: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -61,3 +91,237 @@ pub struct CustomTemplate {
     pub files: Vec<ProjectFile>,
     pub created_at: String,
 }
+
+/// Limits applied to a single program run.
+///
+/// CPU time and memory are enforced with rlimits and are ignored on Windows.
+/// The memory limit caps the address space, which runtimes that reserve large
+/// virtual regions up front (the JVM, V8) may need set generously.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunLimits {
+    /// Wall-clock time before the program, or a step building it, is killed
+    pub timeout_ms: Option<u64>,
+    /// CPU time the program may consume
+    pub cpu_time_secs: Option<u64>,
+    /// Maximum address space in megabytes
+    pub max_memory_mb: Option<u64>,
+    /// Combined stdout and stderr bytes before the program is killed
+    pub max_output_bytes: Option<u64>,
+}
+
+impl Default for RunLimits {
+    fn default() -> Self {
+        Self {
+            timeout_ms: None,
+            cpu_time_secs: Some(60),
+            max_memory_mb: None,
+            max_output_bytes: Some(16 * 1024 * 1024),
+        }
+    }
+}
+
+/// The limit that stopped a program
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitKind {
+    Timeout,
+    CpuTime,
+    Memory,
+    Output,
+}
+
+/// Settings for Rust projects built with Cargo
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CargoOptions {
+    /// Build with `--offline`, using only vendored, path or already
+    /// downloaded dependencies
+    pub offline: bool,
+}
+
+/// Settings for Python projects that declare requirements
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PythonOptions {
+    /// Local directory of wheels to install from instead of the package index
+    pub wheelhouse: Option<String>,
+}
+
+/// Settings for Node and TypeScript projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NodeOptions {
+    /// npm packages to install when the project has no `package.json`,
+    /// as package name to version range
+    pub dependencies: BTreeMap<String, String>,
+    /// Install with `--offline`, from npm's cache only
+    pub offline: bool,
+    /// Runtime for JavaScript and TypeScript
+    pub runtime: JsRuntime,
+    /// Deno permissions granted with `--allow-*`, e.g. `read` or
+    /// `net=api.example.com`; all of them when unset
+    pub deno_permissions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsRuntime {
+    #[default]
+    Node,
+    Deno,
+    Bun,
+}
+
+/// Compiler settings for C and C++ projects
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CcOptions {
+    /// Compiler family; the first installed of GCC and Clang when unset
+    pub compiler: Option<CcCompiler>,
+    /// Language standard passed as `-std=`, e.g. `c17` or `c++20`
+    pub standard: Option<String>,
+    /// Optimization level passed as `-O`: `0`-`3`, `s`, `z` or `g`
+    pub optimization: Option<String>,
+    /// Extra compiler and linker flags, e.g. `-Wall` or `-lm`
+    pub flags: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CcCompiler {
+    Gcc,
+    Clang,
+}
+
+/// A project's choice of toolchain from a version manager, e.g. Python 3.11
+/// via pyenv or Rust nightly
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolchainPin {
+    pub runtime: ManagedRuntime,
+    /// Any manager that has a matching installation when unset
+    #[serde(default)]
+    pub manager: Option<VersionManager>,
+    /// A toolchain or environment name (`nightly`, a conda env), or a
+    /// version prefix such as `3.11`, which picks the newest match
+    pub version: String,
+}
+
+/// Runtimes that version managers install
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ManagedRuntime {
+    Python,
+    Node,
+    Rust,
+    Java,
+    Kotlin,
+    Scala,
+    Groovy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VersionManager {
+    Pyenv,
+    Conda,
+    Nvm,
+    Fnm,
+    Sdkman,
+    Rustup,
+}
+
+/// How a project's program is started
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunConfig {
+    /// Command-line arguments passed to the program
+    pub args: Vec<String>,
+    /// Environment variables, applied on top of `env_file`
+    pub env: BTreeMap<String, String>,
+    /// `.env` file to load variables from, relative to the project root
+    pub env_file: Option<String>,
+    /// Working directory, relative to the project root; defaults to the root
+    pub working_dir: Option<String>,
+}
+
+/// Compiler output `emit_artifact` can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArtifactKind {
+    /// Assembly from rustc, GCC or Clang
+    Asm,
+    /// LLVM IR from rustc
+    LlvmIr,
+    /// Rust's mid-level IR
+    Mir,
+    /// JVM bytecode, disassembled by javap
+    Bytecode,
+}
+
+impl ArtifactKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Asm => "assembly",
+            Self::LlvmIr => "LLVM IR",
+            Self::Mir => "MIR",
+            Self::Bytecode => "bytecode",
+        }
+    }
+}
+
+/// What `emit_artifact` shows and how the code is compiled for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactOptions {
+    pub kind: ArtifactKind,
+    /// Optimization level: `0`-`3`, `s` or `z`. Rust defaults to `0`; C and
+    /// C++ to the project's setting.
+    #[serde(default)]
+    pub opt_level: Option<String>,
+    /// CPU to generate code for, e.g. `native` or `skylake`
+    #[serde(default)]
+    pub target_cpu: Option<String>,
+}
+
+/// A compiler error or warning, located in a project file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// Project file name, relative to the project root
+    pub file: String,
+    /// 1-based line of the primary location
+    pub line: u32,
+    /// 1-based column of the primary location
+    pub column: u32,
+    pub severity: Severity,
+    /// Compiler error code, e.g. `E0308` or `TS2322`
+    pub code: Option<String>,
+    pub message: String,
+    pub spans: Vec<DiagnosticSpan>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+/// A source range a diagnostic points at; positions are 1-based and the end
+/// column is exclusive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticSpan {
+    pub file: String,
+    pub line_start: u32,
+    pub column_start: u32,
+    pub line_end: u32,
+    pub column_end: u32,
+    pub label: Option<String>,
+    /// Whether this is the main location rather than related context
+    pub primary: bool,
+}
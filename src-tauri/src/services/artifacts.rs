@@ -0,0 +1,630 @@
+//! Compiler output shown beside the source: assembly, LLVM IR, MIR and JVM
+//! bytecode.
+//!
+//! Each format is trimmed to what's worth reading, and every line left is
+//! mapped back to the project file and line it was generated from, where the
+//! compiler records one: assembly through `.loc` directives, LLVM IR through
+//! `!dbg` metadata and javap output through its `LineNumberTable`s. MIR
+//! carries no locations on stable Rust, so only its function headers are
+//! mapped.
+
+use crate::models::{ArtifactKind, ArtifactOptions, ProjectFile};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use thiserror::Error;
+
+/// File name, without extension, compilers write artifacts to
+pub const ARTIFACT_NAME: &str = "codecell_artifact";
+
+/// Assembler directives that emit data, kept for string constants and tables
+const DATA_DIRECTIVES: &[&str] = &[
+    "ascii", "asciz", "string", "byte", "short", "word", "long", "int", "quad", "zero",
+];
+
+#[derive(Error, Debug)]
+pub enum ArtifactError {
+    #[error("Invalid optimization level: {0} (expected 0, 1, 2, 3, s or z)")]
+    InvalidOptLevel(String),
+    #[error("Invalid target CPU: {0}")]
+    InvalidTargetCpu(String),
+    #[error("{0} can't be shown as {1}")]
+    Unsupported(String, &'static str),
+}
+
+/// Compiler output, with the source line each of its lines came from
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    pub kind: ArtifactKind,
+    pub text: String,
+    /// One entry per line of `text`
+    pub lines: Vec<Option<SourceLine>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceLine {
+    /// Project file name, relative to the project root
+    pub file: String,
+    /// 1-based
+    pub line: u32,
+}
+
+impl Artifact {
+    fn new(kind: ArtifactKind) -> Self {
+        Self {
+            kind,
+            text: String::new(),
+            lines: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, text: &str, source: Option<SourceLine>) {
+        self.text.push_str(text);
+        self.text.push('\n');
+        self.lines.push(source);
+    }
+}
+
+/// Check options before they're passed to a compiler
+pub fn validate_options(options: &ArtifactOptions) -> Result<(), ArtifactError> {
+    if let Some(level) = &options.opt_level {
+        if !["0", "1", "2", "3", "s", "z"].contains(&level.as_str()) {
+            return Err(ArtifactError::InvalidOptLevel(level.clone()));
+        }
+    }
+    if let Some(cpu) = &options.target_cpu {
+        let is_valid = !cpu.is_empty()
+            && cpu
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !is_valid {
+            return Err(ArtifactError::InvalidTargetCpu(cpu.clone()));
+        }
+    }
+    Ok(())
+}
+
+/// rustc arguments writing the artifact to `output`, with line tables for
+/// the mapping. A single codegen unit keeps the output in one file.
+pub fn rustc_args(options: &ArtifactOptions, output: &Path) -> Result<Vec<String>, ArtifactError> {
+    let emit = match options.kind {
+        ArtifactKind::Asm => "asm",
+        ArtifactKind::LlvmIr => "llvm-ir",
+        ArtifactKind::Mir => "mir",
+        ArtifactKind::Bytecode => {
+            return Err(ArtifactError::Unsupported(
+                "Rust".to_string(),
+                options.kind.name(),
+            ))
+        }
+    };
+    let mut args = vec![
+        format!("--emit={emit}={}", output.display()),
+        "-C".to_string(),
+        "debuginfo=line-tables-only".to_string(),
+        "-C".to_string(),
+        "codegen-units=1".to_string(),
+        "-C".to_string(),
+        format!("opt-level={}", options.opt_level.as_deref().unwrap_or("0")),
+    ];
+    if let Some(cpu) = &options.target_cpu {
+        args.push("-C".to_string());
+        args.push(format!("target-cpu={cpu}"));
+    }
+    Ok(args)
+}
+
+/// GCC and Clang arguments that write assembly, with line tables for the
+/// mapping, instead of linking
+pub fn cc_args(options: &ArtifactOptions) -> Vec<String> {
+    let mut args = vec!["-S".to_string(), "-g1".to_string()];
+    if let Some(cpu) = &options.target_cpu {
+        args.push(format!("-march={cpu}"));
+    }
+    args
+}
+
+/// The extension of the file rustc or a C compiler writes `kind` to
+pub fn extension(kind: ArtifactKind) -> &'static str {
+    match kind {
+        ArtifactKind::Asm => "s",
+        ArtifactKind::LlvmIr => "ll",
+        ArtifactKind::Mir => "mir",
+        ArtifactKind::Bytecode => "class",
+    }
+}
+
+/// Trim assembly to instructions, labels that are jumped to and data, dropping
+/// directives, comments and debug sections
+pub fn filter_asm(asm: &str, workspace: &Path, files: &[ProjectFile]) -> Artifact {
+    // `.file` ids to project files; ids of other files map to `None`
+    let mut source_files: HashMap<&str, Option<String>> = HashMap::new();
+    let mut location: Option<SourceLine> = None;
+    let mut in_debug_section = false;
+    let mut kept: Vec<(&str, Option<SourceLine>)> = Vec::new();
+
+    for line in asm.lines() {
+        let trimmed = line.trim();
+        if is_asm_label(line) {
+            if !in_debug_section {
+                if !is_local_label(trimmed) {
+                    location = None;
+                }
+                kept.push((line, None));
+            }
+            continue;
+        }
+        if let Some(directive) = trimmed.strip_prefix('.') {
+            let (name, args) = directive
+                .split_once(char::is_whitespace)
+                .unwrap_or((directive, ""));
+            let args = args.trim();
+            match name {
+                // Data sections have no source lines
+                "section" => {
+                    in_debug_section = args.starts_with(".debug") || args.starts_with("__DWARF");
+                    location = None;
+                }
+                "text" | "data" | "bss" => {
+                    in_debug_section = false;
+                    location = None;
+                }
+                "file" => {
+                    if let Some((id, file)) = parse_file_directive(args, workspace, files) {
+                        source_files.insert(id, file);
+                    }
+                }
+                "loc" => {
+                    let mut parts = args.split_whitespace();
+                    let file = parts.next().and_then(|id| source_files.get(id)?.clone());
+                    let line = parts.next().and_then(|line| line.parse().ok());
+                    location = match (file, line) {
+                        (Some(file), Some(line)) if line > 0 => Some(SourceLine { file, line }),
+                        _ => None,
+                    };
+                }
+                _ if !in_debug_section && DATA_DIRECTIVES.contains(&name) => {
+                    kept.push((line, location.clone()));
+                }
+                _ => {}
+            }
+            continue;
+        }
+        let is_comment = ["#", ";", "//", "@"].iter().any(|c| trimmed.starts_with(c));
+        if in_debug_section || trimmed.is_empty() || is_comment {
+            continue;
+        }
+        kept.push((line, location.clone()));
+    }
+
+    // Local labels nothing refers to mark debug info and basic blocks that
+    // are only fallen into
+    let referenced: HashSet<&str> = kept
+        .iter()
+        .filter(|(line, _)| !is_asm_label(line))
+        .flat_map(|(line, _)| {
+            line.split(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$')))
+        })
+        .collect();
+    let mut artifact = Artifact::new(ArtifactKind::Asm);
+    for (line, source) in kept {
+        let label = line.trim_end().trim_end_matches(':');
+        if is_asm_label(line) && is_local_label(label) && !referenced.contains(label) {
+            continue;
+        }
+        artifact.push(line, source);
+    }
+    artifact
+}
+
+/// A label definition: a name at the start of the line ending with `:`
+fn is_asm_label(line: &str) -> bool {
+    let line = line.trim_end();
+    line.ends_with(':')
+        && !line.starts_with(char::is_whitespace)
+        && !line.contains(char::is_whitespace)
+}
+
+/// Assembler-local labels: `.L` on ELF, `L` on Mach-O
+fn is_local_label(label: &str) -> bool {
+    label.starts_with(".L") || (cfg!(target_os = "macos") && label.starts_with('L'))
+}
+
+/// The id and project file of `.file 1 "dir" "name"` or `.file 1 "name"`
+fn parse_file_directive<'a>(
+    args: &'a str,
+    workspace: &Path,
+    files: &[ProjectFile],
+) -> Option<(&'a str, Option<String>)> {
+    let (id, rest) = args.split_once(char::is_whitespace)?;
+    if !id.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    // Quoted strings sit at the odd indices
+    let strings: Vec<&str> = rest.split('"').skip(1).step_by(2).collect();
+    let (directory, name) = match strings.as_slice() {
+        [name] => (None, *name),
+        [directory, name, ..] => (Some(*directory), *name),
+        [] => return None,
+    };
+    Some((id, project_file(directory, name, workspace, files)))
+}
+
+/// The project file a compiler's file reference names, given relative to
+/// `directory` (or the workspace) or absolutely
+fn project_file(
+    directory: Option<&str>,
+    name: &str,
+    workspace: &Path,
+    files: &[ProjectFile],
+) -> Option<String> {
+    let path = match directory {
+        Some(directory) => Path::new(directory).join(name),
+        None => workspace.join(name),
+    };
+    let relative = path
+        .strip_prefix(workspace)
+        .ok()?
+        .to_string_lossy()
+        .replace('\\', "/");
+    files
+        .iter()
+        .find(|f| f.name == relative)
+        .map(|f| f.name.clone())
+}
+
+/// Trim LLVM IR of its metadata, mapping instructions and function
+/// definitions through their `!dbg` attachments
+pub fn filter_llvm_ir(ir: &str, workspace: &Path, files: &[ProjectFile]) -> Artifact {
+    let metadata: HashMap<&str, &str> = ir
+        .lines()
+        .filter_map(|line| line.split_once(" = "))
+        .filter(|(id, _)| {
+            id.strip_prefix('!')
+                .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        })
+        .collect();
+
+    let mut artifact = Artifact::new(ArtifactKind::LlvmIr);
+    for line in ir.lines() {
+        if line.starts_with('!') || line.starts_with("attributes #") {
+            continue;
+        }
+        let Some(start) = line.find("!dbg !") else {
+            artifact.push(line, None);
+            continue;
+        };
+        let id_start = start + "!dbg ".len();
+        let id_end = line[id_start + 1..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(line.len(), |end| id_start + 1 + end);
+        let text = format!(
+            "{}{}",
+            line[..start].trim_end().trim_end_matches(','),
+            &line[id_end..]
+        );
+        let source = ir_location(&line[id_start..id_end], &metadata, workspace, files);
+        artifact.push(&text, source);
+    }
+    artifact
+}
+
+/// The source line of a `DILocation` or `DISubprogram`. Locations name
+/// their scope, whose chain leads to the file.
+fn ir_location(
+    id: &str,
+    metadata: &HashMap<&str, &str>,
+    workspace: &Path,
+    files: &[ProjectFile],
+) -> Option<SourceLine> {
+    let node = metadata.get(id)?;
+    let line = metadata_field(node, "line")?.parse().ok()?;
+
+    let mut scope = *node;
+    // Scope chains are shallow; the bound only guards against cycles
+    for _ in 0..64 {
+        if let Some(file) = metadata_field(scope, "file") {
+            let file = metadata.get(file)?;
+            let name = metadata_field(file, "filename")?;
+            let directory = metadata_field(file, "directory");
+            let file = project_file(directory, name, workspace, files)?;
+            return (line > 0).then_some(SourceLine { file, line });
+        }
+        scope = metadata.get(metadata_field(scope, "scope")?)?;
+    }
+    None
+}
+
+/// A field of a metadata node like `!DILocation(line: 6, scope: !52)`,
+/// unquoted
+fn metadata_field<'a>(node: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("{name}: ");
+    let start = node
+        .match_indices(&key)
+        .find(|(i, _)| node[..*i].ends_with(['(', ' ']))
+        .map(|(i, _)| i + key.len())?;
+    let value = &node[start..];
+    match value.strip_prefix('"') {
+        Some(quoted) => quoted.split('"').next(),
+        None => value.split([',', ')']).next(),
+    }
+}
+
+/// Map MIR function headers to the `fn` they were lowered from
+pub fn map_mir(mir: &str, files: &[ProjectFile]) -> Artifact {
+    let mut artifact = Artifact::new(ArtifactKind::Mir);
+    for line in mir.lines().skip_while(|line| line.starts_with("//")) {
+        let source = line
+            .strip_prefix("fn ")
+            .and_then(|header| header.split('(').next())
+            .and_then(|path| {
+                // `main::{closure#0}` is part of `main`
+                path.split("::")
+                    .filter(|segment| !segment.starts_with('{'))
+                    .last()
+            })
+            .and_then(|name| find_rust_fn(name.split('<').next()?, files));
+        artifact.push(line, source);
+    }
+    artifact
+}
+
+/// The line defining `fn name` in the project's Rust files
+fn find_rust_fn(name: &str, files: &[ProjectFile]) -> Option<SourceLine> {
+    let definition = format!("fn {name}");
+    files
+        .iter()
+        .filter(|f| {
+            Path::new(&f.name)
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("rs"))
+        })
+        .find_map(|f| {
+            let index = f.content.lines().position(|line| {
+                line.match_indices(&definition).any(|(i, _)| {
+                    let after = &line[i + definition.len()..];
+                    let before = &line[..i];
+                    after.starts_with(['(', '<'])
+                        && (before.is_empty() || before.ends_with(char::is_whitespace))
+                })
+            })?;
+            Some(SourceLine {
+                file: f.name.clone(),
+                line: u32::try_from(index + 1).ok()?,
+            })
+        })
+}
+
+/// Map `javap -c -l` output through each method's `LineNumberTable`, which
+/// is dropped along with `LocalVariableTable`s
+pub fn filter_javap(javap: &str, files: &[ProjectFile]) -> Artifact {
+    let mut artifact = Artifact::new(ArtifactKind::Bytecode);
+    let mut source_name: Option<&str> = None;
+    let mut source_file: Option<String> = None;
+    // A method's lines, with the bytecode offset of instructions, until its
+    // line numbers are known
+    let mut pending: Vec<(&str, Option<u32>)> = Vec::new();
+    let mut line_numbers: Vec<(u32, u32)> = Vec::new();
+    let mut in_table = false;
+
+    let flush = |artifact: &mut Artifact,
+                 pending: &mut Vec<(&str, Option<u32>)>,
+                 line_numbers: &mut Vec<(u32, u32)>,
+                 file: &Option<String>| {
+        for (text, offset) in pending.drain(..) {
+            let line = offset.and_then(|offset| {
+                line_numbers
+                    .iter()
+                    .filter(|(start, _)| *start <= offset)
+                    .max_by_key(|(start, _)| *start)
+                    .map(|(_, line)| *line)
+            });
+            let source = line
+                .zip(file.clone())
+                .map(|(line, file)| SourceLine { file, line });
+            artifact.push(text, source);
+        }
+        line_numbers.clear();
+    };
+
+    for line in javap.lines() {
+        let trimmed = line.trim();
+        if trimmed == "LineNumberTable:" || trimmed.starts_with("LocalVariable") {
+            in_table = true;
+            continue;
+        }
+        if in_table {
+            if let Some((line_number, offset)) = trimmed
+                .strip_prefix("line ")
+                .and_then(|entry| entry.split_once(": "))
+            {
+                if let (Ok(line_number), Ok(offset)) = (line_number.parse(), offset.parse()) {
+                    line_numbers.push((offset, line_number));
+                }
+                continue;
+            }
+            if trimmed.starts_with("Start ") || trimmed.starts_with(|c: char| c.is_ascii_digit()) {
+                continue;
+            }
+            in_table = false;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("Compiled from \"") {
+            flush(&mut artifact, &mut pending, &mut line_numbers, &source_file);
+            source_name = rest.split('"').next();
+            source_file = None;
+        } else if source_file.is_none() && line.ends_with('{') {
+            source_file = source_name.and_then(|name| java_source(line, name, files));
+        }
+
+        let offset = trimmed
+            .split_once(": ")
+            .and_then(|(offset, _)| offset.parse().ok());
+        pending.push((line, offset));
+        if trimmed.is_empty() || trimmed == "}" {
+            flush(&mut artifact, &mut pending, &mut line_numbers, &source_file);
+        }
+    }
+    flush(&mut artifact, &mut pending, &mut line_numbers, &source_file);
+    artifact
+}
+
+/// The project file a class declared as `public class app.Main {` was
+/// compiled from, given javap's `Compiled from "Main.java"`
+fn java_source(declaration: &str, source_name: &str, files: &[ProjectFile]) -> Option<String> {
+    let mut words = declaration.split_whitespace();
+    words.find(|word| ["class", "interface", "enum", "record"].contains(word))?;
+    let class = words.next()?.split('<').next()?;
+    let expected = match class.rsplit_once('.') {
+        Some((package, _)) => format!("{}/{source_name}", package.replace('.', "/")),
+        None => source_name.to_string(),
+    };
+    // Sources needn't sit in their package's directory
+    files
+        .iter()
+        .find(|f| f.name == expected)
+        .or_else(|| {
+            files
+                .iter()
+                .find(|f| f.name == source_name || f.name.ends_with(&format!("/{source_name}")))
+        })
+        .map(|f| f.name.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> ProjectFile {
+        ProjectFile {
+            name: name.to_string(),
+            content: content.to_string(),
+            language: String::new(),
+        }
+    }
+
+    fn source(file: &str, line: u32) -> Option<SourceLine> {
+        Some(SourceLine {
+            file: file.to_string(),
+            line,
+        })
+    }
+
+    #[test]
+    fn test_filter_asm() {
+        let files = [file("main.c", "")];
+        let asm = "\t.file\t\"main.c\"\n\t.text\n.Ltext0:\n\t.file 0 \"/ws\" \"main.c\"\n\t.globl\tsquare\n\t.type\tsquare, @function\nsquare:\n.LFB11:\n\t.file 1 \"main.c\"\n\t.loc 1 3 19 view -0\n\t.cfi_startproc\n\t.loc 1 4 14 is_stmt 0 view .LVU2\n\timull\t%edi, %edi\n.L2:\n\tjmp\t.L2\n\tret\n\t.cfi_endproc\n\t.section\t.rodata.str1.1,\"aMS\",@progbits,1\n.LC0:\n\t.string\t\"%d\\n\"\n\t.file 2 \"/usr/include/stdio.h\"\n\t.loc 2 10 1\n\tnop\n\t.section\t.debug_info,\"\",@progbits\n.Ldebug_info0:\n\t.long\t0x8c\n";
+        let artifact = filter_asm(asm, Path::new("/ws"), &files);
+
+        assert_eq!(
+            artifact.text,
+            "square:\n\timull\t%edi, %edi\n.L2:\n\tjmp\t.L2\n\tret\n\t.string\t\"%d\\n\"\n\tnop\n"
+        );
+        assert_eq!(
+            artifact.lines,
+            [
+                None,
+                source("main.c", 4),
+                None,
+                source("main.c", 4),
+                source("main.c", 4),
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_llvm_ir() {
+        let files = [file("src/main.rs", "")];
+        let ir = "define hidden void @_ZN4main4main17hE() unnamed_addr #0 !dbg !52 {\nstart:\n  store i32 49, ptr %v, align 4, !dbg !56\n  call void @print(), !dbg !57\n  ret void\n}\n\nattributes #0 = { nounwind }\n!52 = distinct !DISubprogram(name: \"main\", scope: !54, file: !53, line: 5, scopeLine: 5)\n!53 = !DIFile(filename: \"src/main.rs\", directory: \"/ws\")\n!56 = !DILocation(line: 6, column: 13, scope: !52)\n!57 = !DILocation(line: 143, column: 5, scope: !58)\n!58 = !DILexicalBlockFile(scope: !52, file: !60, discriminator: 0)\n!60 = !DIFile(filename: \"library/std/src/macros.rs\", directory: \"/rustc/abc\")\n";
+        let artifact = filter_llvm_ir(ir, Path::new("/ws"), &files);
+
+        assert_eq!(
+            artifact.text,
+            "define hidden void @_ZN4main4main17hE() unnamed_addr #0 {\nstart:\n  store i32 49, ptr %v, align 4\n  call void @print()\n  ret void\n}\n\n"
+        );
+        assert_eq!(
+            artifact.lines,
+            [
+                source("src/main.rs", 5),
+                None,
+                source("src/main.rs", 6),
+                None,
+                None,
+                None,
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_mir() {
+        let files = [file(
+            "main.rs",
+            "fn square(x: i32) -> i32 {\n    x * x\n}\n\nfn main() {\n    let f = || square(2);\n}\n",
+        )];
+        let mir = "// WARNING: This output format is intended for human consumers only\nfn square(_1: i32) -> i32 {\n    bb0: {\n    }\n}\n\nfn main::{closure#0}(_1: &{closure@main.rs:6:13}) -> i32 {\n}\n";
+        let artifact = map_mir(mir, &files);
+
+        assert!(artifact.text.starts_with("fn square"));
+        assert_eq!(artifact.lines[0], source("main.rs", 1));
+        assert_eq!(artifact.lines[1], None);
+        assert_eq!(artifact.lines[5], source("main.rs", 5));
+    }
+
+    #[test]
+    fn test_filter_javap() {
+        let files = [file("src/app/Main.java", "")];
+        let javap = "Compiled from \"Main.java\"\npublic class app.Main {\n  static int square(int);\n    Code:\n       0: iload_0\n       1: iload_0\n       2: imul\n       3: ireturn\n    LineNumberTable:\n      line 5: 0\n    LocalVariableTable:\n      Start  Length  Slot  Name   Signature\n          0       4     0     x   I\n\n  public static void main(java.lang.String[]);\n    Code:\n       0: bipush        7\n       2: invokestatic  #7                  // Method square:(I)I\n       5: istore_1\n    LineNumberTable:\n      line 9: 0\n      line 10: 5\n}\n";
+        let artifact = filter_javap(javap, &files);
+
+        assert!(!artifact.text.contains("LineNumberTable"));
+        assert!(!artifact.text.contains("Signature"));
+        let mapped: Vec<_> = artifact
+            .text
+            .lines()
+            .zip(&artifact.lines)
+            .filter_map(|(text, source)| Some((text.trim(), source.as_ref()?.line)))
+            .collect();
+        assert_eq!(
+            mapped,
+            [
+                ("0: iload_0", 5),
+                ("1: iload_0", 5),
+                ("2: imul", 5),
+                ("3: ireturn", 5),
+                ("0: bipush        7", 9),
+                (
+                    "2: invokestatic  #7                  // Method square:(I)I",
+                    9
+                ),
+                ("5: istore_1", 10),
+            ]
+        );
+        assert_eq!(
+            artifact.lines[4].as_ref().unwrap().file,
+            "src/app/Main.java"
+        );
+    }
+
+    #[test]
+    fn test_validate_options() {
+        let options = |opt_level: &str, target_cpu: &str| ArtifactOptions {
+            kind: ArtifactKind::Asm,
+            opt_level: Some(opt_level.to_string()),
+            target_cpu: Some(target_cpu.to_string()),
+        };
+        assert!(validate_options(&options("3", "x86-64-v3")).is_ok());
+        assert!(matches!(
+            validate_options(&options("fast", "native")),
+            Err(ArtifactError::InvalidOptLevel(_))
+        ));
+        assert!(matches!(
+            validate_options(&options("2", "native -C panic=abort")),
+            Err(ArtifactError::InvalidTargetCpu(_))
+        ));
+    }
+}
@@ -0,0 +1,246 @@
+//! Cargo mode for Rust snippets.
+//!
+//! A snippet opts into Cargo by embedding a manifest, either as cargo-script
+//! frontmatter:
+//!
+//! ```text
+//! ---
+//! [dependencies]
+//! rand = "0.8"
+//! ---
+//! ```
+//!
+//! or as a `//! ```cargo` doc comment block (the `rust-script` style). The
+//! embedded manifest is completed into a scratch `Cargo.toml` next to the
+//! source. Projects that ship their own `Cargo.toml` are built as-is.
+
+use crate::models::Diagnostic;
+use crate::services::diagnostics::parse_rustc_message;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Package name used for generated manifests
+const SNIPPET_PACKAGE: &str = "codecell_snippet";
+
+#[derive(Error, Debug)]
+pub enum CargoError {
+    #[error("Invalid embedded manifest: {0}")]
+    Manifest(#[from] toml::de::Error),
+    #[error("Failed to write manifest: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// Manifest found in a snippet, along with the source rustc should compile
+#[derive(Debug, PartialEq)]
+pub struct EmbeddedManifest {
+    pub manifest: String,
+    /// The source with frontmatter blanked out, keeping line numbers intact
+    pub source: String,
+}
+
+/// Look for an embedded manifest in frontmatter or a `//! ```cargo` block
+pub fn extract_manifest(source: &str) -> Option<EmbeddedManifest> {
+    extract_frontmatter(source).or_else(|| {
+        extract_doc_manifest(source).map(|manifest| EmbeddedManifest {
+            manifest,
+            source: source.to_string(),
+        })
+    })
+}
+
+fn extract_frontmatter(source: &str) -> Option<EmbeddedManifest> {
+    let lines: Vec<&str> = source.lines().collect();
+
+    // An optional shebang (but not an inner attribute) may come first
+    let mut start = usize::from(
+        lines
+            .first()
+            .is_some_and(|l| l.starts_with("#!") && !l.starts_with("#![")),
+    );
+    while lines.get(start).is_some_and(|l| l.trim().is_empty()) {
+        start += 1;
+    }
+
+    // Opening fence, optionally with an info string: `---` or `---cargo`
+    let opening = lines.get(start)?;
+    let info = opening.trim_start_matches('-').trim();
+    let is_fence = opening.starts_with("---")
+        && info
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+    if !is_fence {
+        return None;
+    }
+    let end = start + 1 + lines[start + 1..].iter().position(|l| l.trim() == "---")?;
+
+    let manifest = lines[start + 1..end].join("\n");
+    let source = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| if (start..=end).contains(&i) { "" } else { line })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(EmbeddedManifest { manifest, source })
+}
+
+fn extract_doc_manifest(source: &str) -> Option<String> {
+    let doc_lines = source
+        .lines()
+        .map(str::trim_start)
+        .take_while(|l| l.starts_with("//!") || l.is_empty() || l.starts_with("#!"))
+        .filter_map(|l| l.strip_prefix("//!"))
+        .map(|l| l.strip_prefix(' ').unwrap_or(l));
+
+    let mut manifest = Vec::new();
+    let mut in_block = false;
+    for line in doc_lines {
+        if in_block {
+            if line.trim() == "```" {
+                return Some(manifest.join("\n"));
+            }
+            manifest.push(line);
+        } else if line.trim() == "```cargo" {
+            in_block = true;
+        }
+    }
+    None
+}
+
+/// Complete an embedded manifest into a full `Cargo.toml` for `bin_path`
+pub fn generate_manifest(embedded: &str, bin_path: &str) -> Result<String, CargoError> {
+    let mut manifest: toml::Table = embedded.parse()?;
+
+    let package = manifest
+        .entry("package")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    if let toml::Value::Table(package) = package {
+        package
+            .entry("name")
+            .or_insert_with(|| SNIPPET_PACKAGE.into());
+        package.entry("version").or_insert_with(|| "0.1.0".into());
+        package.entry("edition").or_insert_with(|| "2021".into());
+    }
+
+    let mut bin = toml::Table::new();
+    bin.insert("name".to_string(), SNIPPET_PACKAGE.into());
+    bin.insert("path".to_string(), bin_path.into());
+    manifest.insert(
+        "bin".to_string(),
+        toml::Value::Array(vec![toml::Value::Table(bin)]),
+    );
+
+    // Keep the scratch project out of any workspace the temp dir sits in
+    manifest
+        .entry("workspace")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+
+    Ok(toml::to_string(&manifest)?)
+}
+
+/// What `cargo build --message-format=json` reported
+#[derive(Debug, Default)]
+pub struct BuildOutput {
+    /// Path of the binary that was built
+    pub executable: Option<PathBuf>,
+    /// Human-readable compiler messages, as rustc would print them
+    pub rendered: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Collect the executable and rendered diagnostics from cargo's JSON stream
+pub fn parse_build_output(stdout: &str) -> BuildOutput {
+    let mut output = BuildOutput::default();
+
+    for message in stdout
+        .lines()
+        .filter_map(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+    {
+        match message["reason"].as_str() {
+            Some("compiler-artifact") => {
+                if let Some(executable) = message["executable"].as_str() {
+                    output.executable = Some(PathBuf::from(executable));
+                }
+            }
+            Some("compiler-message") => {
+                if let Some(rendered) = message["message"]["rendered"].as_str() {
+                    output.rendered.push_str(rendered);
+                }
+                output
+                    .diagnostics
+                    .extend(parse_rustc_message(&message["message"]));
+            }
+            _ => {}
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_frontmatter() {
+        let source =
+            "#!/usr/bin/env cargo\n---cargo\n[dependencies]\nrand = \"0.8\"\n---\n\nfn main() {}\n";
+        let embedded = extract_manifest(source).unwrap();
+        assert_eq!(embedded.manifest, "[dependencies]\nrand = \"0.8\"");
+        // Fence lines are blanked, so `fn main` stays on line 7
+        assert_eq!(embedded.source.lines().nth(6), Some("fn main() {}"));
+        assert!(!embedded.source.contains("---"));
+    }
+
+    #[test]
+    fn test_extract_doc_manifest() {
+        let source =
+            "//! ```cargo\n//! [dependencies]\n//! serde = \"1\"\n//! ```\n\nfn main() {}\n";
+        let embedded = extract_manifest(source).unwrap();
+        assert_eq!(embedded.manifest, "[dependencies]\nserde = \"1\"");
+        assert_eq!(embedded.source, source);
+    }
+
+    #[test]
+    fn test_plain_source_has_no_manifest() {
+        assert_eq!(
+            extract_manifest("fn main() {\n    println!(\"---\");\n}\n"),
+            None
+        );
+        assert_eq!(extract_manifest("//! Just docs\nfn main() {}\n"), None);
+    }
+
+    #[test]
+    fn test_generate_manifest() {
+        let manifest = generate_manifest("[dependencies]\nrand = \"0.8\"\n", "main.rs").unwrap();
+        let parsed: toml::Table = manifest.parse().unwrap();
+        assert_eq!(parsed["package"]["name"].as_str(), Some(SNIPPET_PACKAGE));
+        assert_eq!(parsed["package"]["edition"].as_str(), Some("2021"));
+        assert_eq!(parsed["bin"][0]["path"].as_str(), Some("main.rs"));
+        assert_eq!(parsed["dependencies"]["rand"].as_str(), Some("0.8"));
+
+        // Package settings from the snippet win over the defaults
+        let manifest = generate_manifest("[package]\nedition = \"2024\"\n", "main.rs").unwrap();
+        let parsed: toml::Table = manifest.parse().unwrap();
+        assert_eq!(parsed["package"]["edition"].as_str(), Some("2024"));
+    }
+
+    #[test]
+    fn test_parse_build_output() {
+        let stdout = concat!(
+            r#"{"reason":"compiler-message","message":{"rendered":"warning: unused variable\n","message":"unused variable","level":"warning","spans":[{"file_name":"main.rs","line_start":2,"line_end":2,"column_start":9,"column_end":10,"is_primary":true}],"children":[]}}"#,
+            "\n",
+            r#"{"reason":"compiler-artifact","executable":"/tmp/target/debug/codecell_snippet"}"#,
+            "\n",
+            r#"{"reason":"build-finished","success":true}"#,
+        );
+        let output = parse_build_output(stdout);
+        assert_eq!(
+            output.executable,
+            Some(PathBuf::from("/tmp/target/debug/codecell_snippet"))
+        );
+        assert_eq!(output.rendered, "warning: unused variable\n");
+        assert_eq!(output.diagnostics.len(), 1);
+        assert_eq!(output.diagnostics[0].line, 2);
+    }
+}
@@ -0,0 +1,171 @@
+//! Compiling C and C++ with GCC or Clang.
+
+use crate::models::{CcCompiler, CcOptions, ProjectFile};
+use crate::services::RuntimeInfo;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum CcError {
+    #[error("Invalid language standard: {0}")]
+    InvalidStandard(String),
+    #[error("Invalid optimization level: {0} (expected 0, 1, 2, 3, s, z or g)")]
+    InvalidOptimization(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CcLanguage {
+    C,
+    Cpp,
+}
+
+impl CcLanguage {
+    /// Extensions of the source files that get compiled; headers are only
+    /// included
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            CcLanguage::C => &["c"],
+            CcLanguage::Cpp => &["cpp", "cc", "cxx", "c++"],
+        }
+    }
+
+    /// The compiler driver for this language
+    pub fn runtime(self, compiler: CcCompiler) -> RuntimeInfo {
+        match (self, compiler) {
+            (CcLanguage::C, CcCompiler::Gcc) => RuntimeInfo::GCC,
+            (CcLanguage::Cpp, CcCompiler::Gcc) => RuntimeInfo::GXX,
+            (CcLanguage::C, CcCompiler::Clang) => RuntimeInfo::CLANG,
+            (CcLanguage::Cpp, CcCompiler::Clang) => RuntimeInfo::CLANGXX,
+        }
+    }
+}
+
+/// The configured compiler, or else the first of GCC and Clang that
+/// `is_installed`.
+///
+/// Falls back to GCC when neither is installed, so the missing-runtime hint
+/// suggests the usual toolchain.
+pub fn select_compiler(
+    preferred: Option<CcCompiler>,
+    is_installed: impl Fn(CcCompiler) -> bool,
+) -> CcCompiler {
+    preferred
+        .or_else(|| {
+            [CcCompiler::Gcc, CcCompiler::Clang]
+                .into_iter()
+                .find(|&compiler| is_installed(compiler))
+        })
+        .unwrap_or(CcCompiler::Gcc)
+}
+
+/// Arguments compiling every source file in `files` into `output`
+pub fn compiler_args(
+    language: CcLanguage,
+    options: &CcOptions,
+    files: &[ProjectFile],
+    output: &str,
+) -> Result<Vec<String>, CcError> {
+    let mut args = Vec::new();
+    if let Some(standard) = &options.standard {
+        let is_valid = !standard.is_empty()
+            && standard
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '+');
+        if !is_valid {
+            return Err(CcError::InvalidStandard(standard.clone()));
+        }
+        args.push(format!("-std={standard}"));
+    }
+    if let Some(level) = &options.optimization {
+        if !["0", "1", "2", "3", "s", "z", "g"].contains(&level.as_str()) {
+            return Err(CcError::InvalidOptimization(level.clone()));
+        }
+        args.push(format!("-O{level}"));
+    }
+
+    args.extend(
+        files
+            .iter()
+            .filter(|f| {
+                Path::new(&f.name).extension().is_some_and(|ext| {
+                    language
+                        .extensions()
+                        .iter()
+                        .any(|e| ext.eq_ignore_ascii_case(e))
+                })
+            })
+            .map(|f| f.name.clone()),
+    );
+    args.push("-o".to_string());
+    args.push(output.to_string());
+    // Flags go last so libraries (`-lm`) follow the sources that use them
+    args.extend(options.flags.iter().cloned());
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str) -> ProjectFile {
+        ProjectFile {
+            name: name.to_string(),
+            content: String::new(),
+            language: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_select_compiler() {
+        assert_eq!(select_compiler(None, |_| true), CcCompiler::Gcc);
+        assert_eq!(
+            select_compiler(None, |c| c == CcCompiler::Clang),
+            CcCompiler::Clang
+        );
+        assert_eq!(select_compiler(None, |_| false), CcCompiler::Gcc);
+        assert_eq!(
+            select_compiler(Some(CcCompiler::Clang), |_| false),
+            CcCompiler::Clang
+        );
+    }
+
+    #[test]
+    fn test_compiler_args() {
+        let files = [
+            file("main.cpp"),
+            file("util.cc"),
+            file("util.h"),
+            file("notes.c"),
+        ];
+        let options = CcOptions {
+            compiler: None,
+            standard: Some("c++20".to_string()),
+            optimization: Some("2".to_string()),
+            flags: vec!["-Wall".to_string(), "-lm".to_string()],
+        };
+        assert_eq!(
+            compiler_args(CcLanguage::Cpp, &options, &files, "codecell_bin").unwrap(),
+            [
+                "-std=c++20",
+                "-O2",
+                "main.cpp",
+                "util.cc",
+                "-o",
+                "codecell_bin",
+                "-Wall",
+                "-lm"
+            ]
+        );
+
+        let options = CcOptions {
+            standard: Some("gnu 17".to_string()),
+            ..CcOptions::default()
+        };
+        assert!(compiler_args(CcLanguage::C, &options, &files, "out").is_err());
+        let options = CcOptions {
+            optimization: Some("fast".to_string()),
+            ..CcOptions::default()
+        };
+        assert!(compiler_args(CcLanguage::C, &options, &files, "out").is_err());
+    }
+}
@@ -0,0 +1,360 @@
+//! Content-addressed cache of compiled programs.
+//!
+//! Entries are keyed by a hash of the sources, the compiler version and the
+//! compiler flags, so running unchanged code again skips compilation. Each
+//! entry is a directory of build artifacts; once the cache grows past its
+//! size cap the least recently used entries are evicted.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+use thiserror::Error;
+
+/// Default size cap for the whole cache
+pub const DEFAULT_MAX_BYTES: u64 = 512 * 1024 * 1024;
+
+/// File touched on every hit; its modification time orders entries for eviction
+const LAST_USED_FILE: &str = ".last-used";
+
+/// Compiler output stored with the artifacts, so warnings survive a cache hit
+const LOG_FILE: &str = ".compiler-output";
+
+#[derive(Error, Debug)]
+pub enum CacheError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Artifact is outside the build directory: {0}")]
+    InvalidArtifact(String),
+}
+
+/// Builder for a cache key; every part is length-prefixed so that
+/// `("ab", "c")` and `("a", "bc")` hash differently
+pub struct CacheKey {
+    hasher: Sha256,
+}
+
+impl CacheKey {
+    pub fn new(language: &str) -> Self {
+        let mut key = Self {
+            hasher: Sha256::new(),
+        };
+        key.add(language.as_bytes());
+        key
+    }
+
+    pub fn add(&mut self, part: &[u8]) -> &mut Self {
+        self.hasher.update((part.len() as u64).to_le_bytes());
+        self.hasher.update(part);
+        self
+    }
+
+    pub fn finish(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+pub struct CompileCache {
+    root: PathBuf,
+    max_bytes: u64,
+}
+
+impl CompileCache {
+    pub fn new(root: PathBuf, max_bytes: u64) -> Self {
+        Self { root, max_bytes }
+    }
+
+    /// Copy a cached entry's artifacts into `dest`.
+    ///
+    /// Returns the compiler output stored with the entry, or `None` on a
+    /// cache miss. Artifacts are copied rather than run in place, so evicting
+    /// an entry never pulls a binary out from under a running program.
+    pub fn restore(&self, key: &str, dest: &Path) -> Result<Option<Vec<u8>>, CacheError> {
+        let entry = self.root.join(key);
+        if !entry.is_dir() {
+            return Ok(None);
+        }
+
+        copy_tree(&entry, dest)?;
+        touch(&entry.join(LAST_USED_FILE))?;
+        Ok(Some(fs::read(entry.join(LOG_FILE)).unwrap_or_default()))
+    }
+
+    /// Store the given artifacts (paths relative to `build_dir`) and the
+    /// compiler's output under `key`, then evict old entries if the cache is
+    /// over its size cap
+    pub fn store(
+        &self,
+        key: &str,
+        build_dir: &Path,
+        artifacts: &[PathBuf],
+        log: &[u8],
+    ) -> Result<(), CacheError> {
+        fs::create_dir_all(&self.root)?;
+
+        // Build the entry under a temporary name so readers never see a
+        // half-written entry
+        let staging = self
+            .root
+            .join(format!(".{}-{}", key, uuid::Uuid::new_v4().simple()));
+        let result = stage_artifacts(&staging, build_dir, artifacts).and_then(|()| {
+            fs::write(staging.join(LOG_FILE), log)?;
+            touch(&staging.join(LAST_USED_FILE))?;
+            Ok(fs::rename(&staging, self.root.join(key))?)
+        });
+        if let Err(e) = result {
+            let _ = fs::remove_dir_all(&staging);
+            // Losing the race to another run storing the same key is fine
+            if !self.root.join(key).is_dir() {
+                return Err(e);
+            }
+        }
+
+        self.evict()
+    }
+
+    /// Remove least recently used entries until the cache fits its size cap
+    fn evict(&self) -> Result<(), CacheError> {
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&self.root)?.flatten() {
+            let path = entry.path();
+            let is_staging = entry.file_name().to_string_lossy().starts_with('.');
+            if is_staging || !path.is_dir() {
+                continue;
+            }
+            let last_used = fs::metadata(path.join(LAST_USED_FILE))
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            entries.push((last_used, dir_size(&path), path));
+        }
+
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(last_used, _, _)| *last_used);
+        for (_, size, path) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_dir_all(&path).is_ok() {
+                total = total.saturating_sub(size);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compiler version output seen this session, keyed by [`version_key`]
+static VERSIONS: OnceLock<Mutex<HashMap<String, Vec<u8>>>> = OnceLock::new();
+
+/// Identify a compiler's version query by its full path and modification
+/// time, so an upgraded compiler is asked again.
+///
+/// `path_var` is the `PATH` the compiler runs with; `parts` are anything else
+/// its output depends on, such as the version flag and environment. Returns
+/// `None` when the compiler can't be found.
+pub fn version_key<'a>(
+    program: &Path,
+    path_var: Option<&OsStr>,
+    parts: impl IntoIterator<Item = &'a [u8]>,
+) -> Option<String> {
+    let path = find_program(program, path_var)?;
+    let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok()?;
+    let since_epoch = modified
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let mut key = CacheKey::new(&path.to_string_lossy());
+    key.add(&since_epoch.as_nanos().to_le_bytes());
+    for part in parts {
+        key.add(part);
+    }
+    Some(key.finish())
+}
+
+/// Version output stored by [`remember_version`] earlier in the session
+pub fn cached_version(key: &str) -> Option<Vec<u8>> {
+    let versions = VERSIONS.get_or_init(Mutex::default).lock().ok()?;
+    versions.get(key).cloned()
+}
+
+pub fn remember_version(key: String, version: Vec<u8>) {
+    if let Ok(mut versions) = VERSIONS.get_or_init(Mutex::default).lock() {
+        versions.insert(key, version);
+    }
+}
+
+/// The file `program` runs: itself when it is a path, else the first match
+/// in `path_var`
+fn find_program(program: &Path, path_var: Option<&OsStr>) -> Option<PathBuf> {
+    if program.components().count() > 1 {
+        return fs::canonicalize(program).ok();
+    }
+    let exe = program.with_extension(std::env::consts::EXE_EXTENSION);
+    std::env::split_paths(path_var?)
+        .flat_map(|dir| [dir.join(program), dir.join(&exe)])
+        .find(|candidate| candidate.is_file())
+        .and_then(|found| fs::canonicalize(found).ok())
+}
+
+fn stage_artifacts(
+    staging: &Path,
+    build_dir: &Path,
+    artifacts: &[PathBuf],
+) -> Result<(), CacheError> {
+    for artifact in artifacts {
+        let relative = artifact
+            .strip_prefix(build_dir)
+            .map_err(|_| CacheError::InvalidArtifact(artifact.display().to_string()))?;
+        let target = staging.join(relative);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(artifact, target)?;
+    }
+    Ok(())
+}
+
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    for entry in fs::read_dir(from)?.flatten() {
+        let name = entry.file_name();
+        if name == LAST_USED_FILE || name == LOG_FILE {
+            continue;
+        }
+        let target = to.join(&name);
+        if entry.file_type()?.is_dir() {
+            fs::create_dir_all(&target)?;
+            copy_tree(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+fn dir_size(path: &Path) -> u64 {
+    fs::read_dir(path)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn touch(path: &Path) -> std::io::Result<()> {
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path)?;
+    file.set_modified(SystemTime::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!(
+            "codecell_cache_test_{}_{}",
+            name,
+            uuid::Uuid::new_v4().simple()
+        ));
+        fs::create_dir_all(&root).unwrap();
+        root
+    }
+
+    #[test]
+    fn test_cache_key_separates_parts() {
+        let mut a = CacheKey::new("rust");
+        a.add(b"ab").add(b"c");
+        let mut b = CacheKey::new("rust");
+        b.add(b"a").add(b"bc");
+        assert_ne!(a.finish(), b.finish());
+    }
+
+    #[test]
+    fn test_store_and_restore() {
+        let root = temp_root("roundtrip");
+        let build = root.join("build");
+        fs::create_dir_all(build.join("pkg")).unwrap();
+        fs::write(build.join("pkg/Main.class"), "bytes").unwrap();
+
+        let cache = CompileCache::new(root.join("cache"), DEFAULT_MAX_BYTES);
+        let dest = root.join("dest");
+        fs::create_dir_all(&dest).unwrap();
+        assert_eq!(cache.restore("k", &dest).unwrap(), None);
+
+        cache
+            .store("k", &build, &[build.join("pkg/Main.class")], b"warning")
+            .unwrap();
+        assert_eq!(
+            cache.restore("k", &dest).unwrap().as_deref(),
+            Some(&b"warning"[..])
+        );
+        assert_eq!(
+            fs::read_to_string(dest.join("pkg/Main.class")).unwrap(),
+            "bytes"
+        );
+        assert!(!dest.join(LAST_USED_FILE).exists());
+        assert!(!dest.join(LOG_FILE).exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let root = temp_root("evict");
+        let build = root.join("build");
+        fs::create_dir_all(&build).unwrap();
+        fs::write(build.join("bin"), [0u8; 100]).unwrap();
+        let artifacts = [build.join("bin")];
+
+        // Room for two 100-byte entries
+        let cache = CompileCache::new(root.join("cache"), 250);
+        cache.store("old", &build, &artifacts, b"").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        cache.store("used", &build, &artifacts, b"").unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+
+        // Using "old" again makes "used" the eviction candidate
+        assert!(cache.restore("old", &build).unwrap().is_some());
+        std::thread::sleep(Duration::from_millis(20));
+        cache.store("new", &build, &artifacts, b"").unwrap();
+
+        assert!(root.join("cache/old").exists());
+        assert!(!root.join("cache/used").exists());
+        assert!(root.join("cache/new").exists());
+
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn test_version_key_follows_compiler_mtime() {
+        let root = temp_root("version");
+        let compiler = root.join("cc");
+        fs::write(&compiler, "").unwrap();
+        let path_var = root.as_os_str();
+
+        let key = version_key(Path::new("cc"), Some(path_var), [&b"--version"[..]]).unwrap();
+        assert_eq!(
+            version_key(&compiler, None, [&b"--version"[..]]).as_deref(),
+            Some(key.as_str())
+        );
+        assert_eq!(version_key(Path::new("missing"), Some(path_var), []), None);
+
+        let file = fs::File::options().write(true).open(&compiler).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let upgraded = version_key(Path::new("cc"), Some(path_var), [&b"--version"[..]]);
+        assert_ne!(upgraded.as_deref(), Some(key.as_str()));
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,78 @@
+//! Per-project directories of installed dependencies.
+//!
+//! Python venvs and `node_modules` trees outlive a single run so that
+//! packages are installed once per project. Each directory records a
+//! fingerprint of the dependencies it was installed from; a changed
+//! fingerprint means the install has to run again.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Written into the directory after a successful install
+const FINGERPRINT_FILE: &str = ".codecell-installed";
+
+pub struct DependencyEnv {
+    root: PathBuf,
+}
+
+impl DependencyEnv {
+    /// The directory for `project_id` inside `parent`
+    pub fn new(parent: &Path, project_id: &str) -> Self {
+        let name: String = project_id
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        Self {
+            root: parent.join(name),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// Whether the dependencies with this fingerprint are already installed
+    pub fn is_current(&self, fingerprint: &str) -> bool {
+        fs::read_to_string(self.root.join(FINGERPRINT_FILE))
+            .is_ok_and(|installed| installed == fingerprint)
+    }
+
+    /// Record that the dependencies with this fingerprint were installed
+    pub fn mark_installed(&self, fingerprint: &str) -> std::io::Result<()> {
+        fs::write(self.root.join(FINGERPRINT_FILE), fingerprint)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name_is_sanitized() {
+        let env = DependencyEnv::new(Path::new("/data/venvs"), "../abc def");
+        assert_eq!(env.path(), Path::new("/data/venvs/___abc_def"));
+    }
+
+    #[test]
+    fn test_fingerprint_roundtrip() {
+        let parent = std::env::temp_dir().join(format!(
+            "codecell_env_test_{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let env = DependencyEnv::new(&parent, "project");
+        fs::create_dir_all(env.path()).unwrap();
+
+        assert!(!env.is_current("abc"));
+        env.mark_installed("abc").unwrap();
+        assert!(env.is_current("abc"));
+        assert!(!env.is_current("def"));
+
+        fs::remove_dir_all(parent).unwrap();
+    }
+}
@@ -0,0 +1,697 @@
+//! Parsing of compiler output into [`Diagnostic`]s.
+//!
+//! rustc (and cargo) report diagnostics as JSON with `--error-format=json`;
+//! javac, tsc, the Go compiler, GCC, Clang, kotlinc, Scala, Python and mypy
+//! are parsed from their standard text formats.
+
+use crate::models::{Diagnostic, DiagnosticSpan, ProjectFile, Severity};
+
+/// Diagnostics parsed from a compiler run, along with the human-readable
+/// text to show in the output panel
+#[derive(Debug, Default)]
+pub struct CompilerOutput {
+    pub diagnostics: Vec<Diagnostic>,
+    pub rendered: String,
+}
+
+/// Parse rustc's `--error-format=json` stderr.
+///
+/// Lines that aren't JSON (linker errors, ICE backtraces) are kept in the
+/// rendered text as-is.
+pub fn parse_rustc_json(stderr: &str) -> CompilerOutput {
+    let mut output = CompilerOutput::default();
+    for line in stderr.lines() {
+        match serde_json::from_str::<serde_json::Value>(line) {
+            Ok(message) if message.is_object() => {
+                if let Some(rendered) = message["rendered"].as_str() {
+                    output.rendered.push_str(rendered);
+                }
+                output.diagnostics.extend(parse_rustc_message(&message));
+            }
+            _ => {
+                output.rendered.push_str(line);
+                output.rendered.push('\n');
+            }
+        }
+    }
+    output
+}
+
+/// Convert one rustc JSON diagnostic, as found in rustc's stderr and in
+/// cargo's `compiler-message` events.
+///
+/// Messages without a primary span, like "aborting due to previous error",
+/// can't be placed in a file and yield `None`.
+pub fn parse_rustc_message(message: &serde_json::Value) -> Option<Diagnostic> {
+    let severity = match message["level"].as_str()? {
+        "warning" => Severity::Warning,
+        "note" => Severity::Note,
+        "help" => Severity::Help,
+        // "error" and "error: internal compiler error"
+        level if level.starts_with("error") => Severity::Error,
+        _ => return None,
+    };
+
+    let spans: Vec<DiagnosticSpan> = message["spans"]
+        .as_array()?
+        .iter()
+        .filter_map(|span| {
+            let position = |key: &str| u32::try_from(span[key].as_u64()?).ok();
+            Some(DiagnosticSpan {
+                file: span["file_name"].as_str()?.to_string(),
+                line_start: position("line_start")?,
+                column_start: position("column_start")?,
+                line_end: position("line_end")?,
+                column_end: position("column_end")?,
+                label: span["label"].as_str().map(str::to_string),
+                primary: span["is_primary"].as_bool().unwrap_or(false),
+            })
+        })
+        .collect();
+    let primary = spans.iter().find(|s| s.primary)?;
+
+    // Notes and help attached to the diagnostic read best as part of its message
+    let mut text = message["message"].as_str()?.to_string();
+    for child in message["children"].as_array().into_iter().flatten() {
+        if let (Some(level), Some(child_message)) =
+            (child["level"].as_str(), child["message"].as_str())
+        {
+            text.push('\n');
+            text.push_str(level);
+            text.push_str(": ");
+            text.push_str(child_message);
+        }
+    }
+
+    Some(Diagnostic {
+        file: primary.file.clone(),
+        line: primary.line_start,
+        column: primary.column_start,
+        severity,
+        code: message["code"]["code"].as_str().map(str::to_string),
+        message: text,
+        spans,
+    })
+}
+
+/// Parse javac's standard output format:
+///
+/// ```text
+/// Main.java:3: error: cannot find symbol
+///         System.out.println(x);
+///                            ^
+///   symbol:   variable x
+///   location: class Main
+/// ```
+pub fn parse_javac(stderr: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    // Lines after a header: the source line, then the caret, then details
+    let mut lines_since_header = None;
+
+    for line in stderr.lines() {
+        if let Some(diagnostic) = parse_javac_header(line) {
+            diagnostics.push(diagnostic);
+            lines_since_header = Some(0);
+            continue;
+        }
+        let (Some(count), Some(diagnostic)) = (lines_since_header.as_mut(), diagnostics.last_mut())
+        else {
+            continue;
+        };
+        *count += 1;
+
+        if let Some(caret) = line.find('^').filter(|_| line.trim() == "^") {
+            let column = u32::try_from(caret + 1).unwrap_or(1);
+            diagnostic.column = column;
+            diagnostic.spans[0].column_start = column;
+            diagnostic.spans[0].column_end = column + 1;
+        } else if *count > 1 && line.starts_with("  ") {
+            diagnostic.message.push('\n');
+            diagnostic.message.push_str(line.trim());
+        } else if *count > 1 {
+            // "1 error", "Note: ..." and anything else ends the diagnostic
+            lines_since_header = None;
+        }
+    }
+    diagnostics
+}
+
+fn parse_javac_header(line: &str) -> Option<Diagnostic> {
+    let (file, rest) = line.split_once(".java:")?;
+    let (line_number, rest) = rest.split_once(": ")?;
+    let line_number: u32 = line_number.parse().ok()?;
+    let (severity, message) = if let Some(message) = rest.strip_prefix("error: ") {
+        (Severity::Error, message)
+    } else if let Some(message) = rest.strip_prefix("warning: ") {
+        (Severity::Warning, message)
+    } else {
+        return None;
+    };
+
+    // The column comes from the caret line that follows
+    Some(point_diagnostic(
+        format!("{file}.java"),
+        line_number,
+        1,
+        severity,
+        None,
+        message.to_string(),
+    ))
+}
+
+/// Parse tsc's `--pretty false` output:
+///
+/// ```text
+/// index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.
+/// ```
+///
+/// Indented lines that follow continue the previous message.
+pub fn parse_tsc(stdout: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for line in stdout.lines() {
+        if let Some(diagnostic) = parse_tsc_line(line) {
+            diagnostics.push(diagnostic);
+        } else if let Some(diagnostic) = diagnostics.last_mut().filter(|_| line.starts_with(' ')) {
+            diagnostic.message.push('\n');
+            diagnostic.message.push_str(line.trim());
+        }
+    }
+    diagnostics
+}
+
+fn parse_tsc_line(line: &str) -> Option<Diagnostic> {
+    let (location, rest) = line.split_once("): ")?;
+    let (file, position) = location.rsplit_once('(')?;
+    let (line_number, column) = position.split_once(',')?;
+    let (line_number, column): (u32, u32) = (line_number.parse().ok()?, column.parse().ok()?);
+
+    let (severity, rest) = if let Some(rest) = rest.strip_prefix("error ") {
+        (Severity::Error, rest)
+    } else if let Some(rest) = rest.strip_prefix("warning ") {
+        (Severity::Warning, rest)
+    } else {
+        return None;
+    };
+    let (code, message) = rest.split_once(": ")?;
+
+    Some(point_diagnostic(
+        file.to_string(),
+        line_number,
+        column,
+        severity,
+        Some(code.to_string()),
+        message.to_string(),
+    ))
+}
+
+/// Parse `go build` output:
+///
+/// ```text
+/// # codecell
+/// ./main.go:6:2: declared and not used: x
+/// ./main.go:7:9: cannot use "a" (untyped string constant) as int value in return statement
+/// ```
+///
+/// Go has no warnings, so every diagnostic is an error. Tab-indented lines
+/// continue the previous message.
+pub fn parse_go(stderr: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for line in stderr.lines() {
+        if let Some(diagnostic) = parse_go_line(line) {
+            diagnostics.push(diagnostic);
+        } else if let Some(diagnostic) = diagnostics.last_mut().filter(|_| line.starts_with('\t')) {
+            diagnostic.message.push('\n');
+            diagnostic.message.push_str(line.trim());
+        }
+    }
+    diagnostics
+}
+
+fn parse_go_line(line: &str) -> Option<Diagnostic> {
+    let line = line.strip_prefix("./").unwrap_or(line);
+    let (file, rest) = line.split_once(".go:")?;
+    let (position, message) = rest.split_once(": ")?;
+    // The column is missing from some errors, such as import cycles
+    let (line_number, column) = match position.split_once(':') {
+        Some((line_number, column)) => (line_number.parse().ok()?, column.parse().ok()?),
+        None => (position.parse().ok()?, 1),
+    };
+
+    Some(point_diagnostic(
+        format!("{file}.go"),
+        line_number,
+        column,
+        Severity::Error,
+        None,
+        message.to_string(),
+    ))
+}
+
+/// Parse GCC and Clang output:
+///
+/// ```text
+/// main.c: In function 'main':
+/// main.c:4:9: warning: unused variable 'y' [-Wunused-variable]
+///     4 |     int y;
+///       |         ^
+/// main.c:5:5: error: 'x' undeclared (first use in this function)
+/// ```
+///
+/// Notes become diagnostics of their own, since they point elsewhere than
+/// the error they explain. Source excerpts and linker errors are skipped.
+/// kotlinc reports in the same format.
+pub fn parse_gcc(stderr: &str) -> Vec<Diagnostic> {
+    stderr.lines().filter_map(parse_gcc_line).collect()
+}
+
+fn parse_gcc_line(line: &str) -> Option<Diagnostic> {
+    let (location, rest) = line.split_once(": ")?;
+    let mut parts = location.rsplitn(3, ':');
+    let column: u32 = parts.next()?.parse().ok()?;
+    let line_number: u32 = parts.next()?.parse().ok()?;
+    let file = parts.next()?;
+    let file = file.strip_prefix("./").unwrap_or(file);
+
+    let (severity, message) = match rest.split_once(": ")? {
+        ("error" | "fatal error", message) => (Severity::Error, message),
+        ("warning", message) => (Severity::Warning, message),
+        ("note", message) => (Severity::Note, message),
+        _ => return None,
+    };
+    // Warnings end with the flag that controls them
+    let (message, code) = match message.strip_suffix(']').and_then(|m| m.rsplit_once(" [")) {
+        Some((message, flag)) if flag.starts_with("-W") => (message, Some(flag.to_string())),
+        _ => (message, None),
+    };
+
+    Some(point_diagnostic(
+        file.to_string(),
+        line_number,
+        column,
+        severity,
+        code,
+        message.to_string(),
+    ))
+}
+
+/// Parse Scala 3's output, from scalac or Scala CLI:
+///
+/// ```text
+/// -- [E006] Not Found Error: main.scala:3:10 ---------------------------
+/// 3 |  println(x)
+///   |          ^
+///   |          Not found: x
+/// [error] ./main.scala:3:10
+/// [error] Not found: x
+/// ```
+///
+/// The message is the first line of text after the location.
+pub fn parse_scala(output: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    // Whether the last diagnostic is still waiting for its message
+    let mut pending = false;
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix("-- ") {
+            pending = false;
+            if let Some(diagnostic) = parse_scala_header(header) {
+                diagnostics.push(diagnostic);
+                pending = true;
+            }
+            continue;
+        }
+
+        // Scala CLI prefixes every line with its level; scalac puts messages
+        // in a margin after the source excerpt
+        let text = match line.split_once("] ") {
+            Some(("[error" | "[warn", text)) => {
+                if let Some((file, line_number, column)) = scala_location(text) {
+                    let severity = if line.starts_with("[warn") {
+                        Severity::Warning
+                    } else {
+                        Severity::Error
+                    };
+                    diagnostics.push(point_diagnostic(
+                        file,
+                        line_number,
+                        column,
+                        severity,
+                        None,
+                        String::new(),
+                    ));
+                    pending = true;
+                    continue;
+                }
+                Some(text.trim())
+            }
+            _ => line.trim().strip_prefix('|').map(str::trim),
+        };
+        let Some(text) = text.filter(|t| !t.is_empty() && !t.chars().all(|c| c == '^')) else {
+            continue;
+        };
+        if let Some(diagnostic) = diagnostics.last_mut().filter(|_| pending) {
+            diagnostic.message = text.to_string();
+            pending = false;
+        }
+    }
+    diagnostics
+}
+
+/// A header like `[E006] Not Found Error: main.scala:3:10 -----`, whose
+/// title stands in as the message until the real one is read
+fn parse_scala_header(header: &str) -> Option<Diagnostic> {
+    let header = header.trim_end_matches('-').trim_end();
+    let (title, location) = header.rsplit_once(": ")?;
+    let (file, line_number, column) = scala_location(location)?;
+    let (code, title) = match title.strip_prefix('[').and_then(|t| t.split_once("] ")) {
+        Some((code, title)) => (Some(code.to_string()), title),
+        None => (None, title),
+    };
+    let severity = if title.ends_with("Warning") {
+        Severity::Warning
+    } else if title.ends_with("Error") {
+        Severity::Error
+    } else {
+        Severity::Note
+    };
+    Some(point_diagnostic(
+        file,
+        line_number,
+        column,
+        severity,
+        code,
+        title.to_string(),
+    ))
+}
+
+/// A `file:line:column` location
+fn scala_location(text: &str) -> Option<(String, u32, u32)> {
+    let mut parts = text.trim().rsplitn(3, ':');
+    let column = parts.next()?.parse().ok()?;
+    let line_number = parts.next()?.parse().ok()?;
+    let file = parts.next().filter(|f| !f.is_empty() && !f.contains(' '))?;
+    let file = file.strip_prefix("./").unwrap_or(file);
+    Some((file.to_string(), line_number, column))
+}
+
+/// Parse the syntax errors `python -m py_compile` reports, in either of its
+/// formats:
+///
+/// ```text
+///   File "main.py", line 3
+///     def f(:
+///           ^
+/// SyntaxError: invalid syntax
+/// Sorry: IndentationError: unexpected indent (util.py, line 2)
+/// ```
+///
+/// Python prints the offending line without its indentation, so the caret's
+/// column is corrected with the line from `files`.
+pub fn parse_python(stderr: &str, files: &[ProjectFile]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut location: Option<(&str, u32)> = None;
+    let mut column = 1;
+    for line in stderr.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("File \"") {
+            location = rest
+                .split_once("\", line ")
+                .and_then(|(file, rest)| Some((file, rest.split(',').next()?.parse().ok()?)));
+            column = 1;
+        } else if let Some((file, line_number)) = location.filter(|_| trimmed.starts_with('^')) {
+            let caret = line.find('^').unwrap_or(0).saturating_sub(4);
+            column = u32::try_from(caret + indentation(files, file, line_number) + 1).unwrap_or(1);
+        } else if let Some((name, message)) = parse_python_error(line) {
+            // The one-line format ends with the location instead
+            let located = message
+                .strip_suffix(')')
+                .and_then(|m| m.rsplit_once(" ("))
+                .and_then(|(message, rest)| {
+                    let (file, line_number) = rest.split_once(", line ")?;
+                    Some((message, (file, line_number.parse().ok()?), 1))
+                });
+            let (message, (file, line_number), column) = match located {
+                Some(located) => located,
+                None => match location.take() {
+                    Some(location) => (message, location, column),
+                    None => continue,
+                },
+            };
+            let file = file.strip_prefix("./").unwrap_or(file);
+            diagnostics.push(point_diagnostic(
+                file.to_string(),
+                line_number,
+                column,
+                Severity::Error,
+                Some(name.to_string()),
+                message.to_string(),
+            ));
+        }
+    }
+    diagnostics
+}
+
+/// The exception name and message of a line like `SyntaxError: invalid syntax`
+fn parse_python_error(line: &str) -> Option<(&str, &str)> {
+    let line = line.strip_prefix("Sorry: ").unwrap_or(line);
+    let (name, message) = line.split_once(": ")?;
+    let is_name = name.ends_with("Error") && name.chars().all(|c| c.is_ascii_alphanumeric());
+    is_name.then_some((name, message))
+}
+
+/// Width of the leading whitespace of a line in a project file
+fn indentation(files: &[ProjectFile], file: &str, line: u32) -> usize {
+    let file = file.strip_prefix("./").unwrap_or(file);
+    files
+        .iter()
+        .find(|f| f.name == file)
+        .and_then(|f| f.content.lines().nth(line.checked_sub(1)? as usize))
+        .map_or(0, |text| text.len() - text.trim_start().len())
+}
+
+/// Parse mypy's output with `--show-column-numbers`:
+///
+/// ```text
+/// main.py:4:12: error: Incompatible return value type (got "str", expected "int")  [return-value]
+/// ```
+///
+/// It is GCC's format, with the error code after the message.
+pub fn parse_mypy(stdout: &str) -> Vec<Diagnostic> {
+    stdout
+        .lines()
+        .filter_map(|line| {
+            let mut diagnostic = parse_gcc_line(line)?;
+            let coded = diagnostic
+                .message
+                .strip_suffix(']')
+                .and_then(|m| m.rsplit_once("  ["))
+                .map(|(message, code)| (message.to_string(), code.to_string()));
+            if let Some((message, code)) = coded {
+                diagnostic.message = message;
+                diagnostic.code = Some(code);
+            }
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// A diagnostic located at a single position
+fn point_diagnostic(
+    file: String,
+    line: u32,
+    column: u32,
+    severity: Severity,
+    code: Option<String>,
+    message: String,
+) -> Diagnostic {
+    Diagnostic {
+        file: file.clone(),
+        line,
+        column,
+        severity,
+        code,
+        message,
+        spans: vec![DiagnosticSpan {
+            file,
+            line_start: line,
+            column_start: column,
+            line_end: line,
+            column_end: column + 1,
+            label: None,
+            primary: true,
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rustc_json() {
+        let stderr = concat!(
+            r#"{"$message_type":"diagnostic","message":"mismatched types","code":{"code":"E0308","explanation":"..."},"level":"error","spans":[{"file_name":"main.rs","byte_start":40,"byte_end":43,"line_start":4,"line_end":4,"column_start":24,"column_end":27,"is_primary":true,"text":[],"label":"expected `u8`, found `&str`"},{"file_name":"main.rs","byte_start":34,"byte_end":36,"line_start":4,"line_end":4,"column_start":18,"column_end":20,"is_primary":false,"text":[],"label":"expected due to this"}],"children":[],"rendered":"error[E0308]: mismatched types\n"}"#,
+            "\n",
+            r#"{"$message_type":"diagnostic","message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error\n"}"#,
+            "\n",
+            "error: linking with `cc` failed\n",
+        );
+        let output = parse_rustc_json(stderr);
+
+        assert_eq!(output.diagnostics.len(), 1);
+        let diagnostic = &output.diagnostics[0];
+        assert_eq!(diagnostic.file, "main.rs");
+        assert_eq!((diagnostic.line, diagnostic.column), (4, 24));
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(diagnostic.code.as_deref(), Some("E0308"));
+        assert_eq!(diagnostic.spans.len(), 2);
+
+        assert_eq!(
+            output.rendered,
+            "error[E0308]: mismatched types\nerror: aborting due to 1 previous error\nerror: linking with `cc` failed\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_rustc_children() {
+        let message: serde_json::Value = serde_json::from_str(
+            r#"{"message":"unused variable: `x`","code":{"code":"unused_variables"},"level":"warning","spans":[{"file_name":"main.rs","line_start":2,"line_end":2,"column_start":9,"column_end":10,"is_primary":true,"label":null}],"children":[{"message":"if this is intentional, prefix it with an underscore: `_x`","level":"help","spans":[],"children":[]}]}"#,
+        )
+        .unwrap();
+        let diagnostic = parse_rustc_message(&message).unwrap();
+        assert_eq!(diagnostic.severity, Severity::Warning);
+        assert_eq!(
+            diagnostic.message,
+            "unused variable: `x`\nhelp: if this is intentional, prefix it with an underscore: `_x`"
+        );
+    }
+
+    #[test]
+    fn test_parse_javac() {
+        let stderr = "Main.java:3: error: cannot find symbol\n        System.out.println(x);\n                           ^\n  symbol:   variable x\n  location: class Main\nutil/Helper.java:7: warning: [removal] Integer(int) in Integer has been deprecated\n        Integer i = new Integer(1);\n                    ^\n1 error\n1 warning\n";
+        let diagnostics = parse_javac(stderr);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file, "Main.java");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 28));
+        assert_eq!(
+            diagnostics[0].message,
+            "cannot find symbol\nsymbol:   variable x\nlocation: class Main"
+        );
+        assert_eq!(diagnostics[1].file, "util/Helper.java");
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert_eq!((diagnostics[1].line, diagnostics[1].column), (7, 21));
+    }
+
+    #[test]
+    fn test_parse_tsc() {
+        let stdout = "index.ts(3,7): error TS2322: Type 'string' is not assignable to type 'number'.\nsrc/util.ts(10,1): error TS2345: Argument of type 'A' is not assignable to parameter of type 'B'.\n  Property 'x' is missing in type 'A'.\n";
+        let diagnostics = parse_tsc(stdout);
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file, "index.ts");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 7));
+        assert_eq!(diagnostics[0].code.as_deref(), Some("TS2322"));
+        assert_eq!(diagnostics[1].file, "src/util.ts");
+        assert!(diagnostics[1]
+            .message
+            .ends_with("\nProperty 'x' is missing in type 'A'."));
+    }
+
+    #[test]
+    fn test_parse_go() {
+        let stderr = "# codecell\n./main.go:6:2: declared and not used: x\n./util/strings.go:12:9: cannot use s (variable of type string) as int value in return statement\n./main.go:9:14: too many arguments in call to add\n\thave (number, number, number)\n\twant (int, int)\ngo: downloading example.com/x v1.0.0\n";
+        let diagnostics = parse_go(stderr);
+
+        assert_eq!(diagnostics.len(), 3);
+        assert_eq!(diagnostics[0].file, "main.go");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (6, 2));
+        assert_eq!(diagnostics[0].message, "declared and not used: x");
+        assert_eq!(diagnostics[1].file, "util/strings.go");
+        assert_eq!(
+            diagnostics[2].message,
+            "too many arguments in call to add\nhave (number, number, number)\nwant (int, int)"
+        );
+    }
+
+    #[test]
+    fn test_parse_gcc() {
+        let stderr = "main.c: In function 'main':\nmain.c:4:9: warning: unused variable 'y' [-Wunused-variable]\n    4 |     int y;\n      |         ^\nmain.c:5:5: error: 'x' undeclared (first use in this function)\nmain.c:5:5: note: each undeclared identifier is reported only once for each function it appears in\n./lib/util.h:2:10: fatal error: missing.h: No such file or directory\n/usr/bin/ld: main.o: undefined reference to `foo'\n";
+        let diagnostics = parse_gcc(stderr);
+
+        assert_eq!(diagnostics.len(), 4);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("-Wunused-variable"));
+        assert_eq!(diagnostics[0].message, "unused variable 'y'");
+        assert_eq!((diagnostics[1].line, diagnostics[1].column), (5, 5));
+        assert_eq!(diagnostics[2].severity, Severity::Note);
+        assert_eq!(diagnostics[3].file, "lib/util.h");
+        assert_eq!(
+            diagnostics[3].message,
+            "missing.h: No such file or directory"
+        );
+
+        let kotlinc = "main.kt:3:5: error: unresolved reference 'greet'.\nmain.kt:2:9: warning: variable 'x' is never used\n";
+        let diagnostics = parse_gcc(kotlinc);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].message, "unresolved reference 'greet'.");
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_parse_scala() {
+        let scalac = "-- [E006] Not Found Error: main.scala:3:10 ---------------------------\n3 |  println(x)\n  |          ^\n  |          Not found: x\n  |\n  | longer explanation available when compiling with `-explain`\n-- Warning: util/Strings.scala:5:2 -----------------------------------\n5 |  val unused = 1\n  |  ^^^^^^^^^^^^^^\n  |  unused local definition\n1 warning found\n1 error found\n";
+        let diagnostics = parse_scala(scalac);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].file, "main.scala");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (3, 10));
+        assert_eq!(diagnostics[0].code.as_deref(), Some("E006"));
+        assert_eq!(diagnostics[0].message, "Not found: x");
+        assert_eq!(diagnostics[1].file, "util/Strings.scala");
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+        assert_eq!(diagnostics[1].message, "unused local definition");
+
+        let scala_cli = "Compiling project (Scala 3.3.1, JVM (17))\n[error] ./main.scala:3:10\n[error] Not found: x\n[error]   println(x)\n[error]           ^\nError compiling project (Scala 3.3.1, JVM (17))\n";
+        let diagnostics = parse_scala(scala_cli);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, "main.scala");
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[0].message, "Not found: x");
+    }
+
+    #[test]
+    fn test_parse_python() {
+        let files = [ProjectFile {
+            name: "main.py".to_string(),
+            content: "def f():\n    return (1,\n\nif x:\nprint(1)\n".to_string(),
+            language: String::new(),
+        }];
+        let stderr = "  File \"main.py\", line 2\n    return (1,\n           ^\nSyntaxError: '(' was never closed\n";
+        let diagnostics = parse_python(stderr, &files);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (2, 12));
+        assert_eq!(diagnostics[0].code.as_deref(), Some("SyntaxError"));
+        assert_eq!(diagnostics[0].message, "'(' was never closed");
+
+        let stderr = "Sorry: IndentationError: expected an indented block after 'if' statement on line 4 (main.py, line 5)";
+        let diagnostics = parse_python(stderr, &files);
+        assert_eq!(diagnostics[0].file, "main.py");
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (5, 1));
+        assert_eq!(
+            diagnostics[0].message,
+            "expected an indented block after 'if' statement on line 4"
+        );
+    }
+
+    #[test]
+    fn test_parse_mypy() {
+        let stdout = "main.py:4:12: error: Incompatible return value type (got \"str\", expected \"int\")  [return-value]\nmain.py:7:1: note: Revealed type is \"builtins.int\"\n";
+        let diagnostics = parse_mypy(stdout);
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("return-value"));
+        assert_eq!(
+            diagnostics[0].message,
+            "Incompatible return value type (got \"str\", expected \"int\")"
+        );
+        assert_eq!(diagnostics[1].severity, Severity::Note);
+    }
+}
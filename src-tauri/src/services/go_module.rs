@@ -0,0 +1,92 @@
+//! Per-project Go modules.
+//!
+//! `go build` needs a module. Projects without their own `go.mod` get one
+//! from `go mod init`, which is kept per project along with its `go.sum`, so
+//! third-party modules resolved on one run stay pinned for the next.
+
+use crate::services::dependency_env::DependencyEnv;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub const GO_MOD: &str = "go.mod";
+pub const GO_SUM: &str = "go.sum";
+
+/// Module path of generated modules; packages in project subdirectories are
+/// imported as `codecell/<dir>`
+pub const MODULE_PATH: &str = "codecell";
+
+/// A project's saved `go.mod` and `go.sum`
+pub struct GoModule {
+    env: DependencyEnv,
+}
+
+impl GoModule {
+    /// The module for `project_id` inside `modules_dir`
+    pub fn new(modules_dir: &Path, project_id: &str) -> Self {
+        Self {
+            env: DependencyEnv::new(modules_dir, project_id),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.env.path()
+    }
+
+    /// Copy the saved module files into `dir`. Returns `false` when nothing
+    /// has been saved yet.
+    pub fn restore(&self, dir: &Path) -> io::Result<bool> {
+        if !self.path().join(GO_MOD).exists() {
+            return Ok(false);
+        }
+        copy_module_files(self.path(), dir)?;
+        Ok(true)
+    }
+
+    /// Save the module files from `dir`, after a build resolved its imports
+    pub fn save(&self, dir: &Path) -> io::Result<()> {
+        fs::create_dir_all(self.path())?;
+        copy_module_files(dir, self.path())
+    }
+}
+
+fn copy_module_files(from: &Path, to: &Path) -> io::Result<()> {
+    fs::copy(from.join(GO_MOD), to.join(GO_MOD))?;
+    // A module without dependencies has no go.sum
+    if from.join(GO_SUM).exists() {
+        fs::copy(from.join(GO_SUM), to.join(GO_SUM))?;
+    } else if to.join(GO_SUM).exists() {
+        fs::remove_file(to.join(GO_SUM))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_restore() {
+        let root = std::env::temp_dir().join(format!(
+            "codecell_go_module_test_{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        let (first_run, second_run) = (root.join("run1"), root.join("run2"));
+        fs::create_dir_all(&first_run).unwrap();
+        fs::create_dir_all(&second_run).unwrap();
+        let module = GoModule::new(&root.join("modules"), "project");
+
+        assert!(!module.restore(&first_run).unwrap());
+        fs::write(first_run.join(GO_MOD), "module codecell\n").unwrap();
+        fs::write(first_run.join(GO_SUM), "example.com/x v1.0.0 h1:abc\n").unwrap();
+        module.save(&first_run).unwrap();
+
+        assert!(module.restore(&second_run).unwrap());
+        assert_eq!(
+            fs::read_to_string(second_run.join(GO_SUM)).unwrap(),
+            "example.com/x v1.0.0 h1:abc\n"
+        );
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
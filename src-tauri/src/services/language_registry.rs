@@ -0,0 +1,598 @@
+//! The languages programs can be written in.
+//!
+//! Built-in languages are defined here. More can be added without code
+//! changes by dropping a TOML file into the `languages` directory of the app
+//! data dir; the file name is the language id, so `languages/mydsl.toml`
+//! defines `mydsl`:
+//!
+//! ```toml
+//! name = "My DSL"
+//! extensions = ["dsl"]
+//! compile = ["dslc", "{entry}", "-o", "{bin}"]
+//! check = ["dslc", "--check", "{entry}"]
+//! run = ["{bin}"]
+//!
+//! [[runtimes]]
+//! name = "DSL compiler"
+//! command = "dslc"
+//! download_url = "https://example.com/dsl"
+//! install = { macos = "brew install dslc", linux = "pipx install dslc" }
+//! ```
+//!
+//! Commands may use these placeholders:
+//!
+//! - `{entry}`: the entry file, relative to the workspace
+//! - `{entry_stem}`: the entry file name without its extension
+//! - `{files}`: every project file with one of the language's extensions,
+//!   as separate arguments (only as a whole argument)
+//! - `{bin}`: the path a compile step should write an executable to
+//!
+//! A user file with a built-in id replaces that built-in.
+
+use crate::models::{ProjectFile, TemplateType};
+use crate::services::RuntimeInfo;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Directory in the app data dir holding user language definitions
+pub const LANGUAGES_DIR: &str = "languages";
+
+/// Executable name the `{bin}` placeholder expands to
+pub const BIN_NAME: &str = "codecell_bin";
+
+#[derive(Error, Debug)]
+pub enum LanguageRegistryError {
+    #[error("Failed to read language definition {0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("Invalid language definition {0}: {1}")]
+    Definition(String, toml::de::Error),
+    #[error("Language definition {0} has an empty {1} command")]
+    EmptyCommand(String, &'static str),
+    #[error("Language definition {0} has no {1} command")]
+    MissingCommand(String, &'static str),
+    #[error("Unknown language: {0}")]
+    UnknownLanguage(String),
+    #[error("Language {0} is unavailable: {1}")]
+    Unavailable(String, String),
+}
+
+/// How a language's programs are built and run
+#[derive(Debug, Clone, Default)]
+pub enum LanguageRunner {
+    /// The definition's `compile` and `run` commands
+    #[default]
+    Commands,
+    /// The template's dedicated pipeline, for languages that need more than
+    /// two commands (virtualenvs, npm installs, Cargo, compiler diagnostics)
+    Template(TemplateType),
+}
+
+/// A language: its source files, toolchain and how to run a program
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct LanguageDefinition {
+    /// Taken from the definition's file name
+    #[serde(skip_deserializing)]
+    pub id: String,
+    pub name: String,
+    /// Source file extensions, without the dot
+    #[serde(default)]
+    pub extensions: Vec<String>,
+    /// File name for snippets; `main.<first extension>` when omitted
+    pub entry_file: Option<String>,
+    /// Commands that must be installed, checked before every run
+    #[serde(default)]
+    pub runtimes: Vec<RuntimeInfo>,
+    /// Run once before `run`; a failure is reported instead of running
+    pub compile: Option<Vec<String>>,
+    /// Reports problems without running the program, for `check_project`;
+    /// `compile` is used when omitted
+    pub check: Option<Vec<String>>,
+    /// Required for definitions; templates run through their own pipeline
+    pub run: Option<Vec<String>>,
+    #[serde(skip)]
+    pub runner: LanguageRunner,
+}
+
+impl LanguageDefinition {
+    pub fn entry_file(&self) -> String {
+        self.entry_file.clone().unwrap_or_else(|| {
+            let extension = self.extensions.first().map_or("txt", String::as_str);
+            format!("main.{extension}")
+        })
+    }
+
+    /// Whether a project file is a source file of this language
+    pub fn is_source(&self, name: &str) -> bool {
+        Path::new(name).extension().is_some_and(|ext| {
+            self.extensions
+                .iter()
+                .any(|e| ext.eq_ignore_ascii_case(e.as_str()))
+        })
+    }
+
+    /// Expand a command's placeholders for a run of `entry`. The command
+    /// must start with a program, as checked when definitions are loaded.
+    ///
+    /// Paths are relative to the workspace, except a program given as a
+    /// placeholder (`run = ["{bin}"]`), which is joined onto `workspace` so
+    /// it doesn't depend on a PATH lookup. Returns the program and its
+    /// arguments.
+    pub fn command_line(
+        &self,
+        command: &[String],
+        workspace: &Path,
+        entry: &str,
+        files: &[ProjectFile],
+    ) -> (PathBuf, Vec<String>) {
+        let entry_stem = Path::new(entry)
+            .file_stem()
+            .map_or(entry.into(), |stem| stem.to_string_lossy());
+        let bin = format!("{BIN_NAME}{}", std::env::consts::EXE_SUFFIX);
+        let expand = |part: &str| {
+            part.replace("{entry_stem}", &entry_stem)
+                .replace("{entry}", entry)
+                .replace("{bin}", &bin)
+        };
+
+        let mut parts = Vec::new();
+        for part in command {
+            if part == "{files}" {
+                parts.extend(
+                    files
+                        .iter()
+                        .filter(|f| self.is_source(&f.name))
+                        .map(|f| f.name.clone()),
+                );
+            } else {
+                parts.push(expand(part));
+            }
+        }
+
+        let program = parts.remove(0);
+        let program = if command[0].contains('{') {
+            workspace.join(program)
+        } else {
+            PathBuf::from(program)
+        };
+        (program, parts)
+    }
+
+    /// Commands must start with a program
+    fn validate(&self) -> Result<(), LanguageRegistryError> {
+        let has_program = |command: &[String]| {
+            command
+                .first()
+                .is_some_and(|p| !p.is_empty() && p != "{files}")
+        };
+        match &self.run {
+            Some(run) if !has_program(run) => {
+                return Err(LanguageRegistryError::EmptyCommand(self.id.clone(), "run"));
+            }
+            None if matches!(self.runner, LanguageRunner::Commands) => {
+                return Err(LanguageRegistryError::MissingCommand(
+                    self.id.clone(),
+                    "run",
+                ));
+            }
+            _ => {}
+        }
+        if self.compile.as_deref().is_some_and(|c| !has_program(c)) {
+            return Err(LanguageRegistryError::EmptyCommand(
+                self.id.clone(),
+                "compile",
+            ));
+        }
+        if self.check.as_deref().is_some_and(|c| !has_program(c)) {
+            return Err(LanguageRegistryError::EmptyCommand(
+                self.id.clone(),
+                "check",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The language a template's projects and snippets run with
+pub fn template_language(template: &TemplateType) -> Option<&'static str> {
+    match template {
+        TemplateType::Python => Some("python"),
+        TemplateType::Node => Some("node"),
+        TemplateType::Typescript => Some("typescript"),
+        TemplateType::Rust => Some("rust"),
+        TemplateType::Java => Some("java"),
+        TemplateType::Go => Some("go"),
+        TemplateType::C => Some("c"),
+        TemplateType::Cpp => Some("cpp"),
+        TemplateType::Ruby => Some("ruby"),
+        TemplateType::Php => Some("php"),
+        TemplateType::Perl => Some("perl"),
+        TemplateType::Lua => Some("lua"),
+        TemplateType::Bash => Some("bash"),
+        TemplateType::Kotlin => Some("kotlin"),
+        TemplateType::Scala => Some("scala"),
+        TemplateType::Groovy => Some("groovy"),
+        TemplateType::Web => None,
+    }
+}
+
+/// Built-in languages plus the user's definitions
+pub struct LanguageRegistry {
+    languages: Vec<LanguageDefinition>,
+    /// User definitions that failed to load, by language id
+    errors: Vec<(String, LanguageRegistryError)>,
+}
+
+impl LanguageRegistry {
+    /// The built-in languages, with the definitions in `dir` added on top.
+    ///
+    /// A broken definition only makes its own language unavailable; running
+    /// it reports why.
+    pub fn load(dir: &Path) -> Self {
+        let mut registry = Self {
+            languages: builtin_languages(),
+            errors: Vec::new(),
+        };
+
+        let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let Some(id) = path.file_stem().map(|s| s.to_string_lossy().into_owned()) else {
+                continue;
+            };
+            match load_definition(&path, &id) {
+                Ok(definition) => registry.insert(definition),
+                Err(e) => {
+                    // A broken override shouldn't fall back to the built-in
+                    registry.languages.retain(|l| l.id != id);
+                    registry.errors.push((id, e));
+                }
+            }
+        }
+        registry
+    }
+
+    fn insert(&mut self, definition: LanguageDefinition) {
+        match self.languages.iter_mut().find(|l| l.id == definition.id) {
+            Some(existing) => *existing = definition,
+            None => self.languages.push(definition),
+        }
+    }
+
+    pub fn languages(&self) -> &[LanguageDefinition] {
+        &self.languages
+    }
+
+    pub fn get(&self, id: &str) -> Result<&LanguageDefinition, LanguageRegistryError> {
+        if let Some(language) = self.languages.iter().find(|l| l.id == id) {
+            return Ok(language);
+        }
+        Err(match self.errors.iter().find(|(failed, _)| failed == id) {
+            Some((_, e)) => LanguageRegistryError::Unavailable(id.to_string(), e.to_string()),
+            None => LanguageRegistryError::UnknownLanguage(id.to_string()),
+        })
+    }
+}
+
+fn load_definition(path: &Path, id: &str) -> Result<LanguageDefinition, LanguageRegistryError> {
+    let name = path.display().to_string();
+    let content =
+        fs::read_to_string(path).map_err(|e| LanguageRegistryError::Io(name.clone(), e))?;
+    let mut definition: LanguageDefinition =
+        toml::from_str(&content).map_err(|e| LanguageRegistryError::Definition(name, e))?;
+    definition.id = id.to_string();
+    definition.validate()?;
+    Ok(definition)
+}
+
+fn strings(parts: &[&str]) -> Vec<String> {
+    parts.iter().map(|s| (*s).to_string()).collect()
+}
+
+/// The languages with dedicated templates.
+///
+/// They're built and run by the template pipeline, so they have no `compile`
+/// or `run` commands; `check` is only set where checking needs nothing more
+/// than a command, as for the interpreters' syntax checks.
+fn builtin_languages() -> Vec<LanguageDefinition> {
+    vec![
+        LanguageDefinition {
+            id: "python".to_string(),
+            name: "Python".to_string(),
+            extensions: strings(&["py"]),
+            entry_file: Some("main.py".to_string()),
+            runtimes: vec![RuntimeInfo::PYTHON],
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Python),
+        },
+        LanguageDefinition {
+            id: "node".to_string(),
+            name: "Node.js".to_string(),
+            extensions: strings(&["js", "mjs", "cjs"]),
+            entry_file: Some("main.js".to_string()),
+            runtimes: vec![RuntimeInfo::NODE],
+            compile: None,
+            check: Some(strings(&["node", "--check", "{entry}"])),
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Node),
+        },
+        LanguageDefinition {
+            id: "typescript".to_string(),
+            name: "TypeScript".to_string(),
+            extensions: strings(&["ts", "mts", "cts"]),
+            entry_file: Some("main.ts".to_string()),
+            // Node erases the types; see `ts_strip`
+            runtimes: vec![RuntimeInfo::NODE],
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Typescript),
+        },
+        LanguageDefinition {
+            id: "rust".to_string(),
+            name: "Rust".to_string(),
+            extensions: strings(&["rs"]),
+            entry_file: Some("main.rs".to_string()),
+            runtimes: vec![RuntimeInfo::RUST],
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Rust),
+        },
+        LanguageDefinition {
+            id: "java".to_string(),
+            name: "Java".to_string(),
+            extensions: strings(&["java"]),
+            // Snippets are named after their public class instead
+            entry_file: Some("Main.java".to_string()),
+            // Java needs both javac (compiler) and java (runtime)
+            runtimes: vec![RuntimeInfo::JAVAC, RuntimeInfo::JAVA],
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Java),
+        },
+        LanguageDefinition {
+            id: "kotlin".to_string(),
+            name: "Kotlin".to_string(),
+            extensions: strings(&["kt", "kts"]),
+            entry_file: Some("main.kt".to_string()),
+            // kotlinc runs on an installed JVM
+            runtimes: vec![RuntimeInfo::KOTLINC, RuntimeInfo::JAVA],
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Kotlin),
+        },
+        // Scala CLI or scalac is picked per run
+        LanguageDefinition {
+            id: "scala".to_string(),
+            name: "Scala".to_string(),
+            extensions: strings(&["scala", "sc"]),
+            entry_file: Some("main.scala".to_string()),
+            runtimes: Vec::new(),
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Scala),
+        },
+        LanguageDefinition {
+            id: "groovy".to_string(),
+            name: "Groovy".to_string(),
+            extensions: strings(&["groovy", "gvy"]),
+            entry_file: Some("main.groovy".to_string()),
+            runtimes: vec![RuntimeInfo::GROOVY, RuntimeInfo::JAVA],
+            compile: None,
+            check: Some(strings(&["groovyc", "{files}"])),
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Groovy),
+        },
+        LanguageDefinition {
+            id: "go".to_string(),
+            name: "Go".to_string(),
+            extensions: strings(&["go"]),
+            entry_file: Some("main.go".to_string()),
+            runtimes: vec![RuntimeInfo::GO],
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Go),
+        },
+        // The compiler (GCC or Clang) is picked per run
+        LanguageDefinition {
+            id: "c".to_string(),
+            name: "C".to_string(),
+            extensions: strings(&["c"]),
+            entry_file: Some("main.c".to_string()),
+            runtimes: Vec::new(),
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::C),
+        },
+        LanguageDefinition {
+            id: "cpp".to_string(),
+            name: "C++".to_string(),
+            extensions: strings(&["cpp", "cc", "cxx", "c++"]),
+            entry_file: Some("main.cpp".to_string()),
+            runtimes: Vec::new(),
+            compile: None,
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Cpp),
+        },
+        LanguageDefinition {
+            id: "ruby".to_string(),
+            name: "Ruby".to_string(),
+            extensions: strings(&["rb"]),
+            entry_file: Some("main.rb".to_string()),
+            runtimes: vec![RuntimeInfo::RUBY],
+            compile: None,
+            check: Some(strings(&["ruby", "-c", "{entry}"])),
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Ruby),
+        },
+        LanguageDefinition {
+            id: "php".to_string(),
+            name: "PHP".to_string(),
+            extensions: strings(&["php"]),
+            entry_file: Some("main.php".to_string()),
+            runtimes: vec![RuntimeInfo::PHP],
+            compile: None,
+            check: Some(strings(&["php", "-l", "{entry}"])),
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Php),
+        },
+        LanguageDefinition {
+            id: "perl".to_string(),
+            name: "Perl".to_string(),
+            extensions: strings(&["pl", "pm"]),
+            entry_file: Some("main.pl".to_string()),
+            runtimes: vec![RuntimeInfo::PERL],
+            compile: None,
+            // `perl -c` runs BEGIN blocks and `use`d modules' code, so Perl
+            // is only checked by running it
+            check: None,
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Perl),
+        },
+        LanguageDefinition {
+            id: "lua".to_string(),
+            name: "Lua".to_string(),
+            extensions: strings(&["lua"]),
+            entry_file: Some("main.lua".to_string()),
+            runtimes: vec![RuntimeInfo::LUA],
+            compile: None,
+            check: Some(strings(&["luac", "-p", "{files}"])),
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Lua),
+        },
+        LanguageDefinition {
+            id: "bash".to_string(),
+            name: "Bash".to_string(),
+            extensions: strings(&["sh", "bash"]),
+            entry_file: Some("main.sh".to_string()),
+            runtimes: vec![RuntimeInfo::BASH],
+            compile: None,
+            check: Some(strings(&["bash", "-n", "{entry}"])),
+            run: None,
+            runner: LanguageRunner::Template(TemplateType::Bash),
+        },
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str) -> ProjectFile {
+        ProjectFile {
+            name: name.to_string(),
+            content: String::new(),
+            language: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_load_user_definitions() {
+        let dir = std::env::temp_dir().join(format!(
+            "codecell_languages_test_{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("mydsl.toml"),
+            "name = \"My DSL\"\nextensions = [\"dsl\"]\nrun = [\"dsl\", \"{entry}\"]\n\n[[runtimes]]\nname = \"DSL\"\ncommand = \"dsl\"\ninstall = { linux = \"pipx install dsl\" }\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("python.toml"),
+            "name = \"PyPy\"\nrun = [\"pypy3\", \"{entry}\"]\n",
+        )
+        .unwrap();
+        fs::write(dir.join("broken.toml"), "name = \"Broken\"\nrun = []\n").unwrap();
+        fs::write(dir.join("norun.toml"), "name = \"No run\"\n").unwrap();
+        fs::write(dir.join("typo.toml"), "name = \"Typo\"\nrun = [\"typo\"\n").unwrap();
+
+        let registry = LanguageRegistry::load(&dir);
+        let dsl = registry.get("mydsl").unwrap();
+        assert_eq!(dsl.name, "My DSL");
+        assert_eq!(dsl.entry_file(), "main.dsl");
+        assert_eq!(dsl.runtimes[0].install["linux"], "pipx install dsl");
+        assert!(matches!(dsl.runner, LanguageRunner::Commands));
+
+        // User files replace built-ins with the same id
+        let python = registry.get("python").unwrap();
+        assert_eq!(python.name, "PyPy");
+        assert!(matches!(python.runner, LanguageRunner::Commands));
+        assert!(registry.get("rust").is_ok());
+
+        // Broken definitions report why they can't be used
+        let error = registry.get("broken").unwrap_err().to_string();
+        assert!(error.contains("empty run command"), "{error}");
+        let error = registry.get("norun").unwrap_err().to_string();
+        assert!(error.contains("has no run command"), "{error}");
+        assert!(matches!(
+            registry.get("typo"),
+            Err(LanguageRegistryError::Unavailable(..))
+        ));
+        assert!(matches!(
+            registry.get("cobol"),
+            Err(LanguageRegistryError::UnknownLanguage(_))
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_command_line() {
+        let java: LanguageDefinition = toml::from_str(
+            r#"
+            name = "Java"
+            extensions = ["java"]
+            compile = ["javac", "-d", ".", "{files}"]
+            run = ["java", "-cp", ".", "{entry_stem}"]
+            "#,
+        )
+        .unwrap();
+        let files = [
+            file("app/Main.java"),
+            file("app/Util.java"),
+            file("data.txt"),
+        ];
+        let workspace = Path::new("/tmp/ws");
+
+        let (program, args) = java.command_line(
+            java.compile.as_ref().unwrap(),
+            workspace,
+            "app/Main.java",
+            &files,
+        );
+        assert_eq!(program, PathBuf::from("javac"));
+        assert_eq!(args, ["-d", ".", "app/Main.java", "app/Util.java"]);
+
+        let run = java.run.as_ref().unwrap();
+        let (_, args) = java.command_line(run, workspace, "app/Main.java", &files);
+        assert_eq!(args, ["-cp", ".", "Main"]);
+
+        // A placeholder program runs from the workspace
+        let (program, args) =
+            java.command_line(&strings(&["{bin}"]), workspace, "Main.java", &files);
+        assert_eq!(
+            program,
+            workspace.join(format!("{BIN_NAME}{}", std::env::consts::EXE_SUFFIX))
+        );
+        assert!(args.is_empty());
+    }
+}
@@ -0,0 +1,259 @@
+//! Hints for imports of packages that aren't installed.
+//!
+//! Python's `ModuleNotFoundError` and Node's `Cannot find module` name what
+//! the program imported, which for a few well-known packages differs from
+//! what has to be installed (`cv2` comes from `opencv-python`).
+
+use serde::Serialize;
+use std::ffi::OsStr;
+
+/// Python imports provided by a package of another name; an entry covers
+/// its submodules too
+const PIP_PACKAGES: &[(&str, &str)] = &[
+    ("Crypto", "pycryptodome"),
+    ("MySQLdb", "mysqlclient"),
+    ("OpenSSL", "pyOpenSSL"),
+    ("PIL", "Pillow"),
+    ("attr", "attrs"),
+    ("bs4", "beautifulsoup4"),
+    ("cv2", "opencv-python"),
+    ("dateutil", "python-dateutil"),
+    ("distutils", "setuptools"),
+    ("docx", "python-docx"),
+    ("dotenv", "python-dotenv"),
+    ("fitz", "PyMuPDF"),
+    ("gi", "PyGObject"),
+    ("google.protobuf", "protobuf"),
+    ("jwt", "PyJWT"),
+    ("magic", "python-magic"),
+    ("pkg_resources", "setuptools"),
+    ("pptx", "python-pptx"),
+    ("psycopg2", "psycopg2-binary"),
+    ("serial", "pyserial"),
+    ("skimage", "scikit-image"),
+    ("sklearn", "scikit-learn"),
+    ("telegram", "python-telegram-bot"),
+    ("usb", "pyusb"),
+    ("win32api", "pywin32"),
+    ("win32com", "pywin32"),
+    ("wx", "wxPython"),
+    ("yaml", "PyYAML"),
+    ("zmq", "pyzmq"),
+];
+
+/// Standard library modules that some distros package separately, which pip
+/// can't install
+const SYSTEM_MODULES: &[&str] = &["tkinter", "_tkinter", "_curses", "_dbm", "_gdbm"];
+
+/// A package the program needed, sent with the `execution:missing-module`
+/// event
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingModule {
+    /// The name the program imported
+    pub module: String,
+    pub package: String,
+    pub install_command: String,
+    /// Where to declare the package so the project's environment keeps it
+    pub manifest: String,
+}
+
+/// How packages are installed for the program being run
+#[derive(Debug, Clone, PartialEq)]
+pub enum PackageInstaller {
+    /// pip, run by the program's interpreter so packages land in its
+    /// environment (the project's venv, or the system's)
+    Pip(String),
+    Npm,
+    Bun,
+    Deno,
+}
+
+impl PackageInstaller {
+    /// The installer for a program run as `command`, using `program`
+    pub fn for_command(command: &str, program: &OsStr) -> Option<Self> {
+        match command {
+            "python3" => Some(Self::Pip(program.to_string_lossy().into_owned())),
+            "node" => Some(Self::Npm),
+            "bun" => Some(Self::Bun),
+            "deno" => Some(Self::Deno),
+            _ => None,
+        }
+    }
+
+    /// The first missing module a failed run's stderr reports, and how to
+    /// install it
+    pub fn diagnose(&self, stderr: &str) -> Option<MissingModule> {
+        let (module, package) = if let Self::Pip(_) = self {
+            let module = missing_python_module(stderr)?;
+            (module, pip_package(module)?)
+        } else {
+            let specifier = missing_node_module(stderr)?;
+            (specifier, npm_package(specifier)?)
+        };
+
+        let (install_command, manifest) = match self {
+            Self::Pip(python) => {
+                let python = if python.contains(' ') {
+                    format!("\"{python}\"")
+                } else {
+                    python.clone()
+                };
+                (
+                    format!("{python} -m pip install {package}"),
+                    "requirements.txt",
+                )
+            }
+            Self::Npm => (format!("npm install {package}"), "package.json"),
+            Self::Bun => (format!("bun add {package}"), "package.json"),
+            Self::Deno => (format!("deno add npm:{package}"), "deno.json"),
+        };
+        Some(MissingModule {
+            module: module.to_string(),
+            package: package.to_string(),
+            install_command,
+            manifest: manifest.to_string(),
+        })
+    }
+}
+
+/// The module named by `ModuleNotFoundError: No module named 'x.y'`
+fn missing_python_module(stderr: &str) -> Option<&str> {
+    let line = stderr.lines().rev().find_map(|line| {
+        line.trim()
+            .strip_prefix("ModuleNotFoundError: No module named ")
+    })?;
+    quoted(line)
+}
+
+/// The pip package providing `module`, or `None` when pip can't install it
+fn pip_package(module: &str) -> Option<&str> {
+    let top_level = module.split('.').next().unwrap_or(module);
+    if SYSTEM_MODULES.contains(&top_level) {
+        return None;
+    }
+    let package = PIP_PACKAGES
+        .iter()
+        .find(|(import, _)| module == *import || module.starts_with(&format!("{import}.")))
+        .map_or(top_level, |(_, package)| *package);
+    Some(package)
+}
+
+/// The specifier in Node's `Cannot find module 'x'` and `Cannot find package
+/// 'x'`, Bun's variants of those, and Deno's bare specifier error
+fn missing_node_module(stderr: &str) -> Option<&str> {
+    const MESSAGES: &[&str] = &[
+        "Cannot find module ",
+        "Cannot find package ",
+        "Relative import path ",
+    ];
+    stderr.lines().find_map(|line| {
+        MESSAGES.iter().find_map(|message| {
+            let start = line.find(message)? + message.len();
+            quoted(&line[start..])
+        })
+    })
+}
+
+/// The npm package a bare specifier imports from: `lodash` for
+/// `lodash/fp`, `@scope/pkg` for `@scope/pkg/sub`. Paths, URLs and
+/// `node:` builtins aren't packages.
+fn npm_package(specifier: &str) -> Option<&str> {
+    let specifier = specifier.strip_prefix("npm:").unwrap_or(specifier);
+    if specifier.starts_with(['.', '/', '\\']) || specifier.contains(':') {
+        return None;
+    }
+    let segments = if specifier.starts_with('@') { 2 } else { 1 };
+    let end = specifier
+        .match_indices('/')
+        .nth(segments - 1)
+        .map_or(specifier.len(), |(i, _)| i);
+    Some(&specifier[..end])
+}
+
+/// The text between the quotes that `text` starts with
+fn quoted(text: &str) -> Option<&str> {
+    let quote = text.chars().next().filter(|c| matches!(c, '\'' | '"'))?;
+    let rest = &text[1..];
+    rest.find(quote).map(|end| &rest[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diagnose_python() {
+        let pip = PackageInstaller::Pip("/data/venvs/p1/bin/python".to_string());
+        let traceback = "Traceback (most recent call last):\n  File \"/tmp/w/main.py\", line 1, in <module>\n    import cv2\nModuleNotFoundError: No module named 'cv2'\n";
+        assert_eq!(
+            pip.diagnose(traceback),
+            Some(MissingModule {
+                module: "cv2".to_string(),
+                package: "opencv-python".to_string(),
+                install_command: "/data/venvs/p1/bin/python -m pip install opencv-python"
+                    .to_string(),
+                manifest: "requirements.txt".to_string(),
+            })
+        );
+
+        let package = |stderr: &str| pip.diagnose(stderr).map(|missing| missing.package);
+        assert_eq!(
+            package("ModuleNotFoundError: No module named 'requests.adapters'").as_deref(),
+            Some("requests")
+        );
+        assert_eq!(
+            package("ModuleNotFoundError: No module named 'google.protobuf'").as_deref(),
+            Some("protobuf")
+        );
+        assert_eq!(
+            package("ModuleNotFoundError: No module named 'sklearn.linear_model'").as_deref(),
+            Some("scikit-learn")
+        );
+        // Distro packages, not pip's
+        assert_eq!(
+            package("ModuleNotFoundError: No module named 'tkinter'"),
+            None
+        );
+        assert_eq!(package("NameError: name 'x' is not defined"), None);
+    }
+
+    #[test]
+    fn test_diagnose_node() {
+        let package = |installer: &PackageInstaller, stderr: &str| {
+            installer
+                .diagnose(stderr)
+                .map(|missing| missing.install_command)
+        };
+        let npm = PackageInstaller::Npm;
+        assert_eq!(
+            package(&npm, "node:internal/modules/cjs/loader:1080\n  throw err;\n\nError: Cannot find module 'lodash/fp'\nRequire stack:\n- /tmp/w/main.js").as_deref(),
+            Some("npm install lodash")
+        );
+        assert_eq!(
+            package(&npm, "Error [ERR_MODULE_NOT_FOUND]: Cannot find package '@types/node' imported from /tmp/w/main.mjs").as_deref(),
+            Some("npm install @types/node")
+        );
+        // The user's own files
+        assert_eq!(
+            package(&npm, "Error: Cannot find module './util'\nRequire stack:"),
+            None
+        );
+        assert_eq!(
+            package(
+                &PackageInstaller::Bun,
+                "error: Cannot find package \"chalk\" from \"/tmp/w/main.ts\""
+            )
+            .as_deref(),
+            Some("bun add chalk")
+        );
+        assert_eq!(
+            package(
+                &PackageInstaller::Deno,
+                "error: Relative import path \"zod\" not prefixed with / or ./ or ../"
+            )
+            .as_deref(),
+            Some("deno add npm:zod")
+        );
+    }
+}
@@ -1,5 +1,28 @@
+pub mod artifacts;
+pub mod cargo_project;
+pub mod cc_compiler;
+pub mod compile_cache;
+pub mod dependency_env;
+pub mod diagnostics;
+pub mod go_module;
+pub mod language_registry;
+pub mod missing_modules;
+pub mod node_env;
+pub mod process_group;
+pub mod process_inspector;
 mod project_manager;
+pub mod python_env;
+pub mod resource_limits;
+pub mod run_config;
 pub mod runtime_detector;
+pub mod runtime_inventory;
+pub mod runtime_overrides;
+pub mod ts_strip;
+pub mod version_managers;
+mod workspace;
 
+pub use process_inspector::is_waiting_for_stdin;
 pub use project_manager::ProjectManager;
-pub use runtime_detector::{check_runtime, RuntimeInfo};
+pub use resource_limits::{apply_rlimits, detect_exceeded, limit_message, step_limits};
+pub use runtime_detector::{check_runtime, command_exists, RuntimeInfo};
+pub use workspace::{find_entry, Workspace, WorkspaceError};
@@ -0,0 +1,162 @@
+//! Per-project npm dependencies for Node and TypeScript.
+//!
+//! Dependencies come from a `package.json` in the project, or from the
+//! dependency list in the project's Node settings. They are installed into a
+//! per-project directory, and runs happen in a subdirectory of it so Node's
+//! module resolution walks up into its `node_modules`.
+
+use crate::models::ProjectFile;
+use crate::services::compile_cache::CacheKey;
+use crate::services::dependency_env::DependencyEnv;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use thiserror::Error;
+
+pub const PACKAGE_JSON: &str = "package.json";
+pub const PACKAGE_LOCK: &str = "package-lock.json";
+
+#[derive(Error, Debug)]
+pub enum NodeEnvError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid package.json: {0}")]
+    Manifest(#[from] serde_json::Error),
+}
+
+/// The manifest npm installs a project's dependencies from
+#[derive(Debug, PartialEq)]
+pub struct PackageManifest {
+    pub package_json: String,
+    pub lockfile: Option<String>,
+    /// Hash of the manifest and lockfile, to tell when to reinstall
+    pub fingerprint: String,
+}
+
+/// Find the dependencies a project declares.
+///
+/// A project `package.json` wins over `dependencies` from the project's
+/// settings. Returns `None` when there is nothing to install.
+pub fn find_manifest(
+    files: &[ProjectFile],
+    dependencies: &BTreeMap<String, String>,
+) -> Result<Option<PackageManifest>, NodeEnvError> {
+    let (package_json, lockfile) = match files.iter().find(|f| f.name == PACKAGE_JSON) {
+        Some(file) => {
+            let manifest: serde_json::Value = serde_json::from_str(&file.content)?;
+            let has_dependencies = ["dependencies", "devDependencies"].iter().any(|key| {
+                manifest[key]
+                    .as_object()
+                    .is_some_and(|deps| !deps.is_empty())
+            });
+            if !has_dependencies {
+                return Ok(None);
+            }
+            let lockfile = files
+                .iter()
+                .find(|f| f.name == PACKAGE_LOCK)
+                .map(|f| f.content.clone());
+            (file.content.clone(), lockfile)
+        }
+        None if !dependencies.is_empty() => {
+            let manifest = serde_json::json!({
+                "name": "codecell-snippet",
+                "private": true,
+                "dependencies": dependencies,
+            });
+            (serde_json::to_string_pretty(&manifest)?, None)
+        }
+        None => return Ok(None),
+    };
+
+    let mut key = CacheKey::new("npm");
+    key.add(package_json.as_bytes())
+        .add(lockfile.as_deref().unwrap_or_default().as_bytes());
+
+    Ok(Some(PackageManifest {
+        package_json,
+        lockfile,
+        fingerprint: key.finish(),
+    }))
+}
+
+/// A project's installed npm packages
+pub struct NodeEnv {
+    env: DependencyEnv,
+}
+
+impl NodeEnv {
+    /// The install directory for `project_id` inside `envs_dir`
+    pub fn new(envs_dir: &Path, project_id: &str) -> Self {
+        Self {
+            env: DependencyEnv::new(envs_dir, project_id),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.env.path()
+    }
+
+    pub fn is_current(&self, manifest: &PackageManifest) -> bool {
+        self.path().join("node_modules").is_dir() && self.env.is_current(&manifest.fingerprint)
+    }
+
+    /// Write the manifest npm installs from.
+    ///
+    /// A lockfile npm generated earlier is kept unless the project has its own.
+    pub fn write_manifest(&self, manifest: &PackageManifest) -> Result<(), NodeEnvError> {
+        fs::create_dir_all(self.path())?;
+        fs::write(self.path().join(PACKAGE_JSON), &manifest.package_json)?;
+        if let Some(lockfile) = &manifest.lockfile {
+            fs::write(self.path().join(PACKAGE_LOCK), lockfile)?;
+        }
+        Ok(())
+    }
+
+    pub fn mark_installed(&self, manifest: &PackageManifest) -> Result<(), NodeEnvError> {
+        Ok(self.env.mark_installed(&manifest.fingerprint)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> ProjectFile {
+        ProjectFile {
+            name: name.to_string(),
+            content: content.to_string(),
+            language: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_find_manifest_from_package_json() {
+        let files = [
+            file("index.js", "require('lodash')"),
+            file(PACKAGE_JSON, r#"{"dependencies": {"lodash": "^4.17.21"}}"#),
+        ];
+        let manifest = find_manifest(&files, &BTreeMap::new()).unwrap().unwrap();
+        assert_eq!(manifest.package_json, files[1].content);
+        assert_eq!(manifest.lockfile, None);
+
+        // A package.json without dependencies needs no install
+        let files = [file(PACKAGE_JSON, r#"{"type": "module"}"#)];
+        assert_eq!(find_manifest(&files, &BTreeMap::new()).unwrap(), None);
+
+        let files = [file(PACKAGE_JSON, "{")];
+        assert!(find_manifest(&files, &BTreeMap::new()).is_err());
+    }
+
+    #[test]
+    fn test_find_manifest_from_settings() {
+        let dependencies = BTreeMap::from([("zod".to_string(), "^3".to_string())]);
+        let manifest = find_manifest(&[file("index.ts", "")], &dependencies)
+            .unwrap()
+            .unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&manifest.package_json).unwrap();
+        assert_eq!(parsed["dependencies"]["zod"], "^3");
+
+        assert_eq!(find_manifest(&[], &BTreeMap::new()).unwrap(), None);
+    }
+}
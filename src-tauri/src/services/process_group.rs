@@ -0,0 +1,119 @@
+//! Process tree isolation and termination.
+//!
+//! Every program runs as the leader of its own process group (a new process
+//! group on Windows), so stopping it also reaches the workers it spawned:
+//! `npx` children, Node worker trees, Python multiprocessing pools.
+
+use std::time::Duration;
+use tokio::process::{Child, Command};
+
+/// How long a stopped program gets to exit after the polite signal
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// How long programs get to exit when the app quits
+pub const EXIT_GRACE_PERIOD: Duration = Duration::from_millis(200);
+
+/// Start the command in its own process group
+pub fn isolate(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        cmd.process_group(0);
+    }
+
+    #[cfg(windows)]
+    {
+        const CREATE_NEW_PROCESS_GROUP: u32 = 0x0000_0200;
+        cmd.creation_flags(CREATE_NEW_PROCESS_GROUP);
+    }
+}
+
+/// Stop a program and everything in its process group.
+///
+/// Sends SIGTERM to the group, waits up to `grace` for the program to exit,
+/// then sends SIGKILL to whatever is left of the group.
+pub async fn terminate(child: &mut Child, grace: Duration) {
+    let Some(pid) = child.id() else {
+        // Already exited and reaped
+        return;
+    };
+
+    #[cfg(unix)]
+    {
+        signal_group(pid, libc::SIGTERM);
+        let _ = tokio::time::timeout(grace, child.wait()).await;
+        // Sent even if the leader exited: workers may have outlived it
+        signal_group(pid, libc::SIGKILL);
+    }
+
+    #[cfg(windows)]
+    {
+        taskkill(pid, false).await;
+        if tokio::time::timeout(grace, child.wait()).await.is_err() {
+            taskkill(pid, true).await;
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (pid, grace);
+    }
+
+    let _ = child.kill().await;
+}
+
+#[cfg(unix)]
+fn signal_group(pid: u32, signal: libc::c_int) {
+    if let Ok(pgid) = libc::pid_t::try_from(pid) {
+        // SAFETY: killpg has no memory safety requirements; a stale group
+        // id only makes it fail with ESRCH
+        unsafe {
+            libc::killpg(pgid, signal);
+        }
+    }
+}
+
+#[cfg(windows)]
+async fn taskkill(pid: u32, force: bool) {
+    let mut cmd = Command::new("taskkill");
+    cmd.arg("/PID").arg(pid.to_string()).arg("/T");
+    if force {
+        cmd.arg("/F");
+    }
+    let _ = cmd.output().await;
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminate_kills_grandchildren() {
+        tauri::async_runtime::block_on(async {
+            // The shell ignores SIGTERM and leaves a grandchild behind
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c")
+                .arg("trap '' TERM; sleep 30 & echo $!; wait")
+                .stdout(std::process::Stdio::piped());
+            isolate(&mut cmd);
+            let mut child = cmd.spawn().unwrap();
+
+            let mut stdout = child.stdout.take().unwrap();
+            let mut line = String::new();
+            tokio::io::AsyncBufReadExt::read_line(
+                &mut tokio::io::BufReader::new(&mut stdout),
+                &mut line,
+            )
+            .await
+            .unwrap();
+            let grandchild: libc::pid_t = line.trim().parse().unwrap();
+
+            terminate(&mut child, Duration::from_millis(200)).await;
+            tokio::time::sleep(Duration::from_millis(100)).await;
+
+            // Killed processes may linger as zombies until reaped
+            let alive = std::fs::read_to_string(format!("/proc/{grandchild}/stat"))
+                .is_ok_and(|stat| !stat.contains(") Z "));
+            assert!(!alive, "grandchild {grandchild} survived");
+        });
+    }
+}
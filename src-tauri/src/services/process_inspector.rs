@@ -0,0 +1,181 @@
+//! Best-effort inspection of running child processes.
+//!
+//! Used to tell whether a program started from an editor window is blocked
+//! waiting for input on stdin. Only Linux exposes enough through `/proc` to
+//! answer this; on other platforms every check returns `None`.
+
+/// Syscall numbers that indicate a process is blocked reading
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+mod syscalls {
+    pub const READ: &[u64] = &[0];
+    pub const EPOLL_WAIT: &[u64] = &[232, 281, 441];
+}
+
+#[cfg(all(target_os = "linux", target_arch = "aarch64"))]
+mod syscalls {
+    pub const READ: &[u64] = &[63];
+    pub const EPOLL_WAIT: &[u64] = &[22, 441];
+}
+
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "x86_64", target_arch = "aarch64"))
+))]
+mod syscalls {
+    pub const READ: &[u64] = &[];
+    pub const EPOLL_WAIT: &[u64] = &[];
+}
+
+/// Check whether the process `pid` (or any of its descendants) is blocked
+/// waiting for data on stdin.
+///
+/// Returns `None` when the platform does not support the check.
+pub fn is_waiting_for_stdin(pid: u32) -> Option<bool> {
+    #[cfg(target_os = "linux")]
+    {
+        Some(
+            process_tree(pid)
+                .into_iter()
+                .any(linux::is_blocked_on_stdin),
+        )
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = pid;
+        None
+    }
+}
+
+/// Collect `pid` and every descendant process
+#[cfg(target_os = "linux")]
+fn process_tree(pid: u32) -> Vec<u32> {
+    let mut parents: Vec<(u32, u32)> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let Some(child) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+            if let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) {
+                if let Some(ppid) = parse_stat_ppid(&stat) {
+                    parents.push((child, ppid));
+                }
+            }
+        }
+    }
+
+    let mut tree = vec![pid];
+    let mut i = 0;
+    while i < tree.len() {
+        let current = tree[i];
+        tree.extend(
+            parents
+                .iter()
+                .filter(|(_, ppid)| *ppid == current)
+                .map(|(child, _)| *child),
+        );
+        i += 1;
+    }
+    tree
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use super::{parse_syscall, syscalls};
+
+    /// A process waits on stdin when it is inside `read(0, ...)`, or inside an
+    /// epoll wait whose interest list contains fd 0 (as Node.js does)
+    pub fn is_blocked_on_stdin(pid: u32) -> bool {
+        let Ok(syscall) = std::fs::read_to_string(format!("/proc/{pid}/syscall")) else {
+            return false;
+        };
+        let Some((nr, first_arg)) = parse_syscall(&syscall) else {
+            return false;
+        };
+
+        if syscalls::READ.contains(&nr) {
+            return first_arg == 0;
+        }
+        if syscalls::EPOLL_WAIT.contains(&nr) {
+            return epoll_watches_stdin(pid, first_arg);
+        }
+        false
+    }
+
+    fn epoll_watches_stdin(pid: u32, epoll_fd: u64) -> bool {
+        std::fs::read_to_string(format!("/proc/{pid}/fdinfo/{epoll_fd}"))
+            .is_ok_and(|info| super::fdinfo_watches_fd(&info, 0))
+    }
+}
+
+/// Parse the parent pid out of a `/proc/<pid>/stat` line.
+///
+/// The command name is wrapped in parentheses and may itself contain spaces,
+/// so fields are counted from the last `)`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_stat_ppid(stat: &str) -> Option<u32> {
+    let rest = &stat[stat.rfind(')')? + 1..];
+    // Fields after the name: state, ppid, ...
+    rest.split_whitespace().nth(1)?.parse().ok()
+}
+
+/// Parse `/proc/<pid>/syscall` into the syscall number and its first argument.
+///
+/// A running process reports `running` instead of a syscall number.
+#[cfg(any(target_os = "linux", test))]
+fn parse_syscall(content: &str) -> Option<(u64, u64)> {
+    let mut fields = content.split_whitespace();
+    let nr = fields.next()?.parse().ok()?;
+    let arg = fields.next()?;
+    let arg = u64::from_str_radix(arg.trim_start_matches("0x"), 16).ok()?;
+    Some((nr, arg))
+}
+
+/// Check whether an eventpoll `fdinfo` listing has `fd` in its interest list
+#[cfg(any(target_os = "linux", test))]
+fn fdinfo_watches_fd(fdinfo: &str, fd: u32) -> bool {
+    fdinfo.lines().any(|line| {
+        line.trim_start()
+            .strip_prefix("tfd:")
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|tfd| tfd.parse::<u32>().ok())
+            == Some(fd)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stat_ppid() {
+        let stat = "4242 (python3) S 4200 4242 4200 0 -1 4194304 900 0 0 0";
+        assert_eq!(parse_stat_ppid(stat), Some(4200));
+
+        // Command names may contain spaces and parentheses
+        let stat = "77 (tsx (watch) x) R 12 77 12 0 -1";
+        assert_eq!(parse_stat_ppid(stat), Some(12));
+    }
+
+    #[test]
+    fn test_parse_syscall() {
+        assert_eq!(
+            parse_syscall("0 0x0 0x55d0 0x2000 0x0 0x0 0x0 0x7ffd 0x7f12"),
+            Some((0, 0))
+        );
+        assert_eq!(
+            parse_syscall("232 0x5 0x7ffd 0x400 0xffffffff"),
+            Some((232, 5))
+        );
+        assert_eq!(parse_syscall("running"), None);
+    }
+
+    #[test]
+    fn test_fdinfo_watches_fd() {
+        let fdinfo = "pos:\t0\nflags:\t02000002\nmnt_id:\t15\n\
+                      tfd:       17 events:       19 data:       11  pos:0 ino:1 sdev:e\n\
+                      tfd:        0 events:       19 data:        0  pos:0 ino:2 sdev:e\n";
+        assert!(fdinfo_watches_fd(fdinfo, 0));
+        assert!(!fdinfo_watches_fd(fdinfo, 3));
+    }
+}
@@ -0,0 +1,220 @@
+//! Per-project Python virtual environments.
+//!
+//! A project declares requirements with a `requirements.txt` next to its
+//! sources or with PEP 723 inline script metadata in the entry file:
+//!
+//! ```text
+//! # /// script
+//! # dependencies = ["numpy"]
+//! # ///
+//! ```
+//!
+//! Projects that declare nothing keep running on the system interpreter.
+
+use crate::models::ProjectFile;
+use crate::services::compile_cache::CacheKey;
+use crate::services::dependency_env::DependencyEnv;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Requirements file picked up from the project root
+pub const REQUIREMENTS_FILE: &str = "requirements.txt";
+
+#[derive(Error, Debug)]
+pub enum PythonEnvError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid script metadata: {0}")]
+    Metadata(#[from] toml::de::Error),
+}
+
+/// What a project needs installed before it runs
+#[derive(Debug, PartialEq)]
+pub struct Requirements {
+    /// Project requirements file, installed with `pip install -r`
+    pub file: Option<String>,
+    /// Dependencies from inline script metadata
+    pub inline: Vec<String>,
+    /// Hash of the requirements, to tell when the venv is out of date
+    pub fingerprint: String,
+}
+
+/// Collect the requirements declared by a project and its entry file.
+///
+/// `wheelhouse` is the local package directory installs come from, if any;
+/// switching it changes what gets installed, so it is part of the fingerprint.
+pub fn find_requirements(
+    files: &[ProjectFile],
+    entry: &ProjectFile,
+    wheelhouse: Option<&str>,
+) -> Result<Option<Requirements>, PythonEnvError> {
+    let file = files.iter().find(|f| f.name == REQUIREMENTS_FILE);
+    let inline = script_dependencies(&entry.content)?.unwrap_or_default();
+    if file.is_none() && inline.is_empty() {
+        return Ok(None);
+    }
+
+    let mut key = CacheKey::new("python-requirements");
+    key.add(file.map_or("", |f| f.content.as_str()).as_bytes());
+    for dependency in &inline {
+        key.add(dependency.as_bytes());
+    }
+    match wheelhouse {
+        Some(dir) => key.add(b"wheelhouse").add(dir.as_bytes()),
+        None => key.add(b"index"),
+    };
+
+    Ok(Some(Requirements {
+        file: file.map(|f| f.name.clone()),
+        inline,
+        fingerprint: key.finish(),
+    }))
+}
+
+/// Read `dependencies` from a PEP 723 `# /// script` block
+pub fn script_dependencies(source: &str) -> Result<Option<Vec<String>>, PythonEnvError> {
+    let mut lines = source.lines();
+    if !lines.any(|l| l.trim_end() == "# /// script") {
+        return Ok(None);
+    }
+
+    let mut metadata = Vec::new();
+    for line in lines {
+        if line.trim_end() == "# ///" {
+            #[derive(serde::Deserialize)]
+            struct Script {
+                #[serde(default)]
+                dependencies: Vec<String>,
+            }
+            let script: Script = toml::from_str(&metadata.join("\n"))?;
+            return Ok(Some(script.dependencies));
+        }
+        match line.strip_prefix("# ") {
+            Some(content) => metadata.push(content),
+            None if line == "#" => metadata.push(""),
+            // Not a comment line: the block was never closed
+            None => break,
+        }
+    }
+    Ok(None)
+}
+
+/// A project's virtual environment
+pub struct Venv {
+    env: DependencyEnv,
+}
+
+impl Venv {
+    /// The venv for `project_id` inside `venvs_dir`
+    pub fn new(venvs_dir: &Path, project_id: &str) -> Self {
+        Self {
+            env: DependencyEnv::new(venvs_dir, project_id),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.env.path()
+    }
+
+    /// The venv's interpreter
+    pub fn python(&self) -> PathBuf {
+        if cfg!(windows) {
+            self.path().join("Scripts").join("python.exe")
+        } else {
+            self.path().join("bin").join("python")
+        }
+    }
+
+    pub fn exists(&self) -> bool {
+        self.python().exists()
+    }
+
+    /// Whether the venv was created from `python`. Always true for a bare
+    /// command name, which could be any interpreter on `PATH`.
+    pub fn created_with(&self, python: &Path) -> bool {
+        let (Some(dir), true) = (python.parent(), python.is_absolute()) else {
+            return true;
+        };
+        let config = fs::read_to_string(self.path().join("pyvenv.cfg")).unwrap_or_default();
+        let home = config.lines().find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "home").then(|| PathBuf::from(value.trim()))
+        });
+        home.is_some_and(|home| {
+            home == dir
+                || matches!(
+                    (fs::canonicalize(&home), fs::canonicalize(dir)),
+                    (Ok(home), Ok(dir)) if home == dir
+                )
+        })
+    }
+
+    /// Whether `requirements` are already installed
+    pub fn is_current(&self, requirements: &Requirements) -> bool {
+        self.env.is_current(&requirements.fingerprint)
+    }
+
+    /// Record that `requirements` were installed
+    pub fn mark_installed(&self, requirements: &Requirements) -> Result<(), PythonEnvError> {
+        Ok(self.env.mark_installed(&requirements.fingerprint)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> ProjectFile {
+        ProjectFile {
+            name: name.to_string(),
+            content: content.to_string(),
+            language: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_script_dependencies() {
+        let source = "# /// script\n# requires-python = \">=3.11\"\n# dependencies = [\n#   \"requests<3\",\n#   \"rich\",\n# ]\n# ///\n\nimport requests\n";
+        assert_eq!(
+            script_dependencies(source).unwrap(),
+            Some(vec!["requests<3".to_string(), "rich".to_string()])
+        );
+
+        assert_eq!(script_dependencies("import os\n").unwrap(), None);
+        // Unterminated blocks are ignored
+        assert_eq!(
+            script_dependencies("# /// script\n# dependencies = []\nimport os\n").unwrap(),
+            None
+        );
+        assert!(script_dependencies("# /// script\n# dependencies = [\n# ///\n").is_err());
+    }
+
+    #[test]
+    fn test_find_requirements() {
+        let entry = file("main.py", "import numpy\n");
+        assert_eq!(
+            find_requirements(&[entry.clone()], &entry, None).unwrap(),
+            None
+        );
+
+        let files = [entry.clone(), file(REQUIREMENTS_FILE, "numpy\n")];
+        let first = find_requirements(&files, &entry, None).unwrap().unwrap();
+        assert_eq!(first.file.as_deref(), Some(REQUIREMENTS_FILE));
+
+        // Changing the requirements changes the fingerprint
+        let files = [entry.clone(), file(REQUIREMENTS_FILE, "numpy==2.0\n")];
+        let second = find_requirements(&files, &entry, None).unwrap().unwrap();
+        assert_ne!(first.fingerprint, second.fingerprint);
+
+        // So does installing from a wheelhouse instead of the index
+        let offline = find_requirements(&files, &entry, Some("/wheels"))
+            .unwrap()
+            .unwrap();
+        assert_ne!(second.fingerprint, offline.fingerprint);
+        let moved = find_requirements(&files, &entry, Some("/other"))
+            .unwrap()
+            .unwrap();
+        assert_ne!(offline.fingerprint, moved.fingerprint);
+    }
+}
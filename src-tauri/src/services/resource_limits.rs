@@ -0,0 +1,200 @@
+//! Enforcement of per-run resource limits.
+//!
+//! CPU time and memory are capped with rlimits inherited by the program and
+//! everything it spawns. Compile and install steps only get the timeout; see
+//! [`step_limits`]. Wall-clock time and output size are watched by the output
+//! streamer, which kills the program itself.
+
+use crate::models::{LimitKind, RunLimits};
+use std::process::ExitStatus;
+use tokio::process::Command;
+
+/// Messages runtimes print when an allocation fails under `RLIMIT_AS`
+const OUT_OF_MEMORY_MARKERS: &[&str] = &[
+    "MemoryError",
+    "memory allocation of",
+    "std::bad_alloc",
+    "java.lang.OutOfMemoryError",
+    "JavaScript heap out of memory",
+    "Failed to reserve virtual memory",
+    "Cannot allocate memory",
+];
+
+/// Apply the CPU time and memory limits to a command before it is spawned
+pub fn apply_rlimits(cmd: &mut Command, limits: &RunLimits) {
+    #[cfg(unix)]
+    {
+        // The soft CPU limit sends SIGXCPU, the hard limit one second later SIGKILL
+        let cpu = limits.cpu_time_secs.map(|secs| libc::rlimit {
+            rlim_cur: secs,
+            rlim_max: secs.saturating_add(1),
+        });
+        let memory = limits.max_memory_mb.map(|mb| {
+            let bytes = mb.saturating_mul(1024 * 1024);
+            libc::rlimit {
+                rlim_cur: bytes,
+                rlim_max: bytes,
+            }
+        });
+
+        if cpu.is_none() && memory.is_none() {
+            return;
+        }
+
+        // SAFETY: the closure runs between fork and exec and only calls
+        // setrlimit, which is async-signal-safe
+        unsafe {
+            cmd.pre_exec(move || {
+                if let Some(limit) = cpu {
+                    if libc::setrlimit(libc::RLIMIT_CPU, std::ptr::addr_of!(limit)) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                if let Some(limit) = memory {
+                    if libc::setrlimit(libc::RLIMIT_AS, std::ptr::addr_of!(limit)) != 0 {
+                        return Err(std::io::Error::last_os_error());
+                    }
+                }
+                Ok(())
+            });
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = (cmd, limits);
+    }
+}
+
+/// The limits for a step that builds or sets up the program: its timeout
+/// only. Compilers, package managers and JVM launchers need more CPU time and
+/// address space than the program is given.
+pub fn step_limits(limits: &RunLimits) -> RunLimits {
+    RunLimits {
+        timeout_ms: limits.timeout_ms,
+        cpu_time_secs: None,
+        max_memory_mb: None,
+        max_output_bytes: None,
+    }
+}
+
+/// Work out whether a program that exited on its own was stopped by an rlimit
+pub fn detect_exceeded(status: &ExitStatus, stderr: &str, limits: &RunLimits) -> Option<LimitKind> {
+    if status.success() {
+        return None;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if limits.cpu_time_secs.is_some() && status.signal() == Some(libc::SIGXCPU) {
+            return Some(LimitKind::CpuTime);
+        }
+    }
+
+    if limits.max_memory_mb.is_some() && is_out_of_memory(stderr) {
+        return Some(LimitKind::Memory);
+    }
+
+    None
+}
+
+fn is_out_of_memory(stderr: &str) -> bool {
+    OUT_OF_MEMORY_MARKERS
+        .iter()
+        .any(|marker| stderr.contains(marker))
+}
+
+/// Format the line appended to stderr when a limit stops the program
+pub fn limit_message(kind: LimitKind, limits: &RunLimits) -> String {
+    let detail = match kind {
+        LimitKind::Timeout => limits
+            .timeout_ms
+            .map(|ms| format!("the {ms} ms time limit")),
+        LimitKind::CpuTime => limits
+            .cpu_time_secs
+            .map(|secs| format!("the {secs} s CPU time limit")),
+        LimitKind::Memory => limits
+            .max_memory_mb
+            .map(|mb| format!("the {mb} MB memory limit")),
+        LimitKind::Output => limits
+            .max_output_bytes
+            .map(|bytes| format!("the {bytes} byte output limit")),
+    };
+
+    format!(
+        "\nProgram stopped: exceeded {}\n",
+        detail.unwrap_or_else(|| "a resource limit".to_string())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_out_of_memory() {
+        assert!(is_out_of_memory(
+            "Traceback (most recent call last):\n  File \"main.py\", line 1\nMemoryError\n"
+        ));
+        assert!(is_out_of_memory(
+            "memory allocation of 4294967296 bytes failed\n"
+        ));
+        assert!(!is_out_of_memory("IndexError: list index out of range\n"));
+    }
+
+    #[test]
+    fn test_limit_message() {
+        let limits = RunLimits {
+            timeout_ms: Some(5000),
+            ..RunLimits::default()
+        };
+        assert!(limit_message(LimitKind::Timeout, &limits).contains("5000 ms time limit"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cpu_limit_stops_busy_loop() {
+        let limits = RunLimits {
+            cpu_time_secs: Some(1),
+            ..RunLimits::default()
+        };
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg("while :; do :; done");
+        apply_rlimits(&mut cmd, &limits);
+
+        let status = tauri::async_runtime::block_on(async { cmd.status().await.unwrap() });
+        assert_eq!(
+            detect_exceeded(&status, "", &limits),
+            Some(LimitKind::CpuTime)
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_steps_are_not_capped() {
+        let limits = RunLimits {
+            timeout_ms: Some(5000),
+            cpu_time_secs: Some(1),
+            max_memory_mb: Some(64),
+            ..RunLimits::default()
+        };
+        let ulimits = |limits: &RunLimits| {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c").arg("ulimit -t; ulimit -v");
+            apply_rlimits(&mut cmd, limits);
+            let output = tauri::async_runtime::block_on(async { cmd.output().await.unwrap() });
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        assert_eq!(ulimits(&limits), "1\n65536\n");
+        // A step inherits the app's own limits, as if none were set
+        let step = step_limits(&limits);
+        let unlimited = RunLimits {
+            cpu_time_secs: None,
+            ..RunLimits::default()
+        };
+        assert_eq!(step.timeout_ms, Some(5000));
+        assert_eq!(ulimits(&step), ulimits(&unlimited));
+    }
+}
@@ -0,0 +1,137 @@
+//! Applying a project's [`RunConfig`] to the program command.
+
+use crate::models::RunConfig;
+use crate::services::{Workspace, WorkspaceError};
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::process::Command;
+
+#[derive(Error, Debug)]
+pub enum RunConfigError {
+    #[error("Failed to read env file {0}: {1}")]
+    EnvFile(String, std::io::Error),
+    #[error("Invalid line {line} in env file: {content}")]
+    EnvSyntax { line: usize, content: String },
+    #[error(transparent)]
+    Workspace(#[from] WorkspaceError),
+    #[error("Working directory does not exist: {0}")]
+    MissingWorkingDir(String),
+}
+
+/// Add the configured arguments, environment and working directory to a
+/// program command.
+///
+/// Relative paths resolve against the workspace, so `.env` and `data/` refer
+/// to project files. Variables set in the config override the env file.
+pub fn apply_run_config(
+    cmd: &mut Command,
+    config: &RunConfig,
+    workspace: &Workspace,
+) -> Result<(), RunConfigError> {
+    cmd.args(&config.args);
+
+    if let Some(env_file) = &config.env_file {
+        let path = resolve(workspace, env_file)?;
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| RunConfigError::EnvFile(env_file.clone(), e))?;
+        cmd.envs(parse_dotenv(&content)?);
+    }
+    cmd.envs(&config.env);
+
+    if let Some(working_dir) = &config.working_dir {
+        let path = resolve(workspace, working_dir)?;
+        if !path.is_dir() {
+            return Err(RunConfigError::MissingWorkingDir(working_dir.clone()));
+        }
+        cmd.current_dir(path);
+    }
+
+    Ok(())
+}
+
+/// Absolute paths are used as-is; relative ones must stay inside the workspace
+fn resolve(workspace: &Workspace, path: &str) -> Result<PathBuf, RunConfigError> {
+    if Path::new(path).is_absolute() {
+        Ok(PathBuf::from(path))
+    } else {
+        Ok(workspace.resolve(path)?)
+    }
+}
+
+/// Parse a `.env` file: `KEY=value` lines, with optional `export` prefixes,
+/// `#` comments and single or double quoted values
+pub fn parse_dotenv(content: &str) -> Result<Vec<(String, String)>, RunConfigError> {
+    let mut vars = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let invalid = || RunConfigError::EnvSyntax {
+            line: index + 1,
+            content: line.to_string(),
+        };
+
+        let (key, value) = line.split_once('=').ok_or_else(invalid)?;
+        let key = key.trim();
+        if key.is_empty() || key.contains(char::is_whitespace) {
+            return Err(invalid());
+        }
+        vars.push((
+            key.to_string(),
+            parse_value(value.trim()).ok_or_else(invalid)?,
+        ));
+    }
+    Ok(vars)
+}
+
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(rest) = value.strip_prefix('"') {
+        let end = rest.rfind('"')?;
+        return Some(
+            rest[..end]
+                .replace("\\n", "\n")
+                .replace("\\\"", "\"")
+                .replace("\\\\", "\\"),
+        );
+    }
+    if let Some(rest) = value.strip_prefix('\'') {
+        let end = rest.rfind('\'')?;
+        return Some(rest[..end].to_string());
+    }
+    // Unquoted values end at an inline comment
+    let value = value.split(" #").next().unwrap_or_default();
+    Some(value.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dotenv() {
+        let content = "# settings\nexport DEBUG=1\nNAME = \"Ada Lovelace\"\nGREETING=\"hi\\nthere\"\nRAW='$HOME \\n'\nURL=http://localhost#anchor # comment\n\nEMPTY=\n";
+        assert_eq!(
+            parse_dotenv(content).unwrap(),
+            vec![
+                ("DEBUG".to_string(), "1".to_string()),
+                ("NAME".to_string(), "Ada Lovelace".to_string()),
+                ("GREETING".to_string(), "hi\nthere".to_string()),
+                ("RAW".to_string(), "$HOME \\n".to_string()),
+                ("URL".to_string(), "http://localhost#anchor".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_dotenv_rejects_invalid_lines() {
+        assert!(matches!(
+            parse_dotenv("OK=1\nnot a variable\n"),
+            Err(RunConfigError::EnvSyntax { line: 2, .. })
+        ));
+        assert!(parse_dotenv("BAD KEY=1\n").is_err());
+        assert!(parse_dotenv("OPEN=\"unterminated\n").is_err());
+    }
+}
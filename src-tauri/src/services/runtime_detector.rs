@@ -1,30 +1,142 @@
+use crate::services::runtime_overrides::{RuntimeOverrides, RUNTIMES_FILE};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::path::Path;
 use std::process::Command;
 
 /// Information about a runtime environment
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all(serialize = "camelCase"))]
 pub struct RuntimeInfo {
-    pub name: &'static str,
-    pub command: &'static str,
-    pub download_url: &'static str,
+    pub name: Cow<'static, str>,
+    pub command: Cow<'static, str>,
+    #[serde(default)]
+    pub download_url: Cow<'static, str>,
+    /// Install commands keyed by platform (`macos`, `windows`, `linux` or a
+    /// distro such as `debian`), tried before the built-in ones
+    #[serde(default)]
+    pub install: BTreeMap<String, String>,
 }
 
 /// Platform and package manager information
 #[derive(Debug)]
 pub enum Platform {
-    MacOS { has_homebrew: bool },
-    Linux { distro: LinuxDistro },
-    Windows { has_winget: bool },
+    MacOS {
+        has_homebrew: bool,
+    },
+    Linux {
+        distro: LinuxDistro,
+        has_snap: bool,
+        /// Running inside a Flatpak sandbox, which can't see the host's packages
+        in_flatpak: bool,
+    },
+    Windows {
+        has_winget: bool,
+    },
     Unknown,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LinuxDistro {
-    Debian,  // apt (Ubuntu, Debian, Pop!_OS, etc.)
-    Fedora,  // dnf (Fedora, RHEL, CentOS)
-    Arch,    // pacman (Arch, Manjaro, EndeavourOS)
+    Debian,   // apt (Ubuntu, Debian, Pop!_OS, etc.)
+    Fedora,   // dnf (Fedora, RHEL, CentOS, Rocky)
+    Arch,     // pacman (Arch, Manjaro, EndeavourOS)
+    OpenSuse, // zypper (Leap, Tumbleweed, SLES)
+    Alpine,   // apk
+    Void,     // xbps
+    Gentoo,   // emerge
+    NixOS,    // nix
     Unknown,
 }
 
+impl LinuxDistro {
+    /// The distro named by `os-release` contents: its `ID`, else the first
+    /// `ID_LIKE` entry that is a known family
+    pub fn from_os_release(content: &str) -> Option<Self> {
+        let mut id = "";
+        let mut id_like = "";
+        for line in content.lines() {
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+            match key.trim() {
+                "ID" => id = value,
+                "ID_LIKE" => id_like = value,
+                _ => {}
+            }
+        }
+        std::iter::once(id)
+            .chain(id_like.split_whitespace())
+            .find_map(|id| Self::from_id(&id.to_ascii_lowercase()))
+    }
+
+    fn from_id(id: &str) -> Option<Self> {
+        match id {
+            "debian" | "ubuntu" => Some(Self::Debian),
+            "fedora" | "rhel" | "centos" => Some(Self::Fedora),
+            "arch" => Some(Self::Arch),
+            "suse" | "sles" => Some(Self::OpenSuse),
+            id if id.starts_with("opensuse") => Some(Self::OpenSuse),
+            "alpine" => Some(Self::Alpine),
+            "void" => Some(Self::Void),
+            "gentoo" => Some(Self::Gentoo),
+            "nixos" => Some(Self::NixOS),
+            _ => None,
+        }
+    }
+
+    /// Key for this distro in a runtime's `install` table
+    fn key(self) -> Option<&'static str> {
+        match self {
+            Self::Debian => Some("debian"),
+            Self::Fedora => Some("fedora"),
+            Self::Arch => Some("arch"),
+            Self::OpenSuse => Some("opensuse"),
+            Self::Alpine => Some("alpine"),
+            Self::Void => Some("void"),
+            Self::Gentoo => Some("gentoo"),
+            Self::NixOS => Some("nixos"),
+            Self::Unknown => None,
+        }
+    }
+
+    /// The package manager's command installing `packages` (space separated)
+    fn install(self, packages: &str) -> Option<String> {
+        let prefix = match self {
+            Self::Debian => "sudo apt install",
+            Self::Fedora => "sudo dnf install",
+            Self::Arch => "sudo pacman -S",
+            Self::OpenSuse => "sudo zypper install",
+            Self::Alpine => "sudo apk add",
+            Self::Void => "sudo xbps-install -S",
+            Self::Gentoo => "sudo emerge --ask",
+            Self::NixOS => {
+                let attrs: Vec<_> = packages.split(' ').map(|p| format!("nixos.{p}")).collect();
+                return Some(format!("nix-env -iA {}", attrs.join(" ")));
+            }
+            Self::Unknown => return None,
+        };
+        Some(format!("{prefix} {packages}"))
+    }
+}
+
+impl Platform {
+    /// Keys for this platform in a runtime's `install` table, most specific first
+    fn install_keys(&self) -> Vec<&'static str> {
+        match self {
+            Platform::MacOS { .. } => vec!["macos"],
+            Platform::Linux {
+                in_flatpak: true, ..
+            } => vec!["flatpak", "linux"],
+            Platform::Linux { distro, .. } => distro.key().into_iter().chain(["linux"]).collect(),
+            Platform::Windows { .. } => vec!["windows"],
+            Platform::Unknown => Vec::new(),
+        }
+    }
+}
+
 /// Runtime detection result
 #[derive(Debug)]
 pub struct RuntimeCheckResult {
@@ -33,45 +145,89 @@ pub struct RuntimeCheckResult {
 }
 
 impl RuntimeInfo {
-    pub const NODE: RuntimeInfo = RuntimeInfo {
-        name: "Node.js",
-        command: "node",
-        download_url: "https://nodejs.org/",
-    };
+    const fn builtin(
+        name: &'static str,
+        command: &'static str,
+        download_url: &'static str,
+    ) -> Self {
+        Self {
+            name: Cow::Borrowed(name),
+            command: Cow::Borrowed(command),
+            download_url: Cow::Borrowed(download_url),
+            install: BTreeMap::new(),
+        }
+    }
 
-    pub const PYTHON: RuntimeInfo = RuntimeInfo {
-        name: "Python",
-        command: "python3",
-        download_url: "https://www.python.org/downloads/",
-    };
+    pub const NODE: RuntimeInfo = RuntimeInfo::builtin("Node.js", "node", "https://nodejs.org/");
 
-    pub const RUST: RuntimeInfo = RuntimeInfo {
-        name: "Rust",
-        command: "rustc",
-        download_url: "https://rustup.rs/",
-    };
+    pub const PYTHON: RuntimeInfo =
+        RuntimeInfo::builtin("Python", "python3", "https://www.python.org/downloads/");
 
-    pub const JAVA: RuntimeInfo = RuntimeInfo {
-        name: "Java",
-        command: "java",
-        download_url: "https://adoptium.net/",
-    };
+    pub const RUST: RuntimeInfo = RuntimeInfo::builtin("Rust", "rustc", "https://rustup.rs/");
 
-    pub const JAVAC: RuntimeInfo = RuntimeInfo {
-        name: "Java Compiler",
-        command: "javac",
-        download_url: "https://adoptium.net/",
-    };
+    pub const CARGO: RuntimeInfo = RuntimeInfo::builtin("Cargo", "cargo", "https://rustup.rs/");
 
-    pub const NPX: RuntimeInfo = RuntimeInfo {
-        name: "npx (Node.js)",
-        command: "npx",
-        download_url: "https://nodejs.org/",
-    };
+    pub const JAVA: RuntimeInfo = RuntimeInfo::builtin("Java", "java", "https://adoptium.net/");
+
+    pub const JAVAC: RuntimeInfo =
+        RuntimeInfo::builtin("Java Compiler", "javac", "https://adoptium.net/");
+
+    pub const KOTLINC: RuntimeInfo =
+        RuntimeInfo::builtin("Kotlin", "kotlinc", "https://kotlinlang.org/docs/command-line.html");
+
+    pub const SCALA_CLI: RuntimeInfo =
+        RuntimeInfo::builtin("Scala CLI", "scala-cli", "https://scala-cli.virtuslab.org/install");
+
+    pub const SCALAC: RuntimeInfo =
+        RuntimeInfo::builtin("Scala", "scalac", "https://www.scala-lang.org/download/");
+
+    pub const GROOVY: RuntimeInfo =
+        RuntimeInfo::builtin("Groovy", "groovy", "https://groovy.apache.org/download.html");
+
+    pub const GO: RuntimeInfo = RuntimeInfo::builtin("Go", "go", "https://go.dev/dl/");
+
+    pub const GCC: RuntimeInfo = RuntimeInfo::builtin("GCC", "gcc", "https://gcc.gnu.org/install/");
+
+    pub const GXX: RuntimeInfo = RuntimeInfo::builtin("G++", "g++", "https://gcc.gnu.org/install/");
+
+    pub const CLANG: RuntimeInfo =
+        RuntimeInfo::builtin("Clang", "clang", "https://releases.llvm.org/");
+
+    pub const CLANGXX: RuntimeInfo =
+        RuntimeInfo::builtin("Clang++", "clang++", "https://releases.llvm.org/");
+
+    pub const RUBY: RuntimeInfo =
+        RuntimeInfo::builtin("Ruby", "ruby", "https://www.ruby-lang.org/en/downloads/");
+
+    pub const PHP: RuntimeInfo =
+        RuntimeInfo::builtin("PHP", "php", "https://www.php.net/downloads");
+
+    pub const PERL: RuntimeInfo =
+        RuntimeInfo::builtin("Perl", "perl", "https://www.perl.org/get.html");
+
+    pub const LUA: RuntimeInfo =
+        RuntimeInfo::builtin("Lua", "lua", "https://www.lua.org/download.html");
+
+    pub const BASH: RuntimeInfo =
+        RuntimeInfo::builtin("Bash", "bash", "https://www.gnu.org/software/bash/");
+
+    pub const DENO: RuntimeInfo = RuntimeInfo::builtin("Deno", "deno", "https://deno.com/");
+
+    pub const BUN: RuntimeInfo = RuntimeInfo::builtin("Bun", "bun", "https://bun.sh/");
+
+    pub const NPX: RuntimeInfo =
+        RuntimeInfo::builtin("npx (Node.js)", "npx", "https://nodejs.org/");
+
+    pub const NPM: RuntimeInfo =
+        RuntimeInfo::builtin("npm (Node.js)", "npm", "https://nodejs.org/");
 }
 
-/// Check if a command exists in PATH
-fn command_exists(cmd: &str) -> bool {
+/// Check if a command exists in PATH, or as a file when given a path
+pub fn command_exists(cmd: &str) -> bool {
+    if cmd.contains(['/', std::path::MAIN_SEPARATOR]) {
+        return Path::new(cmd).is_file();
+    }
+
     #[cfg(target_os = "windows")]
     {
         Command::new("where")
@@ -101,8 +257,11 @@ pub fn detect_platform() -> Platform {
 
     #[cfg(target_os = "linux")]
     {
-        let distro = detect_linux_distro();
-        Platform::Linux { distro }
+        Platform::Linux {
+            distro: detect_linux_distro(),
+            has_snap: command_exists("snap"),
+            in_flatpak: Path::new("/.flatpak-info").exists(),
+        }
     }
 
     #[cfg(target_os = "windows")]
@@ -119,123 +278,234 @@ pub fn detect_platform() -> Platform {
 
 #[cfg(target_os = "linux")]
 fn detect_linux_distro() -> LinuxDistro {
-    // Check for common package managers
-    if command_exists("apt") {
-        return LinuxDistro::Debian;
-    }
-    if command_exists("dnf") {
-        return LinuxDistro::Fedora;
+    // os-release names the distro, whichever package managers are installed
+    let os_release = ["/etc/os-release", "/usr/lib/os-release"]
+        .iter()
+        .find_map(|path| std::fs::read_to_string(path).ok());
+    if let Some(distro) = os_release.and_then(|content| LinuxDistro::from_os_release(&content)) {
+        return distro;
     }
-    if command_exists("pacman") {
-        return LinuxDistro::Arch;
+
+    // Fallback: a distro we don't know by name, using a package manager we do
+    let managers = [
+        ("apt", LinuxDistro::Debian),
+        ("dnf", LinuxDistro::Fedora),
+        ("pacman", LinuxDistro::Arch),
+        ("zypper", LinuxDistro::OpenSuse),
+        ("apk", LinuxDistro::Alpine),
+        ("xbps-install", LinuxDistro::Void),
+        ("emerge", LinuxDistro::Gentoo),
+    ];
+    managers
+        .into_iter()
+        .find(|(manager, _)| command_exists(manager))
+        .map_or(LinuxDistro::Unknown, |(_, distro)| distro)
+}
+
+/// Get install command for a runtime based on platform
+pub fn get_install_command(runtime: &RuntimeInfo, platform: &Platform) -> Option<String> {
+    if let Some(cmd) = platform
+        .install_keys()
+        .iter()
+        .find_map(|key| runtime.install.get(*key))
+    {
+        return Some(cmd.clone());
     }
 
-    // Fallback: try to read /etc/os-release
-    if let Ok(content) = std::fs::read_to_string("/etc/os-release") {
-        let content_lower = content.to_lowercase();
-        if content_lower.contains("ubuntu")
-            || content_lower.contains("debian")
-            || content_lower.contains("pop")
-            || content_lower.contains("mint")
-        {
-            return LinuxDistro::Debian;
+    let command = runtime.command.as_ref();
+    match platform {
+        // On macOS, gcc is an alias for the Xcode tools' clang
+        Platform::MacOS { .. } if matches!(command, "gcc" | "g++" | "clang" | "clang++") => {
+            Some("xcode-select --install".to_string())
         }
-        if content_lower.contains("fedora")
-            || content_lower.contains("rhel")
-            || content_lower.contains("centos")
-        {
-            return LinuxDistro::Fedora;
+        Platform::MacOS { has_homebrew: true } => {
+            homebrew_formula(command).map(|formula| format!("brew install {formula}"))
         }
-        if content_lower.contains("arch")
-            || content_lower.contains("manjaro")
-            || content_lower.contains("endeavour")
-            || content_lower.contains("cachyos")
-        {
-            return LinuxDistro::Arch;
+        Platform::Linux {
+            in_flatpak: true, ..
+        } => flatpak_extension(command).map(|extension| {
+            format!("flatpak install flathub org.freedesktop.Sdk.Extension.{extension}")
+        }),
+        Platform::Linux {
+            distro, has_snap, ..
+        } => distro_package(command, *distro)
+            .and_then(|packages| distro.install(packages))
+            .or_else(|| {
+                snap_package(command)
+                    .filter(|_| *has_snap)
+                    .map(|snap| format!("sudo snap install {snap} --classic"))
+            })
+            .or_else(|| installer(command).map(str::to_string)),
+        Platform::Windows { has_winget: true } => {
+            winget_package(command).map(|id| format!("winget install {id}"))
         }
+        _ => None,
     }
+}
 
-    LinuxDistro::Unknown
+fn homebrew_formula(command: &str) -> Option<&'static str> {
+    let formula = match command {
+        "node" | "npx" | "npm" => "node",
+        "deno" => "deno",
+        "bun" => "oven-sh/bun/bun",
+        "python3" => "python",
+        "rustc" | "cargo" => "rust",
+        "java" | "javac" => "openjdk",
+        "kotlinc" | "kotlin" => "kotlin",
+        // Scala CLI fetches the compiler and a JVM itself
+        "scala-cli" | "scalac" => "Virtuslab/scala-cli/scala-cli",
+        "groovy" => "groovy",
+        "go" => "go",
+        "ruby" => "ruby",
+        "php" => "php",
+        "perl" => "perl",
+        "lua" => "lua",
+        "bash" => "bash",
+        _ => return None,
+    };
+    Some(formula)
 }
 
-/// Get install command for a runtime based on platform
-fn get_install_command(runtime: &RuntimeInfo, platform: &Platform) -> Option<String> {
-    match (runtime.command, platform) {
-        // Node.js
-        ("node" | "npx", Platform::MacOS { has_homebrew: true }) => {
-            Some("brew install node".to_string())
-        }
-        ("node" | "npx", Platform::Linux { distro: LinuxDistro::Debian }) => {
-            Some("sudo apt install nodejs npm".to_string())
-        }
-        ("node" | "npx", Platform::Linux { distro: LinuxDistro::Fedora }) => {
-            Some("sudo dnf install nodejs npm".to_string())
-        }
-        ("node" | "npx", Platform::Linux { distro: LinuxDistro::Arch }) => {
-            Some("sudo pacman -S nodejs npm".to_string())
-        }
-        ("node" | "npx", Platform::Windows { has_winget: true }) => {
-            Some("winget install OpenJS.NodeJS".to_string())
-        }
+fn winget_package(command: &str) -> Option<&'static str> {
+    let id = match command {
+        "node" | "npx" | "npm" => "OpenJS.NodeJS",
+        "deno" => "DenoLand.Deno",
+        "bun" => "Oven-sh.Bun",
+        "python3" => "Python.Python.3.12",
+        "rustc" | "cargo" => "Rustlang.Rustup",
+        "java" | "javac" => "EclipseAdoptium.Temurin.21.JDK",
+        "go" => "GoLang.Go",
+        "gcc" | "g++" => "BrechtSanders.WinLibs.POSIX.UCRT",
+        "clang" | "clang++" => "LLVM.LLVM",
+        "ruby" => "RubyInstallerTeam.Ruby.3.3",
+        "php" => "PHP.PHP.8.3",
+        "perl" => "StrawberryPerl.StrawberryPerl",
+        "lua" => "DEVCOM.Lua",
+        // Git for Windows ships Git Bash
+        "bash" => "Git.Git",
+        _ => return None,
+    };
+    Some(id)
+}
 
-        // Python
-        ("python3", Platform::MacOS { has_homebrew: true }) => {
-            Some("brew install python".to_string())
-        }
-        ("python3", Platform::Linux { distro: LinuxDistro::Debian }) => {
-            Some("sudo apt install python3".to_string())
-        }
-        ("python3", Platform::Linux { distro: LinuxDistro::Fedora }) => {
-            Some("sudo dnf install python3".to_string())
-        }
-        ("python3", Platform::Linux { distro: LinuxDistro::Arch }) => {
-            Some("sudo pacman -S python".to_string())
-        }
-        ("python3", Platform::Windows { has_winget: true }) => {
-            Some("winget install Python.Python.3.12".to_string())
-        }
+/// Packages providing `command` in a distro's repositories, space separated
+fn distro_package(command: &str, distro: LinuxDistro) -> Option<&'static str> {
+    use LinuxDistro::{Alpine, Arch, Debian, Fedora, Gentoo, NixOS, OpenSuse, Void};
+
+    let packages = match (command, distro) {
+        ("node" | "npx" | "npm", Debian | Fedora | Arch | Alpine) => "nodejs npm",
+        ("node" | "npx" | "npm", OpenSuse) => "nodejs-default npm-default",
+        ("node" | "npx" | "npm", Void | NixOS) => "nodejs",
+        ("node" | "npx" | "npm", Gentoo) => "net-libs/nodejs",
+        ("deno", Arch | NixOS) => "deno",
+        ("bun", NixOS) => "bun",
+        ("python3", Arch) => "python",
+        ("python3", Gentoo) => "dev-lang/python",
+        ("python3", _) => "python3",
+        // Elsewhere the packaged Rust lags behind; rustup is the usual route
+        ("rustc" | "cargo", Arch) => "rust",
+        ("rustc" | "cargo", Gentoo) => "dev-lang/rust",
+        ("rustc" | "cargo", NixOS) => "rustc cargo",
+        ("java" | "javac", Debian) => "default-jdk",
+        ("java" | "javac", Fedora) => "java-latest-openjdk-devel",
+        ("java" | "javac", Arch) => "jdk-openjdk",
+        ("java" | "javac", OpenSuse) => "java-21-openjdk-devel",
+        ("java" | "javac", Alpine | Void) => "openjdk21",
+        ("java" | "javac", Gentoo) => "dev-java/openjdk",
+        ("java" | "javac", NixOS) => "jdk",
+        ("kotlinc" | "kotlin", Arch | NixOS) => "kotlin",
+        ("scala-cli" | "scalac", NixOS) => "scala-cli",
+        ("groovy", Debian | Arch | NixOS) => "groovy",
+        // The distro packages include gofmt
+        ("go", Debian) => "golang-go",
+        ("go", Fedora) => "golang",
+        ("go", Gentoo) => "dev-lang/go",
+        ("go", _) => "go",
+        ("gcc" | "g++", Debian) => "build-essential",
+        ("gcc" | "g++", Fedora | OpenSuse) => "gcc gcc-c++",
+        ("gcc" | "g++", Arch | Void) => "base-devel",
+        ("gcc" | "g++", Alpine) => "build-base",
+        ("gcc" | "g++", Gentoo) => "sys-devel/gcc",
+        ("gcc" | "g++", NixOS) => "gcc",
+        ("clang" | "clang++", Gentoo) => "llvm-core/clang",
+        ("clang" | "clang++", _) => "clang",
+        ("ruby", Gentoo) => "dev-lang/ruby",
+        ("ruby", _) => "ruby",
+        // Only the command-line interpreter, not a web server module
+        ("php", Debian | Fedora) => "php-cli",
+        ("php", OpenSuse) => "php8",
+        ("php", Gentoo) => "dev-lang/php",
+        ("php", Arch | Void | NixOS) => "php",
+        ("perl", Gentoo) => "dev-lang/perl",
+        ("perl", _) => "perl",
+        // Debian only ships versioned packages; lua5.4 provides `lua`
+        ("lua", Debian) => "lua5.4",
+        ("lua", OpenSuse) => "lua54",
+        ("lua", Gentoo) => "dev-lang/lua",
+        ("lua", Fedora | Arch | NixOS) => "lua",
+        ("bash", Gentoo) => "app-shells/bash",
+        ("bash", _) => "bash",
+        _ => return None,
+    };
+    Some(packages)
+}
 
-        // Rust
-        ("rustc", Platform::MacOS { has_homebrew: true }) => Some("brew install rust".to_string()),
-        ("rustc", Platform::Linux { distro: LinuxDistro::Arch }) => {
-            Some("sudo pacman -S rust".to_string())
-        }
-        ("rustc", Platform::Linux { .. }) => {
-            Some("curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh".to_string())
-        }
-        ("rustc", Platform::Windows { has_winget: true }) => {
-            Some("winget install Rustlang.Rustup".to_string())
-        }
+/// Classic snap for `command`, for distros that don't package it
+fn snap_package(command: &str) -> Option<&'static str> {
+    match command {
+        "node" | "npx" | "npm" => Some("node"),
+        "kotlinc" | "kotlin" => Some("kotlin"),
+        "go" => Some("go"),
+        "ruby" => Some("ruby"),
+        _ => None,
+    }
+}
 
-        // Java
-        ("java" | "javac", Platform::MacOS { has_homebrew: true }) => {
-            Some("brew install openjdk".to_string())
-        }
-        ("java" | "javac", Platform::Linux { distro: LinuxDistro::Debian }) => {
-            Some("sudo apt install default-jdk".to_string())
-        }
-        ("java" | "javac", Platform::Linux { distro: LinuxDistro::Fedora }) => {
-            Some("sudo dnf install java-latest-openjdk-devel".to_string())
-        }
-        ("java" | "javac", Platform::Linux { distro: LinuxDistro::Arch }) => {
-            Some("sudo pacman -S jdk-openjdk".to_string())
-        }
-        ("java" | "javac", Platform::Windows { has_winget: true }) => {
-            Some("winget install EclipseAdoptium.Temurin.21.JDK".to_string())
-        }
+/// Flathub SDK extension providing `command` to sandboxed apps
+fn flatpak_extension(command: &str) -> Option<&'static str> {
+    match command {
+        "node" | "npx" | "npm" => Some("node20"),
+        "rustc" | "cargo" => Some("rust-stable"),
+        "java" | "javac" => Some("openjdk21"),
+        "go" => Some("golang"),
+        "clang" | "clang++" => Some("llvm18"),
+        _ => None,
+    }
+}
 
+/// The upstream installer, on any Linux
+fn installer(command: &str) -> Option<&'static str> {
+    match command {
+        "deno" => Some("curl -fsSL https://deno.land/install.sh | sh"),
+        "bun" => Some("curl -fsSL https://bun.sh/install | bash"),
+        "rustc" | "cargo" => Some("curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh"),
+        // Not packaged by most distros; SDKMAN! is the usual route
+        "kotlinc" | "kotlin" => Some("sdk install kotlin"),
+        "groovy" => Some("sdk install groovy"),
+        "scala-cli" | "scalac" => Some("curl -sSLf https://scala-cli.virtuslab.org/get | sh"),
         _ => None,
     }
 }
 
 /// Check if a runtime is available and return install hints if not
-pub fn check_runtime(runtime: &RuntimeInfo) -> RuntimeCheckResult {
-    if command_exists(runtime.command) {
+pub fn check_runtime(runtime: &RuntimeInfo, overrides: &RuntimeOverrides) -> RuntimeCheckResult {
+    if overrides.is_installed(&runtime.command) {
         return RuntimeCheckResult {
             available: true,
             install_hint: None,
         };
     }
+    // Installing the runtime wouldn't help when the configured path is wrong
+    if let Some(path) = overrides.get(&runtime.command).and_then(|o| o.path.as_ref()) {
+        return RuntimeCheckResult {
+            available: false,
+            install_hint: Some(format!(
+                "Error: {} is set to {} in {RUNTIMES_FILE}, which was not found\n",
+                runtime.name,
+                path.display()
+            )),
+        };
+    }
 
     let platform = detect_platform();
     let install_cmd = get_install_command(runtime, &platform);
@@ -257,7 +527,9 @@ fn format_install_hint(runtime: &RuntimeInfo, install_cmd: Option<String>) -> St
         hint.push_str(&format!("  {}\n\n", cmd));
     }
 
-    hint.push_str(&format!("Or download from: {}\n", runtime.download_url));
+    if !runtime.download_url.is_empty() {
+        hint.push_str(&format!("Or download from: {}\n", runtime.download_url));
+    }
 
     hint
 }
@@ -286,4 +558,133 @@ mod tests {
         assert!(hint.contains("brew install node"));
         assert!(hint.contains("https://nodejs.org/"));
     }
+
+    fn linux(distro: LinuxDistro) -> Platform {
+        Platform::Linux {
+            distro,
+            has_snap: false,
+            in_flatpak: false,
+        }
+    }
+
+    #[test]
+    fn test_install_hints_from_definition() {
+        let mut runtime = RuntimeInfo::PYTHON;
+        runtime
+            .install
+            .insert("arch".to_string(), "sudo pacman -S pypy3".to_string());
+
+        assert_eq!(
+            get_install_command(&runtime, &linux(LinuxDistro::Arch)).as_deref(),
+            Some("sudo pacman -S pypy3")
+        );
+        // Platforms the definition doesn't cover keep the built-in hints
+        assert_eq!(
+            get_install_command(&runtime, &linux(LinuxDistro::Fedora)).as_deref(),
+            Some("sudo dnf install python3")
+        );
+    }
+
+    #[test]
+    fn test_distro_from_os_release() {
+        let cases = [
+            // Arch, even with apt installed
+            (
+                "NAME=\"Arch Linux\"\nID=arch\nBUILD_ID=rolling\n",
+                Some(LinuxDistro::Arch),
+            ),
+            (
+                "NAME=\"Ubuntu\"\nID=ubuntu\nID_LIKE=debian\n",
+                Some(LinuxDistro::Debian),
+            ),
+            (
+                "NAME=\"Pop!_OS\"\nID=pop\nID_LIKE=\"ubuntu debian\"\n",
+                Some(LinuxDistro::Debian),
+            ),
+            (
+                "NAME=\"Manjaro Linux\"\nID=manjaro\nID_LIKE=arch\n",
+                Some(LinuxDistro::Arch),
+            ),
+            (
+                "NAME=\"Rocky Linux\"\nID=\"rocky\"\nID_LIKE=\"rhel centos fedora\"\n",
+                Some(LinuxDistro::Fedora),
+            ),
+            (
+                "NAME=openSUSE\nID=\"opensuse-tumbleweed\"\nID_LIKE=\"opensuse suse\"\n",
+                Some(LinuxDistro::OpenSuse),
+            ),
+            (
+                "NAME=\"Alpine Linux\"\nID=alpine\nVERSION_ID=3.19.1\n",
+                Some(LinuxDistro::Alpine),
+            ),
+            ("NAME=\"Void\"\nID=\"void\"\n", Some(LinuxDistro::Void)),
+            ("NAME=Gentoo\nID=gentoo\n", Some(LinuxDistro::Gentoo)),
+            (
+                "NAME=NixOS\nID=nixos\nID_LIKE=\"\"\n",
+                Some(LinuxDistro::NixOS),
+            ),
+            // A name mentioning another distro doesn't count
+            ("# comment\nNAME=\"Debian-based Arch\"\nID=solus\n", None),
+        ];
+        for (os_release, distro) in cases {
+            assert_eq!(
+                LinuxDistro::from_os_release(os_release),
+                distro,
+                "{os_release}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_install_commands() {
+        let hint =
+            |runtime: &RuntimeInfo, platform: &Platform| get_install_command(runtime, platform);
+        assert_eq!(
+            hint(&RuntimeInfo::GO, &linux(LinuxDistro::OpenSuse)).as_deref(),
+            Some("sudo zypper install go")
+        );
+        assert_eq!(
+            hint(&RuntimeInfo::GCC, &linux(LinuxDistro::Alpine)).as_deref(),
+            Some("sudo apk add build-base")
+        );
+        assert_eq!(
+            hint(&RuntimeInfo::NODE, &linux(LinuxDistro::Void)).as_deref(),
+            Some("sudo xbps-install -S nodejs")
+        );
+        assert_eq!(
+            hint(&RuntimeInfo::JAVA, &linux(LinuxDistro::Gentoo)).as_deref(),
+            Some("sudo emerge --ask dev-java/openjdk")
+        );
+        assert_eq!(
+            hint(&RuntimeInfo::CARGO, &linux(LinuxDistro::NixOS)).as_deref(),
+            Some("nix-env -iA nixos.rustc nixos.cargo")
+        );
+        // Not packaged: the upstream installer
+        assert_eq!(
+            hint(&RuntimeInfo::RUST, &linux(LinuxDistro::Debian)).as_deref(),
+            Some("curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh")
+        );
+
+        // Unknown distros fall back to snaps, where there is one
+        let mut unknown = linux(LinuxDistro::Unknown);
+        assert_eq!(hint(&RuntimeInfo::NODE, &unknown), None);
+        if let Platform::Linux { has_snap, .. } = &mut unknown {
+            *has_snap = true;
+        }
+        assert_eq!(
+            hint(&RuntimeInfo::NODE, &unknown).as_deref(),
+            Some("sudo snap install node --classic")
+        );
+        assert_eq!(hint(&RuntimeInfo::PYTHON, &unknown), None);
+
+        let flatpak = Platform::Linux {
+            distro: LinuxDistro::Debian,
+            has_snap: false,
+            in_flatpak: true,
+        };
+        assert_eq!(
+            hint(&RuntimeInfo::NODE, &flatpak).as_deref(),
+            Some("flatpak install flathub org.freedesktop.Sdk.Extension.node20")
+        );
+    }
 }
@@ -0,0 +1,353 @@
+//! Which of the known runtimes are installed, where, and at what version.
+//!
+//! Scanning runs every runtime's version command, so the result is cached
+//! in a [`RuntimeCache`] and only rescanned when asked to.
+
+use crate::models::TemplateType;
+use crate::services::language_registry::{LanguageDefinition, LanguageRunner};
+use crate::services::runtime_detector::{detect_platform, get_install_command, RuntimeInfo};
+use crate::services::runtime_overrides::RuntimeOverrides;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+/// How long a version command may take; JVM launchers are slow to start
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Runtimes listed even when no language names them
+const BUILTIN_RUNTIMES: &[RuntimeInfo] = &[
+    RuntimeInfo::PYTHON,
+    RuntimeInfo::NODE,
+    RuntimeInfo::NPM,
+    RuntimeInfo::DENO,
+    RuntimeInfo::BUN,
+    RuntimeInfo::RUST,
+    RuntimeInfo::CARGO,
+    RuntimeInfo::JAVA,
+    RuntimeInfo::JAVAC,
+    RuntimeInfo::KOTLINC,
+    RuntimeInfo::SCALA_CLI,
+    RuntimeInfo::SCALAC,
+    RuntimeInfo::GROOVY,
+    RuntimeInfo::GO,
+    RuntimeInfo::GCC,
+    RuntimeInfo::GXX,
+    RuntimeInfo::CLANG,
+    RuntimeInfo::CLANGXX,
+    RuntimeInfo::RUBY,
+    RuntimeInfo::PHP,
+    RuntimeInfo::PERL,
+    RuntimeInfo::LUA,
+    RuntimeInfo::BASH,
+];
+
+/// A runtime and what was found of it on this machine
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeStatus {
+    pub name: String,
+    pub command: String,
+    /// Where the command resolves on `PATH`; `None` when it isn't installed
+    pub path: Option<PathBuf>,
+    pub version: Option<String>,
+    /// Install command for this platform, when the runtime is missing
+    pub install_hint: Option<String>,
+    pub download_url: String,
+}
+
+/// Every known runtime, and the languages that can't run without one
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeInventory {
+    pub runtimes: Vec<RuntimeStatus>,
+    /// Language ids mapped to the missing toolchain, e.g. `"GCC or Clang"`
+    pub unavailable: BTreeMap<String, String>,
+}
+
+/// The last scan, shared by every window
+#[derive(Default)]
+pub struct RuntimeCache {
+    inventory: Mutex<Option<RuntimeInventory>>,
+}
+
+impl RuntimeCache {
+    /// The cached inventory, scanning first if there is none yet. A scan
+    /// already in progress is waited for rather than repeated.
+    pub async fn get(
+        &self,
+        languages: &[LanguageDefinition],
+        overrides: &RuntimeOverrides,
+    ) -> RuntimeInventory {
+        let mut inventory = self.inventory.lock().await;
+        if inventory.is_none() {
+            *inventory = Some(scan(languages, overrides).await);
+        }
+        inventory.clone().unwrap_or_default()
+    }
+
+    /// Scan again, e.g. after the user installed something
+    pub async fn refresh(
+        &self,
+        languages: &[LanguageDefinition],
+        overrides: &RuntimeOverrides,
+    ) -> RuntimeInventory {
+        let mut inventory = self.inventory.lock().await;
+        let fresh = scan(languages, overrides).await;
+        *inventory = Some(fresh.clone());
+        fresh
+    }
+}
+
+/// Probe the built-in runtimes and those of `languages`, in parallel, using
+/// the executables `overrides` configures
+pub async fn scan(
+    languages: &[LanguageDefinition],
+    overrides: &RuntimeOverrides,
+) -> RuntimeInventory {
+    let runtimes = known_runtimes(languages);
+    let probes: Vec<_> = runtimes
+        .iter()
+        .map(|runtime| {
+            let mut version = overrides.command(&runtime.command);
+            version.args(version_args(&runtime.command));
+            tauri::async_runtime::spawn(probe(overrides.program(&runtime.command), version))
+        })
+        .collect();
+
+    let platform = tauri::async_runtime::spawn_blocking(detect_platform)
+        .await
+        .ok();
+    let mut statuses = Vec::with_capacity(runtimes.len());
+    for (runtime, probe) in runtimes.iter().zip(probes) {
+        let (path, version) = probe.await.unwrap_or((None, None));
+        let install_hint = match (&path, &platform) {
+            (None, Some(platform)) => get_install_command(runtime, platform),
+            _ => None,
+        };
+        statuses.push(RuntimeStatus {
+            name: runtime.name.to_string(),
+            command: runtime.command.to_string(),
+            path,
+            version,
+            install_hint,
+            download_url: runtime.download_url.to_string(),
+        });
+    }
+
+    let unavailable = languages
+        .iter()
+        .filter_map(|language| {
+            missing_toolchain(language, &statuses).map(|missing| (language.id.clone(), missing))
+        })
+        .collect();
+    RuntimeInventory {
+        runtimes: statuses,
+        unavailable,
+    }
+}
+
+/// The built-in runtimes followed by any others the languages need, once each
+fn known_runtimes(languages: &[LanguageDefinition]) -> Vec<RuntimeInfo> {
+    let mut runtimes = BUILTIN_RUNTIMES.to_vec();
+    for runtime in languages.iter().flat_map(|language| &language.runtimes) {
+        if !runtimes
+            .iter()
+            .any(|known| known.command == runtime.command)
+        {
+            runtimes.push(runtime.clone());
+        }
+    }
+    runtimes
+}
+
+/// What a language needs installed: every group, and any runtime within one
+fn toolchain(language: &LanguageDefinition) -> Vec<Vec<RuntimeInfo>> {
+    match language.runner {
+        LanguageRunner::Template(TemplateType::C) => {
+            vec![vec![RuntimeInfo::GCC, RuntimeInfo::CLANG]]
+        }
+        LanguageRunner::Template(TemplateType::Cpp) => {
+            vec![vec![RuntimeInfo::GXX, RuntimeInfo::CLANGXX]]
+        }
+        LanguageRunner::Template(TemplateType::Scala) => {
+            vec![vec![RuntimeInfo::SCALA_CLI, RuntimeInfo::SCALAC]]
+        }
+        _ => language
+            .runtimes
+            .iter()
+            .map(|runtime| vec![runtime.clone()])
+            .collect(),
+    }
+}
+
+/// Names of the first toolchain group with nothing installed
+fn missing_toolchain(language: &LanguageDefinition, statuses: &[RuntimeStatus]) -> Option<String> {
+    let installed = |runtime: &RuntimeInfo| {
+        statuses
+            .iter()
+            .any(|status| status.command == runtime.command && status.path.is_some())
+    };
+    toolchain(language)
+        .into_iter()
+        .find(|group| !group.iter().any(installed))
+        .map(|group| {
+            group
+                .iter()
+                .map(|runtime| runtime.name.as_ref())
+                .collect::<Vec<_>>()
+                .join(" or ")
+        })
+}
+
+/// Resolve `program` and run its `version` command
+async fn probe(program: PathBuf, mut version: Command) -> (Option<PathBuf>, Option<String>) {
+    let Some(path) = resolve(program).await else {
+        return (None, None);
+    };
+    let version = run_quietly(&mut version)
+        .await
+        .and_then(|output| parse_version(&output));
+    (Some(path), version)
+}
+
+/// The full path of `program`: itself when it is a path, else what `which`
+/// (or `where` on Windows) finds on `PATH`
+async fn resolve(program: PathBuf) -> Option<PathBuf> {
+    if program.components().count() > 1 {
+        return program.is_file().then_some(program);
+    }
+    let finder = if cfg!(target_os = "windows") {
+        "where"
+    } else {
+        "which"
+    };
+    let output = run_quietly(Command::new(finder).arg(&program)).await?;
+    // `where` lists every match; the first is the one that runs
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Stdout and stderr of a successful run, or `None` if it failed or hung
+async fn run_quietly(cmd: &mut Command) -> Option<String> {
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    let output = tokio::time::timeout(VERSION_TIMEOUT, cmd.output())
+        .await
+        .ok()?
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Some(text)
+}
+
+/// Arguments that make `command` print its version
+fn version_args(command: &str) -> &'static [&'static str] {
+    match command {
+        "java" | "javac" | "kotlinc" | "scalac" => &["-version"],
+        "go" | "scala-cli" => &["version"],
+        "lua" => &["-v"],
+        _ => &["--version"],
+    }
+}
+
+/// The first dotted version number in a version command's output
+pub fn parse_version(output: &str) -> Option<String> {
+    output
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .map(|token| token.trim_matches('.'))
+        .find(|token| token.contains('.') && token.split('.').all(|part| !part.is_empty()))
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::language_registry::LanguageRegistry;
+    use std::path::Path;
+
+    #[test]
+    fn test_parse_version() {
+        let cases = [
+            ("v20.11.0\n", "20.11.0"),
+            ("Python 3.12.1", "3.12.1"),
+            (
+                "openjdk version \"21.0.2\" 2024-01-16\nOpenJDK Runtime",
+                "21.0.2",
+            ),
+            ("java version \"1.8.0_392\"", "1.8.0"),
+            ("go version go1.22.1 linux/amd64", "1.22.1"),
+            ("gcc (Debian 12.2.0-14) 12.2.0", "12.2.0"),
+            (
+                "GNU bash, version 5.2.15(1)-release (x86_64-pc-linux-gnu)",
+                "5.2.15",
+            ),
+            (
+                "\nThis is perl 5, version 36, subversion 0 (v5.36.0)",
+                "5.36.0",
+            ),
+            ("ruby 3.1.2p20 (2022-04-12 revision 4491bb740a)", "3.1.2"),
+            (
+                "Lua 5.4.6  Copyright (C) 1994-2023 Lua.org, PUC-Rio",
+                "5.4.6",
+            ),
+        ];
+        for (output, version) in cases {
+            assert_eq!(parse_version(output).as_deref(), Some(version), "{output}");
+        }
+        assert_eq!(parse_version("x86_64 build 2024-01-16..."), None);
+    }
+
+    #[test]
+    fn test_missing_toolchain() {
+        let registry = LanguageRegistry::load(Path::new("/nonexistent"));
+        let status = |runtime: &RuntimeInfo, installed: bool| RuntimeStatus {
+            name: runtime.name.to_string(),
+            command: runtime.command.to_string(),
+            path: installed.then(|| PathBuf::from("/usr/bin").join(runtime.command.as_ref())),
+            version: None,
+            install_hint: None,
+            download_url: String::new(),
+        };
+        let statuses = [
+            status(&RuntimeInfo::CLANG, true),
+            status(&RuntimeInfo::GCC, false),
+            status(&RuntimeInfo::JAVAC, true),
+            status(&RuntimeInfo::JAVA, false),
+        ];
+
+        let missing = |id: &str| missing_toolchain(registry.get(id).unwrap(), &statuses);
+        // Either compiler will do
+        assert_eq!(missing("c"), None);
+        assert_eq!(missing("cpp").as_deref(), Some("G++ or Clang++"));
+        assert_eq!(missing("java").as_deref(), Some("Java"));
+    }
+
+    #[test]
+    fn test_known_runtimes_include_user_languages() {
+        let mut language = LanguageRegistry::load(Path::new("/nonexistent"))
+            .get("python")
+            .unwrap()
+            .clone();
+        language.runtimes.push(RuntimeInfo {
+            name: "DSL".into(),
+            command: "dsl".into(),
+            download_url: "".into(),
+            install: BTreeMap::new(),
+        });
+        let runtimes = known_runtimes(std::slice::from_ref(&language));
+        assert_eq!(runtimes.len(), BUILTIN_RUNTIMES.len() + 1);
+        assert_eq!(runtimes.last().unwrap().command, "dsl");
+    }
+}
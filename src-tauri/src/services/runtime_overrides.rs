@@ -0,0 +1,150 @@
+//! User overrides for how runtimes are invoked.
+//!
+//! `runtimes.toml` in the app data dir maps a runtime's command to the
+//! executable to use instead, arguments passed before any others, and extra
+//! environment variables:
+//!
+//! ```toml
+//! [python3]
+//! path = "/opt/pypy3/bin/pypy3"
+//!
+//! [java]
+//! path = "/opt/jdk-21/bin/java"
+//! args = ["-Xmx2g"]
+//! env = { JAVA_HOME = "/opt/jdk-21" }
+//! ```
+//!
+//! `path` may also be a bare command name, which is looked up on `PATH`.
+
+use crate::services::runtime_detector::command_exists;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use tokio::process::Command;
+
+/// File in the app data dir holding the overrides
+pub const RUNTIMES_FILE: &str = "runtimes.toml";
+
+#[derive(Error, Debug)]
+pub enum RuntimeOverridesError {
+    #[error("Failed to read {0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("Invalid runtime settings in {0}: {1}")]
+    Parse(String, toml::de::Error),
+}
+
+/// How to invoke one runtime
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuntimeOverride {
+    /// Executable to run instead of the runtime's command
+    pub path: Option<PathBuf>,
+    /// Arguments placed before all others
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Overrides keyed by runtime command (`python3`, `node`, `javac`, ...)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RuntimeOverrides(BTreeMap<String, RuntimeOverride>);
+
+impl RuntimeOverrides {
+    /// Read the overrides in `path`; a missing file means none
+    pub fn load(path: &Path) -> Result<Self, RuntimeOverridesError> {
+        let name = path.display().to_string();
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(RuntimeOverridesError::Io(name, e)),
+        };
+        toml::from_str(&content).map_err(|e| RuntimeOverridesError::Parse(name, e))
+    }
+
+    pub fn get(&self, command: &str) -> Option<&RuntimeOverride> {
+        self.0.get(command)
+    }
+
+    /// The override for `command`, added empty when there is none
+    pub fn entry(&mut self, command: &str) -> &mut RuntimeOverride {
+        self.0.entry(command.to_string()).or_default()
+    }
+
+    /// The executable to run for `command`
+    pub fn program(&self, command: &str) -> PathBuf {
+        self.get(command)
+            .and_then(|o| o.path.clone())
+            .unwrap_or_else(|| PathBuf::from(command))
+    }
+
+    /// Whether the executable for `command` exists
+    pub fn is_installed(&self, command: &str) -> bool {
+        command_exists(&self.program(command).to_string_lossy())
+    }
+
+    /// A command running `command`'s executable with its default arguments
+    /// and environment
+    pub fn command(&self, command: &str) -> Command {
+        self.command_with(command, self.program(command))
+    }
+
+    /// A command running `program` with `command`'s default arguments and
+    /// environment, for executables that stand in for a runtime (a venv's
+    /// Python)
+    pub fn command_with(&self, command: &str, program: impl AsRef<OsStr>) -> Command {
+        let mut cmd = Command::new(program);
+        if let Some(o) = self.get(command) {
+            cmd.args(&o.args).envs(&o.env);
+        }
+        cmd
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_overrides() {
+        let dir = std::env::temp_dir().join(format!("codecell-runtimes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join(RUNTIMES_FILE);
+
+        assert_eq!(
+            RuntimeOverrides::load(&file).unwrap(),
+            RuntimeOverrides::default()
+        );
+
+        fs::write(
+            &file,
+            "[python3]\npath = \"/opt/pypy3/bin/pypy3\"\n\n[java]\nargs = [\"-Xmx2g\"]\nenv = { JAVA_HOME = \"/opt/jdk\" }\n",
+        )
+        .unwrap();
+        let overrides = RuntimeOverrides::load(&file).unwrap();
+        assert_eq!(
+            overrides.program("python3"),
+            Path::new("/opt/pypy3/bin/pypy3")
+        );
+        assert_eq!(overrides.program("node"), Path::new("node"));
+
+        let java = overrides.command("java");
+        let java = java.as_std();
+        assert_eq!(java.get_program(), "java");
+        assert_eq!(java.get_args().collect::<Vec<_>>(), ["-Xmx2g"]);
+        assert_eq!(
+            java.get_envs().collect::<Vec<_>>(),
+            [(OsStr::new("JAVA_HOME"), Some(OsStr::new("/opt/jdk")))]
+        );
+
+        fs::write(&file, "[node]\nexecutable = \"/usr/bin/node\"\n").unwrap();
+        let err = RuntimeOverrides::load(&file).unwrap_err();
+        assert!(err.to_string().contains("unknown field `executable`"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+//! Running TypeScript on Node without a compiler.
+//!
+//! Node 22.13 and later erase TypeScript's types themselves and, with
+//! `--experimental-transform-types`, also compile enums, namespaces and
+//! parameter properties, keeping source maps so stack traces match the
+//! editor. Before a run, [`check_args`] has Node parse every TypeScript file
+//! with the same stripper, so a project it can't handle goes to a real
+//! compiler such as tsx before any of it has run.
+
+use crate::models::ProjectFile;
+use std::path::Path;
+use thiserror::Error;
+
+/// Node flags that run a TypeScript entry file directly
+pub const NODE_FLAGS: &[&str] = &[
+    "--experimental-transform-types",
+    "--disable-warning=ExperimentalWarning",
+    "--enable-source-maps",
+];
+
+/// Exit code of [`CHECK_SCRIPT`] when Node has no type stripping
+const UNSUPPORTED_EXIT: i32 = 2;
+
+/// Strips each file named in the arguments, printing the first error
+const CHECK_SCRIPT: &str = r#"
+const { stripTypeScriptTypes } = require("node:module");
+const { readFileSync } = require("node:fs");
+if (typeof stripTypeScriptTypes !== "function") process.exit(2);
+for (const file of process.argv.slice(1)) {
+  try {
+    stripTypeScriptTypes(readFileSync(file, "utf8"), { mode: "transform" });
+  } catch (e) {
+    console.log(`${file}: ${String(e.message).split("\n")[0]}`);
+    process.exit(1);
+  }
+}
+"#;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum TsStripError {
+    #[error("This Node version can't run TypeScript itself (22.13 or later can)")]
+    Unsupported,
+    #[error("JSX in {0} needs a TypeScript compiler")]
+    Jsx(String),
+    #[error("Node could not strip the types from {0}")]
+    Syntax(String),
+}
+
+/// Name of the script a TypeScript file compiles to, or `None` for files
+/// that aren't compiled (declaration files and everything else)
+pub fn output_name(name: &str) -> Option<String> {
+    if [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+    {
+        return None;
+    }
+    let extension = match Path::new(name).extension()?.to_str()? {
+        "ts" => "js",
+        "mts" => "mjs",
+        "cts" => "cjs",
+        _ => return None,
+    };
+    Some(
+        Path::new(name)
+            .with_extension(extension)
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+/// Node arguments that check every TypeScript file of the project can be
+/// stripped; run them from the project's directory and pass the result to
+/// [`check_result`]
+pub fn check_args(files: &[ProjectFile]) -> Result<Vec<String>, TsStripError> {
+    if let Some(file) = files.iter().find(|f| f.name.ends_with(".tsx")) {
+        return Err(TsStripError::Jsx(file.name.clone()));
+    }
+    let mut args = vec![
+        "--no-warnings".to_string(),
+        "-e".to_string(),
+        CHECK_SCRIPT.to_string(),
+    ];
+    args.extend(
+        files
+            .iter()
+            .filter(|f| output_name(&f.name).is_some())
+            .map(|f| f.name.clone()),
+    );
+    Ok(args)
+}
+
+/// Interpret the exit code and stdout of the [`check_args`] run
+pub fn check_result(code: Option<i32>, stdout: &str) -> Result<(), TsStripError> {
+    match code {
+        Some(0) => Ok(()),
+        Some(UNSUPPORTED_EXIT) => Err(TsStripError::Unsupported),
+        _ => Err(TsStripError::Syntax(
+            stdout.lines().next().unwrap_or_default().trim().to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::process::Command;
+
+    fn file(name: &str, content: &str) -> ProjectFile {
+        ProjectFile {
+            name: name.to_string(),
+            content: content.to_string(),
+            language: String::new(),
+        }
+    }
+
+    /// Check and run `code` with the `node` on `PATH`, or `None` when it
+    /// can't strip types
+    fn run_on_node(code: &str) -> Option<String> {
+        let dir = std::env::temp_dir().join(format!(
+            "codecell_ts_strip_test_{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("main.ts"), code).unwrap();
+
+        let files = [file("main.ts", code)];
+        let check = Command::new("node")
+            .args(check_args(&files).unwrap())
+            .current_dir(&dir)
+            .output()
+            .ok()?;
+        let stdout = String::from_utf8_lossy(&check.stdout);
+        let checked = check_result(check.status.code(), &stdout);
+        if checked == Err(TsStripError::Unsupported) {
+            fs::remove_dir_all(dir).unwrap();
+            return None;
+        }
+        checked.unwrap();
+
+        let run = Command::new("node")
+            .args(NODE_FLAGS)
+            .arg("main.ts")
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        fs::remove_dir_all(dir).unwrap();
+        assert!(run.status.success(), "{run:?}");
+        Some(String::from_utf8_lossy(&run.stdout).into_owned())
+    }
+
+    #[test]
+    fn test_output_name() {
+        assert_eq!(output_name("src/app.ts").as_deref(), Some("src/app.js"));
+        assert_eq!(output_name("lib.mts").as_deref(), Some("lib.mjs"));
+        assert_eq!(output_name("types.d.ts"), None);
+        assert_eq!(output_name("data.json"), None);
+    }
+
+    #[test]
+    fn test_check_args() {
+        let files = [
+            file("main.ts", ""),
+            file("types.d.ts", ""),
+            file("package.json", "{}"),
+        ];
+        let args = check_args(&files).unwrap();
+        assert_eq!(args.last().map(String::as_str), Some("main.ts"));
+        assert!(!args.iter().any(|arg| arg == "types.d.ts"));
+
+        let files = [file("main.ts", ""), file("App.tsx", "")];
+        assert_eq!(
+            check_args(&files),
+            Err(TsStripError::Jsx("App.tsx".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_check_result() {
+        assert_eq!(check_result(Some(0), ""), Ok(()));
+        assert_eq!(
+            check_result(Some(UNSUPPORTED_EXIT), ""),
+            Err(TsStripError::Unsupported)
+        );
+        assert_eq!(
+            check_result(Some(1), "main.ts: Expected ';'\n"),
+            Err(TsStripError::Syntax("main.ts: Expected ';'".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_division_after_non_null_assertion() {
+        let code = "const x: number | null = 12\n\
+                    const y = 3\n\
+                    let z = x! / 2 / y\n\
+                    console.log(z)\n";
+        if let Some(stdout) = run_on_node(code) {
+            assert_eq!(stdout, "2\n");
+        }
+    }
+
+    #[test]
+    fn test_array_after_as_expression() {
+        // No semicolon is inserted before `[`, so it indexes `b` with `(1, 2)`
+        let code = "const b = { 2: [5, 6] }\n\
+                    let a = b as any\n\
+                    [1, 2].forEach((x: number) => console.log(x))\n";
+        if let Some(stdout) = run_on_node(code) {
+            assert_eq!(stdout, "5\n6\n");
+        }
+    }
+}
@@ -0,0 +1,506 @@
+//! Toolchains installed by version managers.
+//!
+//! pyenv, conda, nvm, fnm, sdkman and rustup each keep their installations
+//! in a directory of their own, of which at most one is on `PATH`. They are
+//! found by reading those directories, without running the managers, so a
+//! project can pin one and run with it.
+
+use crate::models::{ManagedRuntime, ToolchainPin, VersionManager};
+use crate::services::runtime_inventory::parse_version;
+use crate::services::runtime_overrides::RuntimeOverrides;
+use serde::Serialize;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ToolchainError {
+    #[error("{0} is not installed")]
+    NotInstalled(String),
+}
+
+/// One installation found in a version manager's directory
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedToolchain {
+    pub manager: VersionManager,
+    pub runtime: ManagedRuntime,
+    /// The manager's name for it: a version directory, conda env or rustup
+    /// toolchain
+    pub name: String,
+    /// Version of the runtime, when the name or metadata tells
+    pub version: Option<String>,
+    /// Installation root, used as `JAVA_HOME` for JDKs
+    pub home: PathBuf,
+    /// Directory holding the executables
+    pub bin_dir: PathBuf,
+}
+
+impl ManagedToolchain {
+    /// Point the runtime's commands at this installation. Its `bin` directory
+    /// goes first on their `PATH` too, so tools they start (cargo's rustc,
+    /// npm's node) come from the same installation.
+    pub fn apply(&self, overrides: &mut RuntimeOverrides) {
+        let mut env = vec![("PATH".to_string(), self.path_var())];
+        match self.runtime {
+            ManagedRuntime::Java => {
+                env.push(("JAVA_HOME".to_string(), self.home.display().to_string()));
+            }
+            // rustup's proxies would otherwise pick the default toolchain
+            ManagedRuntime::Rust => env.push(("RUSTUP_TOOLCHAIN".to_string(), self.name.clone())),
+            _ => {}
+        }
+
+        for command in commands(self.runtime) {
+            let Some(program) = executable(&self.bin_dir, command) else {
+                continue;
+            };
+            let runtime = overrides.entry(command);
+            runtime.path = Some(program);
+            runtime.env.extend(env.iter().cloned());
+        }
+    }
+
+    fn path_var(&self) -> String {
+        let rest = env::var_os("PATH").unwrap_or_default();
+        let paths = std::iter::once(self.bin_dir.clone()).chain(env::split_paths(&rest));
+        env::join_paths(paths)
+            .unwrap_or_else(|_| self.bin_dir.clone().into_os_string())
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn matches(&self, pin: &ToolchainPin) -> bool {
+        if self.runtime != pin.runtime || pin.manager.is_some_and(|m| m != self.manager) {
+            return false;
+        }
+        let wanted = pin.version.trim_start_matches('v');
+        let prefix_of = |s: &str| s == wanted || s.starts_with(&format!("{wanted}."));
+        self.name == pin.version
+            || self.version.as_deref().is_some_and(prefix_of)
+            // Channels: `nightly` is `nightly-x86_64-unknown-linux-gnu`
+            || self.name.starts_with(&format!("{wanted}-"))
+    }
+}
+
+impl fmt::Display for ToolchainPin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.runtime, self.version)?;
+        if let Some(manager) = self.manager {
+            write!(f, " via {}", format!("{manager:?}").to_lowercase())?;
+        }
+        Ok(())
+    }
+}
+
+/// Commands a runtime's installations provide, the main one first
+fn commands(runtime: ManagedRuntime) -> &'static [&'static str] {
+    match runtime {
+        ManagedRuntime::Python => &["python3"],
+        ManagedRuntime::Node => &["node", "npm", "npx"],
+        ManagedRuntime::Rust => &["rustc", "cargo"],
+        ManagedRuntime::Java => &["java", "javac"],
+        ManagedRuntime::Kotlin => &["kotlinc", "kotlin"],
+        ManagedRuntime::Scala => &["scalac", "scala"],
+        ManagedRuntime::Groovy => &["groovy"],
+    }
+}
+
+/// `command` inside `dir`, allowing for Windows suffixes and for Pythons
+/// that only install `python`
+fn executable(dir: &Path, command: &str) -> Option<PathBuf> {
+    let names: &[&str] = if command == "python3" {
+        &["python3", "python"]
+    } else {
+        &[command]
+    };
+    let suffixes: &[&str] = if cfg!(windows) {
+        &[".exe", ".cmd"]
+    } else {
+        &[""]
+    };
+    names
+        .iter()
+        .flat_map(|name| {
+            suffixes
+                .iter()
+                .map(move |suffix| dir.join(format!("{name}{suffix}")))
+        })
+        .find(|path| path.is_file())
+}
+
+/// Where each manager keeps its installations
+#[derive(Debug, Default)]
+pub struct ManagerDirs {
+    pub pyenv: Option<PathBuf>,
+    /// Conda environments, the base environment included
+    pub conda: Vec<PathBuf>,
+    pub nvm: Option<PathBuf>,
+    pub fnm: Option<PathBuf>,
+    pub sdkman: Option<PathBuf>,
+    pub rustup: Option<PathBuf>,
+}
+
+impl ManagerDirs {
+    /// The managers' directories, from their environment variables or else
+    /// their default locations
+    pub fn from_env() -> Self {
+        let home = env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(PathBuf::from)
+            .unwrap_or_default();
+        let var_or = |name: &str, default: PathBuf| {
+            Some(env::var_os(name).map_or(default, PathBuf::from)).filter(|dir| dir.is_dir())
+        };
+
+        let fnm_defaults = [
+            env::var_os("XDG_DATA_HOME").map(|dir| PathBuf::from(dir).join("fnm")),
+            Some(home.join(".local/share/fnm")),
+            Some(home.join("Library/Application Support/fnm")),
+            env::var_os("APPDATA").map(|dir| PathBuf::from(dir).join("fnm")),
+            Some(home.join(".fnm")),
+        ];
+        let fnm = env::var_os("FNM_DIR")
+            .map(PathBuf::from)
+            .or_else(|| fnm_defaults.into_iter().flatten().find(|dir| dir.is_dir()));
+
+        Self {
+            pyenv: var_or("PYENV_ROOT", home.join(".pyenv")).map(|root| {
+                // pyenv-win nests its versions one level down
+                let win = root.join("pyenv-win");
+                if win.is_dir() {
+                    win
+                } else {
+                    root
+                }
+            }),
+            conda: conda_environments(&home),
+            nvm: env::var_os("NVM_HOME")
+                .map(PathBuf::from)
+                .or_else(|| var_or("NVM_DIR", home.join(".nvm"))),
+            fnm,
+            sdkman: var_or("SDKMAN_DIR", home.join(".sdkman")),
+            rustup: var_or("RUSTUP_HOME", home.join(".rustup")),
+        }
+    }
+}
+
+/// Conda's list of environments, plus the usual base installs
+fn conda_environments(home: &Path) -> Vec<PathBuf> {
+    let listed = fs::read_to_string(home.join(".conda/environments.txt")).unwrap_or_default();
+    let mut envs: Vec<PathBuf> = listed
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    for base in ["miniconda3", "anaconda3", "miniforge3", "mambaforge"] {
+        let base = home.join(base);
+        envs.push(base.clone());
+        envs.extend(subdirs(&base.join("envs")));
+    }
+
+    let mut unique = Vec::new();
+    for env in envs {
+        if env.is_dir() && !unique.contains(&env) {
+            unique.push(env);
+        }
+    }
+    unique
+}
+
+/// Every toolchain the version managers on this machine have installed
+pub fn discover_toolchains() -> Vec<ManagedToolchain> {
+    discover(&ManagerDirs::from_env())
+}
+
+pub fn discover(dirs: &ManagerDirs) -> Vec<ManagedToolchain> {
+    let mut found = Vec::new();
+    let mut add = |manager, runtime, name: String, home: PathBuf| {
+        if let Some(toolchain) = toolchain(manager, runtime, name, home) {
+            found.push(toolchain);
+        }
+    };
+
+    if let Some(pyenv) = &dirs.pyenv {
+        for home in subdirs(&pyenv.join("versions")) {
+            add(
+                VersionManager::Pyenv,
+                ManagedRuntime::Python,
+                dir_name(&home),
+                home,
+            );
+        }
+    }
+    for home in &dirs.conda {
+        let name = match home.parent().and_then(Path::file_name) {
+            Some(parent) if parent == "envs" => dir_name(home),
+            _ => "base".to_string(),
+        };
+        add(
+            VersionManager::Conda,
+            ManagedRuntime::Python,
+            name,
+            home.clone(),
+        );
+    }
+    if let Some(nvm) = &dirs.nvm {
+        // nvm-windows keeps versions directly in its root
+        let versions = nvm.join("versions/node");
+        let root = if versions.is_dir() {
+            versions
+        } else {
+            nvm.clone()
+        };
+        for home in subdirs(&root)
+            .into_iter()
+            .filter(|dir| dir_name(dir).starts_with('v'))
+        {
+            add(
+                VersionManager::Nvm,
+                ManagedRuntime::Node,
+                dir_name(&home),
+                home,
+            );
+        }
+    }
+    if let Some(fnm) = &dirs.fnm {
+        for version in subdirs(&fnm.join("node-versions")) {
+            let home = version.join("installation");
+            add(
+                VersionManager::Fnm,
+                ManagedRuntime::Node,
+                dir_name(&version),
+                home,
+            );
+        }
+    }
+    if let Some(sdkman) = &dirs.sdkman {
+        let candidates = [
+            ("java", ManagedRuntime::Java),
+            ("kotlin", ManagedRuntime::Kotlin),
+            ("scala", ManagedRuntime::Scala),
+            ("groovy", ManagedRuntime::Groovy),
+        ];
+        for (candidate, runtime) in candidates {
+            for home in subdirs(&sdkman.join("candidates").join(candidate)) {
+                // `current` links to the default version, listed already
+                if dir_name(&home) != "current" {
+                    add(VersionManager::Sdkman, runtime, dir_name(&home), home);
+                }
+            }
+        }
+    }
+    if let Some(rustup) = &dirs.rustup {
+        for home in subdirs(&rustup.join("toolchains")) {
+            add(
+                VersionManager::Rustup,
+                ManagedRuntime::Rust,
+                dir_name(&home),
+                home,
+            );
+        }
+    }
+    found
+}
+
+/// The installation at `home`, if it has the runtime's main executable
+fn toolchain(
+    manager: VersionManager,
+    runtime: ManagedRuntime,
+    name: String,
+    home: PathBuf,
+) -> Option<ManagedToolchain> {
+    // Windows installs of conda, nvm and fnm keep executables in the root
+    let bin_dir = [home.join("bin"), home.clone()]
+        .into_iter()
+        .find(|dir| executable(dir, commands(runtime)[0]).is_some())?;
+    let version = match manager {
+        VersionManager::Conda => conda_python_version(&home),
+        _ => parse_version(&name),
+    };
+    Some(ManagedToolchain {
+        manager,
+        runtime,
+        name,
+        version,
+        home,
+        bin_dir,
+    })
+}
+
+/// The Python version from the env's package records, e.g.
+/// `conda-meta/python-3.11.5-h955ad1f_0.json`
+fn conda_python_version(env: &Path) -> Option<String> {
+    fs::read_dir(env.join("conda-meta"))
+        .ok()?
+        .flatten()
+        .find_map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let version = name.strip_prefix("python-")?;
+            version
+                .starts_with(|c: char| c.is_ascii_digit())
+                .then(|| parse_version(version))?
+        })
+}
+
+/// The installation a pin selects: one whose name is the pinned version,
+/// else the newest whose version starts with it
+pub fn resolve_pin<'a>(
+    toolchains: &'a [ManagedToolchain],
+    pin: &ToolchainPin,
+) -> Result<&'a ManagedToolchain, ToolchainError> {
+    let matching = toolchains.iter().filter(|t| t.matches(pin));
+    matching
+        .clone()
+        .find(|t| t.name == pin.version)
+        .or_else(|| matching.max_by_key(|t| version_key(t.version.as_deref())))
+        .ok_or_else(|| ToolchainError::NotInstalled(pin.to_string()))
+}
+
+/// Run a project's commands with the toolchains it pins
+pub fn apply_pins(
+    pins: &[ToolchainPin],
+    overrides: &mut RuntimeOverrides,
+) -> Result<(), ToolchainError> {
+    if pins.is_empty() {
+        return Ok(());
+    }
+    let toolchains = discover_toolchains();
+    for pin in pins {
+        resolve_pin(&toolchains, pin)?.apply(overrides);
+    }
+    Ok(())
+}
+
+fn version_key(version: Option<&str>) -> Vec<u64> {
+    version
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn dir_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Lay out fake installations, each with its main executable
+    fn fixture(root: &Path, executables: &[&str]) {
+        for executable in executables {
+            let path = root.join(executable);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, "").unwrap();
+        }
+    }
+
+    fn pin(
+        runtime: ManagedRuntime,
+        manager: Option<VersionManager>,
+        version: &str,
+    ) -> ToolchainPin {
+        ToolchainPin {
+            runtime,
+            manager,
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_discover_and_pin() {
+        let root = std::env::temp_dir().join(format!("codecell-managers-{}", std::process::id()));
+        fixture(
+            &root,
+            &[
+                "pyenv/versions/3.11.2/bin/python3",
+                "pyenv/versions/3.11.9/bin/python3",
+                "pyenv/versions/3.12.1/bin/python3",
+                "conda/envs/ml/bin/python3",
+                "conda/envs/ml/conda-meta/python-3.10.13-h955ad1f_0.json",
+                "conda/envs/ml/conda-meta/python-dateutil-2.8.2-pyhd3eb1b0_0.json",
+                "nvm/versions/node/v20.11.0/bin/node",
+                "nvm/versions/node/v20.11.0/bin/npm",
+                "fnm/node-versions/v18.19.0/installation/bin/node",
+                "sdkman/candidates/java/21.0.2-tem/bin/java",
+                "sdkman/candidates/java/21.0.2-tem/bin/javac",
+                "sdkman/candidates/kotlin/1.9.22/bin/kotlinc",
+                "rustup/toolchains/nightly-x86_64-unknown-linux-gnu/bin/rustc",
+                "rustup/toolchains/stable-x86_64-unknown-linux-gnu/bin/rustc",
+            ],
+        );
+        // Not an installation: no interpreter inside
+        fs::create_dir_all(root.join("pyenv/versions/broken")).unwrap();
+
+        let dirs = ManagerDirs {
+            pyenv: Some(root.join("pyenv")),
+            conda: vec![root.join("conda/envs/ml")],
+            nvm: Some(root.join("nvm")),
+            fnm: Some(root.join("fnm")),
+            sdkman: Some(root.join("sdkman")),
+            rustup: Some(root.join("rustup")),
+        };
+        let toolchains = discover(&dirs);
+        assert_eq!(toolchains.len(), 10);
+
+        let resolve = |pin: ToolchainPin| resolve_pin(&toolchains, &pin).map(|t| t.name.clone());
+        let pyenv = Some(VersionManager::Pyenv);
+        assert_eq!(
+            resolve(pin(ManagedRuntime::Python, pyenv, "3.11")).unwrap(),
+            "3.11.9"
+        );
+        assert_eq!(
+            resolve(pin(ManagedRuntime::Python, None, "3.10")).unwrap(),
+            "ml"
+        );
+        assert_eq!(
+            resolve(pin(ManagedRuntime::Python, None, "ml")).unwrap(),
+            "ml"
+        );
+        assert_eq!(
+            resolve(pin(ManagedRuntime::Node, None, "v18")).unwrap(),
+            "v18.19.0"
+        );
+        assert_eq!(
+            resolve(pin(ManagedRuntime::Java, None, "21")).unwrap(),
+            "21.0.2-tem"
+        );
+        assert_eq!(
+            resolve(pin(ManagedRuntime::Rust, None, "nightly")).unwrap(),
+            "nightly-x86_64-unknown-linux-gnu"
+        );
+        let err = resolve(pin(ManagedRuntime::Python, pyenv, "3.9")).unwrap_err();
+        assert_eq!(err.to_string(), "Python 3.9 via pyenv is not installed");
+
+        let mut overrides = RuntimeOverrides::default();
+        resolve_pin(&toolchains, &pin(ManagedRuntime::Java, None, "21"))
+            .unwrap()
+            .apply(&mut overrides);
+        let jdk = root.join("sdkman/candidates/java/21.0.2-tem");
+        assert_eq!(overrides.program("javac"), jdk.join("bin/javac"));
+        let java = overrides.get("java").unwrap();
+        assert_eq!(java.env["JAVA_HOME"], jdk.display().to_string());
+        assert!(java.env["PATH"].starts_with(&jdk.join("bin").display().to_string()));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
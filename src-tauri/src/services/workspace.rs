@@ -0,0 +1,207 @@
+use crate::models::ProjectFile;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum WorkspaceError {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Invalid file name: {0}")]
+    InvalidPath(String),
+    #[error("File not found in project: {0}")]
+    MissingFile(String),
+}
+
+/// A scratch directory holding one run's project files.
+///
+/// Programs run with the workspace as their working directory, so relative
+/// imports (`mod utils;`, `import helpers`) and file reads resolve against
+/// the project's own files.
+pub struct Workspace {
+    root: PathBuf,
+}
+
+impl Workspace {
+    /// Create an empty workspace for a run in the given window
+    pub fn create(window_id: &str) -> Result<Self, WorkspaceError> {
+        Self::create_in(&std::env::temp_dir(), window_id)
+    }
+
+    /// Create the workspace inside `parent` instead of the temp directory
+    pub fn create_in(parent: &Path, window_id: &str) -> Result<Self, WorkspaceError> {
+        let id = window_id.replace("editor-", "");
+        let root = parent.join(format!(
+            "codecell_run_{}_{}",
+            id,
+            uuid::Uuid::new_v4().simple()
+        ));
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /// Write every project file, creating subdirectories as needed
+    pub fn write_files(&self, files: &[ProjectFile]) -> Result<(), WorkspaceError> {
+        for file in files {
+            let path = self.resolve(&file.name)?;
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(&path, &file.content)?;
+        }
+        Ok(())
+    }
+
+    /// Resolve a project file name to its path inside the workspace.
+    ///
+    /// Names are relative paths; anything that could escape the workspace
+    /// (absolute paths, `..`) is rejected.
+    pub fn resolve(&self, name: &str) -> Result<PathBuf, WorkspaceError> {
+        let relative = Path::new(name);
+        let is_safe = !name.is_empty()
+            && relative
+                .components()
+                .all(|c| matches!(c, Component::Normal(_) | Component::CurDir));
+        if !is_safe {
+            return Err(WorkspaceError::InvalidPath(name.to_string()));
+        }
+        Ok(self.root.join(relative))
+    }
+
+    /// Every file under the workspace with the given extension
+    pub fn files_with_extension(&self, extension: &str) -> Vec<PathBuf> {
+        let mut found = Vec::new();
+        collect_files(
+            &self.root,
+            &|path| path.extension().is_some_and(|ext| ext == extension),
+            &mut found,
+        );
+        found
+    }
+
+    /// Every file under the workspace that isn't one of the project's
+    /// `files`: whatever a build step produced
+    pub fn generated_files(&self, files: &[ProjectFile]) -> Vec<PathBuf> {
+        let written: Vec<PathBuf> = files
+            .iter()
+            .filter_map(|f| self.resolve(&f.name).ok())
+            .collect();
+        let mut found = Vec::new();
+        collect_files(
+            &self.root,
+            &|path| !written.iter().any(|w| w == path),
+            &mut found,
+        );
+        found
+    }
+
+    /// Delete the workspace and everything the program left in it
+    pub fn remove(self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+fn collect_files(dir: &Path, filter: &dyn Fn(&Path) -> bool, found: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        // Symlinks aren't followed: a link to `..` would recurse forever
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_files(&path, filter, found);
+        } else if filter(&path) {
+            found.push(path);
+        }
+    }
+}
+
+/// Pick the project file to run: the requested one, or the first file
+pub fn find_entry<'a>(
+    files: &'a [ProjectFile],
+    entry_file: Option<&str>,
+) -> Result<&'a ProjectFile, WorkspaceError> {
+    match entry_file {
+        Some(name) => files
+            .iter()
+            .find(|f| f.name == name)
+            .ok_or_else(|| WorkspaceError::MissingFile(name.to_string())),
+        None => files
+            .first()
+            .ok_or_else(|| WorkspaceError::MissingFile("(no files)".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, content: &str) -> ProjectFile {
+        ProjectFile {
+            name: name.to_string(),
+            content: content.to_string(),
+            language: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_rejects_escaping_paths() {
+        let workspace = Workspace::create("editor-test-resolve").unwrap();
+
+        assert!(workspace.resolve("main.py").is_ok());
+        assert!(workspace.resolve("pkg/helpers.py").is_ok());
+        assert!(workspace.resolve("../outside.py").is_err());
+        assert!(workspace.resolve("pkg/../../outside.py").is_err());
+        assert!(workspace.resolve("/etc/passwd").is_err());
+        assert!(workspace.resolve("").is_err());
+
+        workspace.remove();
+    }
+
+    #[test]
+    fn test_write_files_creates_subdirectories() {
+        let workspace = Workspace::create("editor-test-write").unwrap();
+        let files = vec![
+            file("main.py", "import utils.helpers"),
+            file("utils/helpers.py", "VALUE = 1"),
+        ];
+
+        workspace.write_files(&files).unwrap();
+        let helper = fs::read_to_string(workspace.path().join("utils/helpers.py")).unwrap();
+        assert_eq!(helper, "VALUE = 1");
+
+        let root = workspace.path().to_path_buf();
+        workspace.remove();
+        assert!(!root.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_generated_files_skips_symlinked_directories() {
+        let workspace = Workspace::create("editor-test-symlink").unwrap();
+        let files = vec![file("main.rs", "")];
+        workspace.write_files(&files).unwrap();
+        fs::create_dir(workspace.path().join("target")).unwrap();
+        fs::write(workspace.path().join("target/app"), "").unwrap();
+        std::os::unix::fs::symlink("..", workspace.path().join("target/parent")).unwrap();
+
+        let generated = workspace.generated_files(&files);
+        assert!(generated.contains(&workspace.path().join("target/app")));
+        assert!(!generated.contains(&workspace.path().join("target/parent/main.rs")));
+
+        workspace.remove();
+    }
+
+    #[test]
+    fn test_find_entry() {
+        let files = vec![file("main.rs", ""), file("utils.rs", "")];
+        assert_eq!(find_entry(&files, None).unwrap().name, "main.rs");
+        assert_eq!(
+            find_entry(&files, Some("utils.rs")).unwrap().name,
+            "utils.rs"
+        );
+        assert!(find_entry(&files, Some("lib.rs")).is_err());
+        assert!(find_entry(&[], None).is_err());
+    }
+}
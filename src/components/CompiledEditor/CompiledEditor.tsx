@@ -1,4 +1,4 @@
-import { useState, useEffect, useCallback, useMemo } from "react";
+import { useState, useEffect, useCallback, useMemo, useRef } from "react";
 import {
   CommandPalette,
   useCommandPalette,
@@ -44,29 +44,59 @@ import {
   useKeyboardShortcuts,
   useWindowState,
 } from "@/hooks";
+import type { CheckResult, Diagnostic } from "@/types";
 
 interface ExecutionResult {
   stdout: string;
   stderr: string;
   exitCode: number;
   durationMs: number;
+  limitExceeded?: "timeout" | "cpuTime" | "memory" | "output" | null;
 }
 
 interface ExecutionOutput {
   line: string;
   stream: "stdout" | "stderr";
+  seq: number;
+  timestamp: number;
 }
 
-const LANGUAGE_CONFIG: Record<string, { name: string; color: string; executor: string }> = {
-  node: { name: "Node.js", color: "var(--color-template-node)", executor: "execute_node" },
-  python: { name: "Python", color: "var(--color-template-python)", executor: "execute_python" },
-  rust: { name: "Rust", color: "var(--color-template-rust)", executor: "execute_rust" },
-  java: { name: "Java", color: "var(--color-template-java)", executor: "execute_java" },
-  typescript: {
-    name: "TypeScript",
-    color: "var(--color-template-react)",
-    executor: "execute_typescript",
-  },
+// Streamed output in arrival order, with the input echoed between it
+interface OutputChunk {
+  text: string;
+  stream: "stdout" | "stderr" | "stdin";
+  seq: number;
+}
+
+interface EnvironmentStatus {
+  state: "creating" | "installing" | "ready" | "failed";
+  message: string;
+}
+
+interface MissingModule {
+  module: string;
+  package: string;
+  installCommand: string;
+  manifest: string;
+}
+
+const LANGUAGE_CONFIG: Record<string, { name: string; color: string }> = {
+  node: { name: "Node.js", color: "var(--color-template-node)" },
+  python: { name: "Python", color: "var(--color-template-python)" },
+  rust: { name: "Rust", color: "var(--color-template-rust)" },
+  java: { name: "Java", color: "var(--color-template-java)" },
+  kotlin: { name: "Kotlin", color: "var(--color-template-kotlin)" },
+  scala: { name: "Scala", color: "var(--color-template-scala)" },
+  groovy: { name: "Groovy", color: "var(--color-template-groovy)" },
+  go: { name: "Go", color: "var(--color-template-go)" },
+  c: { name: "C", color: "var(--color-template-c)" },
+  cpp: { name: "C++", color: "var(--color-template-cpp)" },
+  ruby: { name: "Ruby", color: "var(--color-template-ruby)" },
+  php: { name: "PHP", color: "var(--color-template-php)" },
+  perl: { name: "Perl", color: "var(--color-template-perl)" },
+  lua: { name: "Lua", color: "var(--color-template-lua)" },
+  bash: { name: "Bash", color: "var(--color-template-bash)" },
+  typescript: { name: "TypeScript", color: "var(--color-template-react)" },
 };
 
 export function CompiledEditor() {
@@ -80,8 +110,13 @@ export function CompiledEditor() {
   } = useProjectStore();
   const isDirty = useProjectStore((state) => state.isDirty);
   const [isRunning, setIsRunning] = useState(false);
+  const [isChecking, setIsChecking] = useState(false);
   const [result, setResult] = useState<ExecutionResult | null>(null);
-  const [streamingOutput, setStreamingOutput] = useState({ stdout: "", stderr: "" });
+  const [chunks, setChunks] = useState<OutputChunk[]>([]);
+  const [isWaitingInput, setIsWaitingInput] = useState(false);
+  const [diagnostics, setDiagnostics] = useState<Diagnostic[]>([]);
+  const [environment, setEnvironment] = useState<EnvironmentStatus | null>(null);
+  const [missingModule, setMissingModule] = useState<MissingModule | null>(null);
   const [showOutput, setShowOutput] = useState(true);
   const [showSaveTemplate, setShowSaveTemplate] = useState(false);
   const [showKeyboardShortcuts, setShowKeyboardShortcuts] = useState(false);
@@ -102,35 +137,89 @@ export function CompiledEditor() {
   // Execution handlers
   const handleRun = useCallback(async () => {
     if (!currentProject || isRunning) return;
-    const file = currentProject.files[0];
-    if (!file) return;
 
     setIsRunning(true);
     setResult(null);
-    setStreamingOutput({ stdout: "", stderr: "" });
+    setChunks([]);
+    setIsWaitingInput(false);
+    setDiagnostics([]);
+    setEnvironment(null);
+    setMissingModule(null);
     setShowOutput(true);
 
     try {
-      await invoke(config.executor, { code: file.content, windowId });
+      await invoke("execute_project", { project: currentProject, windowId });
     } catch (error) {
       setResult({ stdout: "", stderr: String(error), exitCode: -1, durationMs: 0 });
       setIsRunning(false);
     }
-  }, [currentProject, isRunning, config.executor, windowId]);
+  }, [currentProject, isRunning, windowId]);
+
+  // Compile or type-check without running; problems show in the output panel
+  const handleCheck = useCallback(async () => {
+    if (!currentProject || isRunning || isChecking) return;
 
+    setIsChecking(true);
+    setResult(null);
+    setChunks([]);
+    setDiagnostics([]);
+    setEnvironment(null);
+    setMissingModule(null);
+    setShowOutput(true);
+
+    try {
+      const check = await invoke<CheckResult>("check_project", {
+        project: currentProject,
+        windowId,
+      });
+      setDiagnostics(check.diagnostics);
+      setResult({
+        stdout: check.success && !check.output ? "No problems found\n" : "",
+        stderr: check.output,
+        exitCode: check.success ? 0 : 1,
+        durationMs: check.durationMs,
+      });
+    } catch (error) {
+      setResult({ stdout: "", stderr: String(error), exitCode: -1, durationMs: 0 });
+    } finally {
+      setIsChecking(false);
+    }
+  }, [currentProject, isRunning, isChecking, windowId]);
+
+  // Ends a run, or the compiler or checker of a check in progress
   const handleStop = useCallback(async () => {
-    if (!isRunning) return;
+    if (!isRunning && !isChecking) return;
     try {
       await invoke("stop_execution", { windowId });
       setIsRunning(false);
     } catch (error) {
       console.error("Failed to stop execution:", error);
     }
-  }, [isRunning, windowId]);
+  }, [isRunning, isChecking, windowId]);
+
+  // Send a line to the running program, echoed like a terminal would
+  const handleSendInput = useCallback(
+    async (data: string) => {
+      try {
+        await invoke("write_stdin", { windowId, data });
+        setChunks((prev) =>
+          insertChunk(prev, { text: data, stream: "stdin", seq: prev[prev.length - 1]?.seq ?? 0 })
+        );
+      } catch (error) {
+        toast.error(String(error));
+      }
+    },
+    [windowId, toast]
+  );
+
+  const handleCloseInput = useCallback(async () => {
+    await invoke("close_stdin", { windowId });
+  }, [windowId]);
 
   // Use shared menu events hook
   useMenuEvents({
     onRun: handleRun,
+    onCheck: handleCheck,
     onStop: handleStop,
     onToggleOutput: () => setShowOutput((prev) => !prev),
     onSaveAsTemplate: () => setShowSaveTemplate(true),
@@ -143,6 +232,7 @@ export function CompiledEditor() {
     onOpen: openProjectInNewWindow,
     onToggleOutput: () => setShowOutput((prev) => !prev),
     onRun: handleRun,
+    onCheck: handleCheck,
     onStop: handleStop,
   });
 
@@ -160,18 +250,49 @@ export function CompiledEditor() {
     });
 
     const unlistenOutput = appWindow.listen<ExecutionOutput>("execution:output", (event) => {
-      const { line, stream } = event.payload;
-      setStreamingOutput((prev) => ({ ...prev, [stream]: prev[stream] + line }));
+      const { line, stream, seq } = event.payload;
+      setChunks((prev) => insertChunk(prev, { text: line, stream, seq }));
     });
 
+    const unlistenDiagnostics = appWindow.listen<Diagnostic[]>(
+      "execution:diagnostics",
+      (event) => {
+        setDiagnostics(event.payload);
+      }
+    );
+
+    const unlistenWaitingInput = appWindow.listen<boolean>("execution:waiting-input", (event) => {
+      setIsWaitingInput(event.payload);
+    });
+
+    const unlistenEnvironment = appWindow.listen<EnvironmentStatus>(
+      "execution:environment",
+      (event) => {
+        setEnvironment(event.payload);
+      }
+    );
+
+    const unlistenMissingModule = appWindow.listen<MissingModule>(
+      "execution:missing-module",
+      (event) => {
+        setMissingModule(event.payload);
+      }
+    );
+
     const unlistenCompleted = appWindow.listen<ExecutionResult>("execution:completed", (event) => {
       setResult(event.payload);
+      setChunks((prev) => appendUnstreamed(prev, event.payload.stderr));
       setIsRunning(false);
+      setIsWaitingInput(false);
     });
 
     return () => {
       unlistenStateChange.then((fn) => fn());
       unlistenOutput.then((fn) => fn());
+      unlistenWaitingInput.then((fn) => fn());
+      unlistenDiagnostics.then((fn) => fn());
+      unlistenEnvironment.then((fn) => fn());
+      unlistenMissingModule.then((fn) => fn());
       unlistenCompleted.then((fn) => fn());
     };
   }, []);
@@ -234,6 +355,15 @@ export function CompiledEditor() {
         action: handleRun,
         category: "Run",
       },
+      {
+        id: "check-code",
+        label: "Check Code",
+        description: "Compile or type-check without running",
+        shortcut: "Ctrl+Shift+Enter",
+        icon: <CheckCircle className="h-4 w-4" />,
+        action: handleCheck,
+        category: "Run",
+      },
       {
         id: "clear-output",
         label: "Clear Output",
@@ -269,23 +399,30 @@ export function CompiledEditor() {
       },
       ...themeCommands,
     ],
-    [showOutput, handleRun, themeCommands]
+    [showOutput, handleRun, handleCheck, themeCommands]
   );
 
   // Menu bar configuration - must be before early return to follow Rules of Hooks
   const menus = useEditorMenus({
     isWebEditor: false,
-    isRunning,
+    isRunning: isRunning || isChecking,
     onSave: saveProject,
     onSaveAs: saveProjectAs,
     onSaveAsTemplate: () => setShowSaveTemplate(true),
     onOpen: openProjectInNewWindow,
     onToggleOutput: () => setShowOutput((prev) => !prev),
     onRun: handleRun,
+    onCheck: handleCheck,
     onStop: handleStop,
     onSettings: openSettings,
   });
 
+  // Diagnostics for the open file, shown as editor markers
+  const fileDiagnostics = useMemo(() => {
+    const name = currentProject?.files[0]?.name;
+    return diagnostics.filter((diagnostic) => diagnostic.file === name);
+  }, [currentProject, diagnostics]);
+
   if (isLoading || !currentProject) {
     return (
       <div className="flex h-screen items-center justify-center bg-base">
@@ -324,7 +461,7 @@ export function CompiledEditor() {
               {isRunning ? "Running..." : "Run"}
             </button>
 
-            {isRunning && (
+            {(isRunning || isChecking) && (
               <button
                 onClick={handleStop}
                 className="flex items-center gap-2 rounded-md bg-error/10 px-3 py-1.5 font-mono text-xs font-medium text-error transition-all hover:bg-error/20"
@@ -359,6 +496,7 @@ export function CompiledEditor() {
                 value={file.content}
                 language={file.language}
                 onChange={handleContentChange}
+                diagnostics={fileDiagnostics}
               />
             )}
           </div>
@@ -367,17 +505,29 @@ export function CompiledEditor() {
             <OutputPanel
               height={outputHeight}
               result={result}
-              streamingOutput={streamingOutput}
+              chunks={chunks}
+              environment={environment}
+              missingModule={missingModule}
               isRunning={isRunning}
+              isWaitingInput={isWaitingInput}
+              onSendInput={handleSendInput}
+              onCloseInput={handleCloseInput}
               onClear={() => {
                 setResult(null);
-                setStreamingOutput({ stdout: "", stderr: "" });
+                setChunks([]);
+                setEnvironment(null);
+                setMissingModule(null);
               }}
               onClose={() => setShowOutput(false)}
+              onCopyInstallCommand={async (command) => {
+                await navigator.clipboard.writeText(command);
+                toast.success("Install command copied to clipboard");
+              }}
               onCopy={async () => {
-                const output = result
-                  ? `${result.stdout}${result.stderr}`
-                  : `${streamingOutput.stdout}${streamingOutput.stderr}`;
+                const output =
+                  chunks.length > 0
+                    ? chunks.map((chunk) => chunk.text).join("")
+                    : `${result?.stdout ?? ""}${result?.stderr ?? ""}`;
                 if (output) {
                   await navigator.clipboard.writeText(output);
                   toast.success("Output copied to clipboard");
@@ -429,27 +579,65 @@ export function CompiledEditor() {
 
 // --- Sub-components extracted for clarity ---
 
+const CHUNK_CLASSES: Record<OutputChunk["stream"], string> = {
+  stdout: "",
+  stderr: "text-error",
+  stdin: "text-accent",
+};
+
+// Place a chunk by `seq`; echoed input shares the seq of the output before it
+function insertChunk(chunks: OutputChunk[], chunk: OutputChunk): OutputChunk[] {
+  let i = chunks.length;
+  while (i > 0 && chunks[i - 1].seq > chunk.seq) i--;
+  return [...chunks.slice(0, i), chunk, ...chunks.slice(i)];
+}
+
+// Add what the completed result reports beyond the streamed stderr, such as
+// a limit message
+function appendUnstreamed(chunks: OutputChunk[], stderr: string): OutputChunk[] {
+  if (chunks.length === 0) return chunks;
+  const streamed = chunks
+    .filter((chunk) => chunk.stream === "stderr")
+    .map((chunk) => chunk.text)
+    .join("");
+  const rest = stderr.startsWith(streamed) ? stderr.slice(streamed.length) : "";
+  if (!rest) return chunks;
+  return [...chunks, { text: rest, stream: "stderr", seq: chunks[chunks.length - 1].seq + 1 }];
+}
+
 interface OutputPanelProps {
   height: number;
   result: ExecutionResult | null;
-  streamingOutput: { stdout: string; stderr: string };
+  chunks: OutputChunk[];
+  environment: EnvironmentStatus | null;
+  missingModule: MissingModule | null;
   isRunning: boolean;
+  isWaitingInput: boolean;
+  onSendInput: (data: string) => void;
+  onCloseInput: () => void;
   onClear: () => void;
   onClose: () => void;
   onCopy: () => void;
+  onCopyInstallCommand: (command: string) => void;
 }
 
 function OutputPanel({
   height,
   result,
-  streamingOutput,
+  chunks,
+  environment,
+  missingModule,
   isRunning,
+  isWaitingInput,
+  onSendInput,
+  onCloseInput,
   onClear,
   onClose,
   onCopy,
+  onCopyInstallCommand,
 }: OutputPanelProps) {
   const hasOutput =
-    result?.stdout || result?.stderr || streamingOutput.stdout || streamingOutput.stderr;
+    result?.stdout || result?.stderr || chunks.length > 0;
   return (
     <div className="flex flex-col border-t border-border bg-mantle" style={{ height }}>
       <div className="flex h-9 flex-shrink-0 items-center justify-between border-b border-border px-4">
@@ -513,6 +701,16 @@ function OutputPanel({
       </div>
 
       <div className="flex-1 overflow-auto p-4">
+        {environment && (
+          <p
+            className={`mb-2 font-mono text-xs ${
+              environment.state === "failed" ? "text-error" : "text-text-subtle"
+            }`}
+          >
+            {environment.message}
+          </p>
+        )}
+
         {!result && !isRunning && (
           <p className="font-mono text-xs text-text-subtle">
             Press{" "}
@@ -521,39 +719,116 @@ function OutputPanel({
           </p>
         )}
 
-        {isRunning && (streamingOutput.stdout || streamingOutput.stderr) && (
-          <div className="space-y-2">
-            {streamingOutput.stdout && (
-              <pre className="whitespace-pre-wrap font-mono text-xs leading-relaxed text-text">
-                {streamingOutput.stdout}
-              </pre>
-            )}
-            {streamingOutput.stderr && (
-              <pre className="whitespace-pre-wrap font-mono text-xs leading-relaxed text-error">
-                {streamingOutput.stderr}
-              </pre>
-            )}
-          </div>
+        {chunks.length > 0 && (
+          <pre className="whitespace-pre-wrap font-mono text-xs leading-relaxed text-text">
+            {chunks.map((chunk, i) => (
+              <span key={i} className={CHUNK_CLASSES[chunk.stream]}>
+                {chunk.text}
+              </span>
+            ))}
+          </pre>
         )}
 
         {result && !isRunning && (
           <div className="space-y-2">
-            {result.stdout && (
+            {chunks.length === 0 && result.stdout && (
               <pre className="whitespace-pre-wrap font-mono text-xs leading-relaxed text-text">
                 {result.stdout}
               </pre>
             )}
-            {result.stderr && (
+            {chunks.length === 0 && result.stderr && (
               <pre className="whitespace-pre-wrap font-mono text-xs leading-relaxed text-error">
                 {result.stderr}
               </pre>
             )}
-            {!result.stdout && !result.stderr && (
+            {chunks.length === 0 && !result.stdout && !result.stderr && (
               <p className="font-mono text-xs text-text-subtle">(No output)</p>
             )}
+            {missingModule && (
+              <div className="rounded border border-border bg-surface-0 p-2 font-mono text-xs text-text-muted">
+                <p>
+                  {missingModule.module === missingModule.package ? (
+                    "The "
+                  ) : (
+                    <>
+                      <span className="text-text">{missingModule.module}</span> comes from the{" "}
+                    </>
+                  )}
+                  <span className="text-text">{missingModule.package}</span> package, which is not
+                  installed. Add it to {missingModule.manifest}, or install it with:
+                </p>
+                <div className="mt-1.5 flex items-center gap-2">
+                  <code className="flex-1 text-text">{missingModule.installCommand}</code>
+                  <button
+                    onClick={() => onCopyInstallCommand(missingModule.installCommand)}
+                    className="flex items-center gap-1 text-text-subtle transition-colors hover:text-text-muted"
+                    title="Copy install command"
+                  >
+                    <Copy className="h-3 w-3" />
+                    Copy
+                  </button>
+                </div>
+              </div>
+            )}
           </div>
         )}
       </div>
+
+      {isRunning && (
+        <InputLine isWaiting={isWaitingInput} onSend={onSendInput} onClose={onCloseInput} />
+      )}
+    </div>
+  );
+}
+
+interface InputLineProps {
+  isWaiting: boolean;
+  onSend: (data: string) => void;
+  onClose: () => void;
+}
+
+// Program input: Enter sends the line, Ctrl+D closes stdin
+function InputLine({ isWaiting, onSend, onClose }: InputLineProps) {
+  const [value, setValue] = useState("");
+  const inputRef = useRef<HTMLInputElement>(null);
+
+  useEffect(() => {
+    if (isWaiting) inputRef.current?.focus();
+  }, [isWaiting]);
+
+  return (
+    <div
+      className={`flex h-8 flex-shrink-0 items-center gap-2 border-t px-4 ${
+        isWaiting ? "border-accent" : "border-border"
+      }`}
+    >
+      <span className={`font-mono text-xs ${isWaiting ? "text-accent" : "text-text-subtle"}`}>
+        &gt;
+      </span>
+      <input
+        ref={inputRef}
+        value={value}
+        onChange={(e) => setValue(e.target.value)}
+        onKeyDown={(e) => {
+          if (e.key === "Enter") {
+            e.preventDefault();
+            onSend(`${value}\n`);
+            setValue("");
+          } else if (e.key === "d" && e.ctrlKey) {
+            e.preventDefault();
+            onClose();
+          }
+        }}
+        placeholder={isWaiting ? "Waiting for input..." : "Program input"}
+        className="flex-1 bg-transparent font-mono text-xs text-text outline-none placeholder:text-text-subtle"
+      />
+      <button
+        onClick={onClose}
+        className="font-mono text-xs text-text-subtle transition-colors hover:text-text-muted"
+        title="Close input (Ctrl+D)"
+      >
+        EOF
+      </button>
     </div>
   );
 }
@@ -1,4 +1,5 @@
 import { useProjectStore } from "@/stores/projectStore";
+import { useRuntimes } from "@/hooks";
 import type { QuickTemplate } from "@/types";
 import {
   Globe,
@@ -9,10 +10,16 @@ import {
   Coffee,
   FileCode,
   Star,
+  Hexagon,
+  Layers,
   Zap,
   Rocket,
   Code2,
   Braces,
+  Gem,
+  Scroll,
+  Moon,
+  SquareTerminal,
   Trash2,
   type LucideIcon,
 } from "lucide-react";
@@ -31,10 +38,16 @@ const ICON_MAP: Record<string, LucideIcon> = {
   code: FileCode,
   "file-code": FileCode,
   star: Star,
+  hexagon: Hexagon,
+  layers: Layers,
   zap: Zap,
   rocket: Rocket,
   code2: Code2,
   braces: Braces,
+  gem: Gem,
+  scroll: Scroll,
+  moon: Moon,
+  "square-terminal": SquareTerminal,
 };
 
 const COLOR_MAP: Record<string, string> = {
@@ -44,6 +57,17 @@ const COLOR_MAP: Record<string, string> = {
   python: "var(--color-template-python)",
   rust: "var(--color-template-rust)",
   java: "var(--color-template-java)",
+  kotlin: "var(--color-template-kotlin)",
+  scala: "var(--color-template-scala)",
+  groovy: "var(--color-template-groovy)",
+  go: "var(--color-template-go)",
+  c: "var(--color-template-c)",
+  cpp: "var(--color-template-cpp)",
+  ruby: "var(--color-template-ruby)",
+  php: "var(--color-template-php)",
+  perl: "var(--color-template-perl)",
+  lua: "var(--color-template-lua)",
+  bash: "var(--color-template-bash)",
 };
 
 const DESCRIPTION_MAP: Record<string, string> = {
@@ -53,10 +77,22 @@ const DESCRIPTION_MAP: Record<string, string> = {
   python: "Versatile scripting",
   rust: "Systems programming",
   java: "Enterprise ready",
+  kotlin: "Concise JVM code",
+  scala: "Functional on the JVM",
+  groovy: "JVM scripting",
+  go: "Simple concurrency",
+  c: "Close to the metal",
+  cpp: "Zero-cost abstractions",
+  ruby: "Developer happiness",
+  php: "Server-side scripting",
+  perl: "Text processing",
+  lua: "Lightweight scripting",
+  bash: "Shell automation",
 };
 
 export function QuickTemplates({ onCreate }: QuickTemplatesProps) {
   const { quickTemplates, deleteCustomTemplate } = useProjectStore();
+  const { inventory } = useRuntimes();
 
   // Separate built-in and custom templates
   const builtInTemplates = quickTemplates.filter((t) => t.isBuiltIn);
@@ -76,6 +112,7 @@ export function QuickTemplates({ onCreate }: QuickTemplatesProps) {
             key={template.id}
             template={template}
             index={index}
+            missingToolchain={inventory?.unavailable[template.type]}
             onClick={() => onCreate(template)}
           />
         ))}
@@ -92,6 +129,7 @@ export function QuickTemplates({ onCreate }: QuickTemplatesProps) {
               key={template.id}
               template={template}
               index={index + builtInTemplates.length}
+              missingToolchain={inventory?.unavailable[template.type]}
               onClick={() => onCreate(template)}
               onDelete={(e) => handleDelete(e, template.id)}
             />
@@ -105,19 +143,30 @@ export function QuickTemplates({ onCreate }: QuickTemplatesProps) {
 interface TemplateRowProps {
   template: QuickTemplate;
   index: number;
+  /** Toolchain the template's language needs but isn't installed */
+  missingToolchain?: string;
   onClick: () => void;
   onDelete?: (e: React.MouseEvent) => void;
 }
 
-function TemplateRow({ template, index, onClick, onDelete }: TemplateRowProps) {
+function TemplateRow({ template, index, missingToolchain, onClick, onDelete }: TemplateRowProps) {
   const Icon = ICON_MAP[template.icon] || FileCode;
-  const color = COLOR_MAP[template.id] || "var(--color-accent)";
-  const description = DESCRIPTION_MAP[template.id] || (template.isBuiltIn ? "" : "Custom template");
+  const color = missingToolchain
+    ? "var(--color-text-subtle)"
+    : COLOR_MAP[template.id] || "var(--color-accent)";
+  const description = missingToolchain
+    ? `Needs ${missingToolchain}`
+    : DESCRIPTION_MAP[template.id] || (template.isBuiltIn ? "" : "Custom template");
 
   return (
     <button
       onClick={onClick}
-      className={`group flex items-center gap-3 rounded-lg border border-transparent bg-transparent px-3 py-2.5 text-left transition-all animate-fade-in hover:border-border hover:bg-surface-0/50 stagger-${Math.min(index + 1, 6)}`}
+      title={
+        missingToolchain
+          ? `${missingToolchain} is not installed. See Settings for how to install it.`
+          : undefined
+      }
+      className={`group flex items-center gap-3 rounded-lg border border-transparent bg-transparent px-3 py-2.5 text-left transition-all animate-fade-in hover:border-border hover:bg-surface-0/50 stagger-${Math.min(index + 1, 6)} ${missingToolchain ? "opacity-60" : ""}`}
     >
       {/* Icon */}
       <div
@@ -131,7 +180,11 @@ function TemplateRow({ template, index, onClick, onDelete }: TemplateRowProps) {
 
       {/* Info */}
       <div className="min-w-0 flex-1">
-        <p className="font-mono text-sm font-medium text-text">{template.name}</p>
+        <p
+          className={`font-mono text-sm font-medium ${missingToolchain ? "text-text-muted" : "text-text"}`}
+        >
+          {template.name}
+        </p>
         {description && <p className="font-mono text-xs text-text-subtle">{description}</p>}
       </div>
 
@@ -8,6 +8,16 @@ import {
   Terminal,
   Cog,
   Coffee,
+  Hexagon,
+  Layers,
+  Star,
+  Zap,
+  Code2,
+  Braces,
+  Gem,
+  Scroll,
+  Moon,
+  SquareTerminal,
   FileCode,
   FolderOpen,
   Clock,
@@ -20,7 +30,18 @@ const TEMPLATE_ICONS: Record<TemplateType, LucideIcon> = {
   python: Terminal,
   rust: Cog,
   java: Coffee,
+  kotlin: Hexagon,
+  scala: Layers,
+  groovy: Star,
   typescript: FileCode,
+  go: Zap,
+  c: Code2,
+  cpp: Braces,
+  ruby: Gem,
+  php: FileCode,
+  perl: Scroll,
+  lua: Moon,
+  bash: SquareTerminal,
 };
 
 const TEMPLATE_COLORS: Record<TemplateType, string> = {
@@ -29,7 +50,18 @@ const TEMPLATE_COLORS: Record<TemplateType, string> = {
   python: "var(--color-template-python)",
   rust: "var(--color-template-rust)",
   java: "var(--color-template-java)",
+  kotlin: "var(--color-template-kotlin)",
+  scala: "var(--color-template-scala)",
+  groovy: "var(--color-template-groovy)",
   typescript: "var(--color-template-react)",
+  go: "var(--color-template-go)",
+  c: "var(--color-template-c)",
+  cpp: "var(--color-template-cpp)",
+  ruby: "var(--color-template-ruby)",
+  php: "var(--color-template-php)",
+  perl: "var(--color-template-perl)",
+  lua: "var(--color-template-lua)",
+  bash: "var(--color-template-bash)",
 };
 
 function formatRelativeTime(dateString: string): string {
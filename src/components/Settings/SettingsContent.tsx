@@ -13,8 +13,10 @@ import {
   ChevronDown,
   Check,
   Loader2,
+  RefreshCw,
 } from "lucide-react";
 import { invoke } from "@tauri-apps/api/core";
+import { useRuntimes } from "@/hooks";
 
 const THEME_OPTIONS: { value: ThemeMode; icon: typeof Sun; label: string; description: string }[] = [
   { value: "light", icon: Sun, label: "Light", description: "Catppuccin Latte" },
@@ -44,6 +46,7 @@ export function SettingsContent({ isActive = true }: SettingsContentProps) {
   const [isLoadingFonts, setIsLoadingFonts] = useState(true);
   const [fontDropdownOpen, setFontDropdownOpen] = useState(false);
   const dropdownRef = useRef<HTMLDivElement>(null);
+  const { inventory, isRefreshing, refresh: refreshRuntimes } = useRuntimes(isActive);
 
   // Load system fonts
   useEffect(() => {
@@ -284,6 +287,75 @@ export function SettingsContent({ isActive = true }: SettingsContentProps) {
           </pre>
         </div>
       </section>
+
+      {/* Runtimes Section */}
+      <section>
+        <div className="mb-3 flex items-center justify-between">
+          <h3 className="font-mono text-sm font-medium uppercase tracking-wider text-text-muted">
+            Runtimes
+          </h3>
+          <button
+            onClick={refreshRuntimes}
+            disabled={isRefreshing}
+            className="flex items-center gap-1.5 rounded-md border border-border bg-surface-0 px-2 py-1 font-mono text-xs text-text-muted transition-colors hover:border-surface-1 hover:text-text disabled:opacity-50"
+          >
+            <RefreshCw className={`h-3.5 w-3.5 ${isRefreshing ? "animate-spin" : ""}`} />
+            Refresh
+          </button>
+        </div>
+
+        {inventory ? (
+          <div className="overflow-hidden rounded-lg border border-border bg-mantle">
+            <table className="w-full table-fixed font-mono text-xs">
+              <thead className="border-b border-border bg-crust text-left text-text-muted">
+                <tr>
+                  <th className="w-36 px-3 py-2 font-medium">Runtime</th>
+                  <th className="w-20 px-3 py-2 font-medium">Version</th>
+                  <th className="px-3 py-2 font-medium">Location</th>
+                </tr>
+              </thead>
+              <tbody>
+                {inventory.runtimes.map((runtime) => {
+                  const location = runtime.path ?? runtime.installHint ?? runtime.downloadUrl;
+                  return (
+                    <tr key={runtime.command} className="border-b border-border last:border-0">
+                      <td className="px-3 py-2">
+                        <div className="flex items-center gap-2">
+                          <span
+                            className={`h-1.5 w-1.5 shrink-0 rounded-full ${
+                              runtime.path ? "bg-success" : "bg-surface-2"
+                            }`}
+                          />
+                          <span
+                            className={`truncate ${runtime.path ? "text-text" : "text-text-subtle"}`}
+                          >
+                            {runtime.name}
+                          </span>
+                        </div>
+                      </td>
+                      <td className="truncate px-3 py-2 text-text-muted">
+                        {runtime.version ?? (runtime.path ? "?" : "—")}
+                      </td>
+                      <td
+                        className={`truncate px-3 py-2 select-text ${
+                          runtime.path ? "text-text-muted" : "text-text-subtle"
+                        }`}
+                        title={location}
+                      >
+                        {location}
+                      </td>
+                    </tr>
+                  );
+                })}
+              </tbody>
+            </table>
+          </div>
+        ) : (
+          <div className="flex justify-center py-4">
+            <Loader2 className="h-4 w-4 animate-spin text-text-muted" />
+          </div>
+        )}
+      </section>
     </div>
   );
 }
@@ -9,6 +9,7 @@ import {
 } from "@codemirror/view";
 import { defaultKeymap, history, historyKeymap } from "@codemirror/commands";
 import { syntaxHighlighting, bracketMatching, HighlightStyle } from "@codemirror/language";
+import { lintGutter, setDiagnostics, type Diagnostic as LintDiagnostic } from "@codemirror/lint";
 import { html } from "@codemirror/lang-html";
 import { css } from "@codemirror/lang-css";
 import { javascript } from "@codemirror/lang-javascript";
@@ -17,12 +18,15 @@ import { rust } from "@codemirror/lang-rust";
 import { java } from "@codemirror/lang-java";
 import { tags } from "@lezer/highlight";
 import { useSettingsStore } from "@/stores/settingsStore";
+import type { Diagnostic } from "@/types";
 
 interface CodeEditorProps {
   value: string;
   language: string;
   onChange?: (value: string) => void;
   readOnly?: boolean;
+  // Compiler diagnostics for this file, shown as underlines and gutter markers
+  diagnostics?: Diagnostic[];
 }
 
 const languageExtensions: Record<string, () => ReturnType<typeof html>> = {
@@ -163,12 +167,45 @@ const catppuccinTheme = EditorView.theme({
   },
 });
 
+const LINT_SEVERITY: Record<Diagnostic["severity"], LintDiagnostic["severity"]> = {
+  error: "error",
+  warning: "warning",
+  note: "info",
+  help: "info",
+};
+
+// Map 1-based compiler locations to document offsets, clamped to the text
+function toLintDiagnostics(doc: EditorState["doc"], diagnostics: Diagnostic[]): LintDiagnostic[] {
+  const offset = (line: number, column: number) => {
+    const docLine = doc.line(Math.min(Math.max(line, 1), doc.lines));
+    return Math.min(docLine.from + Math.max(column - 1, 0), docLine.to);
+  };
+  return diagnostics.map((diagnostic) => {
+    const from = offset(diagnostic.line, diagnostic.column);
+    const span = diagnostic.spans.find((s) => s.primary && s.file === diagnostic.file);
+    const to = span ? offset(span.lineEnd, span.columnEnd) : from;
+    return {
+      from,
+      to: Math.max(to, from),
+      severity: LINT_SEVERITY[diagnostic.severity],
+      source: diagnostic.code ?? undefined,
+      message: diagnostic.message,
+    };
+  });
+}
+
 // Get syntax highlighting based on theme
 function getSyntaxHighlighting(isDark: boolean) {
   return syntaxHighlighting(isDark ? catppuccinMochaHighlight : catppuccinLatteHighlight);
 }
 
-export function CodeEditor({ value, language, onChange, readOnly = false }: CodeEditorProps) {
+export function CodeEditor({
+  value,
+  language,
+  onChange,
+  readOnly = false,
+  diagnostics,
+}: CodeEditorProps) {
   const containerRef = useRef<HTMLDivElement>(null);
   const viewRef = useRef<EditorView | null>(null);
   const onChangeRef = useRef(onChange);
@@ -201,6 +238,7 @@ export function CodeEditor({ value, language, onChange, readOnly = false }: Code
       doc: value,
       extensions: [
         lineNumbers(),
+        lintGutter(),
         highlightActiveLine(),
         highlightActiveLineGutter(),
         history(),
@@ -246,5 +284,12 @@ export function CodeEditor({ value, language, onChange, readOnly = false }: Code
     }
   }, [value]);
 
+  // Show diagnostics, again whenever the view is recreated
+  useEffect(() => {
+    const view = viewRef.current;
+    if (!view) return;
+    view.dispatch(setDiagnostics(view.state, toLintDiagnostics(view.state.doc, diagnostics ?? [])));
+  }, [diagnostics, language, readOnly, getLanguageExtension, resolvedTheme, editorSettings]);
+
   return <div ref={containerRef} className="h-full w-full overflow-hidden" />;
 }
@@ -27,6 +27,7 @@ const SHORTCUT_GROUPS: ShortcutGroup[] = [
     title: "Editor",
     shortcuts: [
       { keys: ["Ctrl", "Enter"], description: "Run code" },
+      { keys: ["Ctrl", "Shift", "Enter"], description: "Check code without running" },
       { keys: ["Ctrl", "."], description: "Stop execution" },
       { keys: ["Ctrl", "`"], description: "Toggle output panel" },
       { keys: ["Ctrl", "P"], description: "Toggle preview (Web)" },
@@ -222,6 +222,7 @@ export function useEditorMenus(options: {
   onTogglePreview?: () => void;
   onToggleOutput?: () => void;
   onRun?: () => void;
+  onCheck?: () => void;
   onStop?: () => void;
   onSettings?: () => void;
 }): MenuDefinition[] {
@@ -235,6 +236,7 @@ export function useEditorMenus(options: {
     onTogglePreview,
     onToggleOutput,
     onRun,
+    onCheck,
     onStop,
     onSettings,
   } = options;
@@ -326,6 +328,27 @@ export function useEditorMenus(options: {
       },
       { type: "action", id: "new-rust", label: "Rust", action: () => handleNewTemplate("rust") },
       { type: "action", id: "new-java", label: "Java", action: () => handleNewTemplate("java") },
+      {
+        type: "action",
+        id: "new-kotlin",
+        label: "Kotlin",
+        action: () => handleNewTemplate("kotlin"),
+      },
+      { type: "action", id: "new-scala", label: "Scala", action: () => handleNewTemplate("scala") },
+      {
+        type: "action",
+        id: "new-groovy",
+        label: "Groovy",
+        action: () => handleNewTemplate("groovy"),
+      },
+      { type: "action", id: "new-go", label: "Go", action: () => handleNewTemplate("go") },
+      { type: "action", id: "new-c", label: "C", action: () => handleNewTemplate("c") },
+      { type: "action", id: "new-cpp", label: "C++", action: () => handleNewTemplate("cpp") },
+      { type: "action", id: "new-ruby", label: "Ruby", action: () => handleNewTemplate("ruby") },
+      { type: "action", id: "new-php", label: "PHP", action: () => handleNewTemplate("php") },
+      { type: "action", id: "new-perl", label: "Perl", action: () => handleNewTemplate("perl") },
+      { type: "action", id: "new-lua", label: "Lua", action: () => handleNewTemplate("lua") },
+      { type: "action", id: "new-bash", label: "Bash", action: () => handleNewTemplate("bash") },
     ];
 
     if (customTemplates.length === 0) {
@@ -448,6 +471,14 @@ export function useEditorMenus(options: {
         action: onRun || (() => {}),
         disabled: isRunning,
       },
+      {
+        type: "action",
+        id: "check-code",
+        label: "Check Code",
+        shortcut: "Ctrl+Shift+Enter",
+        action: onCheck || (() => {}),
+        disabled: isRunning,
+      },
       {
         type: "action",
         id: "stop-code",
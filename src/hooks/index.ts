@@ -6,3 +6,4 @@ export { useUnsavedChanges } from "./useUnsavedChanges";
 export { useWindowTitle } from "./useWindowTitle";
 export { useKeyboardShortcuts } from "./useKeyboardShortcuts";
 export { useWindowState } from "./useWindowState";
+export { useRuntimes } from "./useRuntimes";
@@ -7,6 +7,7 @@ interface KeyboardShortcutHandlers {
   onTogglePreview?: () => void;
   onToggleOutput?: () => void;
   onRun?: () => void;
+  onCheck?: () => void;
   onStop?: () => void;
 }
 
@@ -58,9 +59,13 @@ export function useKeyboardShortcuts(handlers: KeyboardShortcutHandlers): void {
             handlersRef.current.onToggleOutput?.();
             break;
           case "enter":
-            // Run code
+            // Run code, or check it without running
             e.preventDefault();
-            handlersRef.current.onRun?.();
+            if (e.shiftKey) {
+              handlersRef.current.onCheck?.();
+            } else {
+              handlersRef.current.onRun?.();
+            }
             break;
           case ".":
             // Stop code
@@ -8,6 +8,7 @@ interface MenuEventHandlers {
   onTogglePreview?: () => void;
   onToggleOutput?: () => void;
   onRun?: () => void;
+  onCheck?: () => void;
   onStop?: () => void;
   onAbout?: () => void;
   onSaveAsTemplate?: () => void;
@@ -137,6 +138,12 @@ export function useMenuEvents(handlers: MenuEventHandlers = {}): void {
       })
     );
 
+    listeners.push(
+      listen("menu:check-code", () => {
+        handlersRef.current.onCheck?.();
+      })
+    );
+
     listeners.push(
       listen("menu:stop-code", () => {
         handlersRef.current.onStop?.();
@@ -0,0 +1,40 @@
+import { useCallback, useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import type { RuntimeInventory } from "@/types";
+
+/**
+ * Hook to read the installed runtimes. The backend scans them once at startup,
+ * so loading is usually instant; refresh() rescans after installing something.
+ */
+export function useRuntimes(enabled = true) {
+  const [inventory, setInventory] = useState<RuntimeInventory | null>(null);
+  const [isRefreshing, setIsRefreshing] = useState(false);
+
+  useEffect(() => {
+    if (!enabled) return;
+
+    let cancelled = false;
+    invoke<RuntimeInventory>("list_runtimes")
+      .then((result) => {
+        if (!cancelled) setInventory(result);
+      })
+      .catch((error) => console.error("Failed to list runtimes:", error));
+
+    return () => {
+      cancelled = true;
+    };
+  }, [enabled]);
+
+  const refresh = useCallback(async () => {
+    setIsRefreshing(true);
+    try {
+      setInventory(await invoke<RuntimeInventory>("refresh_runtimes"));
+    } catch (error) {
+      console.error("Failed to refresh runtimes:", error);
+    } finally {
+      setIsRefreshing(false);
+    }
+  }, []);
+
+  return { inventory, isRefreshing, refresh };
+}
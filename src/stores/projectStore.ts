@@ -86,6 +86,83 @@ const DEFAULT_QUICK_TEMPLATES: QuickTemplate[] = [
     icon: "coffee",
     isBuiltIn: true,
   },
+  {
+    id: "kotlin",
+    name: "Kotlin",
+    type: "kotlin",
+    icon: "hexagon",
+    isBuiltIn: true,
+  },
+  {
+    id: "scala",
+    name: "Scala",
+    type: "scala",
+    icon: "layers",
+    isBuiltIn: true,
+  },
+  {
+    id: "groovy",
+    name: "Groovy",
+    type: "groovy",
+    icon: "star",
+    isBuiltIn: true,
+  },
+  {
+    id: "go",
+    name: "Go",
+    type: "go",
+    icon: "zap",
+    isBuiltIn: true,
+  },
+  {
+    id: "c",
+    name: "C",
+    type: "c",
+    icon: "code2",
+    isBuiltIn: true,
+  },
+  {
+    id: "cpp",
+    name: "C++",
+    type: "cpp",
+    icon: "braces",
+    isBuiltIn: true,
+  },
+  {
+    id: "ruby",
+    name: "Ruby",
+    type: "ruby",
+    icon: "gem",
+    isBuiltIn: true,
+  },
+  {
+    id: "php",
+    name: "PHP",
+    type: "php",
+    icon: "file-code",
+    isBuiltIn: true,
+  },
+  {
+    id: "perl",
+    name: "Perl",
+    type: "perl",
+    icon: "scroll",
+    isBuiltIn: true,
+  },
+  {
+    id: "lua",
+    name: "Lua",
+    type: "lua",
+    icon: "moon",
+    isBuiltIn: true,
+  },
+  {
+    id: "bash",
+    name: "Bash",
+    type: "bash",
+    icon: "square-terminal",
+    isBuiltIn: true,
+  },
 ];
 
 function generateDefaultFiles(template: TemplateType, _config?: WebTemplateConfig): ProjectFile[] {
@@ -253,6 +330,237 @@ console.log("Time:", greeting.timestamp.toISOString());`,
         },
       ];
 
+    case "kotlin":
+      return [
+        {
+          name: "main.kt",
+          language: "kotlin",
+          content: `fun main() {
+    println("Hello from CodeCell!")
+
+    // Example: Simple calculation
+    val numbers = listOf(1, 2, 3, 4, 5)
+    val sum = numbers.sum()
+    val avg = numbers.average()
+
+    println("Sum: $sum")
+    println("Average: " + "%.2f".format(avg))
+}`,
+        },
+      ];
+
+    case "scala":
+      return [
+        {
+          name: "main.scala",
+          language: "scala",
+          content: `object Main {
+  def main(args: Array[String]): Unit = {
+    println("Hello from CodeCell!")
+
+    // Example: Simple calculation
+    val numbers = List(1, 2, 3, 4, 5)
+    val sum = numbers.sum
+    val avg = sum.toDouble / numbers.size
+
+    println(s"Sum: $sum")
+    println(f"Average: $avg%.2f")
+  }
+}`,
+        },
+      ];
+
+    case "groovy":
+      return [
+        {
+          name: "main.groovy",
+          language: "groovy",
+          content: `println "Hello from CodeCell!"
+
+// Example: Simple calculation
+def numbers = [1, 2, 3, 4, 5]
+def sum = numbers.sum()
+def avg = sum / numbers.size()
+
+println "Sum: $sum"
+println "Average: " + String.format("%.2f", avg)`,
+        },
+      ];
+
+    case "go":
+      return [
+        {
+          name: "main.go",
+          language: "go",
+          content: `package main
+
+import "fmt"
+
+func main() {
+	fmt.Println("Hello from CodeCell!")
+
+	// Example: Simple calculation
+	numbers := []int{1, 2, 3, 4, 5}
+	sum := 0
+	for _, n := range numbers {
+		sum += n
+	}
+	avg := float64(sum) / float64(len(numbers))
+
+	fmt.Println("Sum:", sum)
+	fmt.Printf("Average: %.2f\\n", avg)
+}`,
+        },
+      ];
+
+    case "c":
+      return [
+        {
+          name: "main.c",
+          language: "c",
+          content: `#include <stdio.h>
+
+int main(void) {
+    printf("Hello from CodeCell!\\n");
+
+    // Example: Simple calculation
+    int numbers[] = {1, 2, 3, 4, 5};
+    int count = sizeof(numbers) / sizeof(numbers[0]);
+    int sum = 0;
+    for (int i = 0; i < count; i++) {
+        sum += numbers[i];
+    }
+
+    printf("Sum: %d\\n", sum);
+    printf("Average: %.2f\\n", (double)sum / count);
+    return 0;
+}`,
+        },
+      ];
+
+    case "cpp":
+      return [
+        {
+          name: "main.cpp",
+          language: "cpp",
+          content: `#include <iostream>
+#include <numeric>
+#include <vector>
+
+int main() {
+    std::cout << "Hello from CodeCell!" << std::endl;
+
+    // Example: Simple calculation
+    std::vector<int> numbers = {1, 2, 3, 4, 5};
+    int sum = std::accumulate(numbers.begin(), numbers.end(), 0);
+    double avg = static_cast<double>(sum) / numbers.size();
+
+    std::cout << "Sum: " << sum << std::endl;
+    std::cout << "Average: " << avg << std::endl;
+    return 0;
+}`,
+        },
+      ];
+
+    case "ruby":
+      return [
+        {
+          name: "main.rb",
+          language: "ruby",
+          content: `puts "Hello from CodeCell!"
+
+# Example: Simple calculation
+numbers = [1, 2, 3, 4, 5]
+sum = numbers.sum
+avg = sum.to_f / numbers.size
+
+puts "Sum: #{sum}"
+puts "Average: #{avg}"`,
+        },
+      ];
+
+    case "php":
+      return [
+        {
+          name: "main.php",
+          language: "php",
+          content: `<?php
+
+echo "Hello from CodeCell!\\n";
+
+// Example: Simple calculation
+$numbers = [1, 2, 3, 4, 5];
+$sum = array_sum($numbers);
+$avg = $sum / count($numbers);
+
+echo "Sum: $sum\\n";
+printf("Average: %.2f\\n", $avg);`,
+        },
+      ];
+
+    case "perl":
+      return [
+        {
+          name: "main.pl",
+          language: "perl",
+          content: `use strict;
+use warnings;
+
+print "Hello from CodeCell!\\n";
+
+# Example: Simple calculation
+my @numbers = (1, 2, 3, 4, 5);
+my $sum = 0;
+$sum += $_ for @numbers;
+my $avg = $sum / @numbers;
+
+print "Sum: $sum\\n";
+printf "Average: %.2f\\n", $avg;`,
+        },
+      ];
+
+    case "lua":
+      return [
+        {
+          name: "main.lua",
+          language: "lua",
+          content: `print("Hello from CodeCell!")
+
+-- Example: Simple calculation
+local numbers = { 1, 2, 3, 4, 5 }
+local sum = 0
+for _, n in ipairs(numbers) do
+  sum = sum + n
+end
+local avg = sum / #numbers
+
+print("Sum: " .. sum)
+print(string.format("Average: %.2f", avg))`,
+        },
+      ];
+
+    case "bash":
+      return [
+        {
+          name: "main.sh",
+          language: "shell",
+          content: `#!/usr/bin/env bash
+set -euo pipefail
+
+echo "Hello from CodeCell!"
+
+# Example: Simple calculation
+numbers=(1 2 3 4 5)
+sum=0
+for n in "\${numbers[@]}"; do
+  sum=$((sum + n))
+done
+
+echo "Sum: $sum"
+echo "Average: $((sum / \${#numbers[@]}))"`,
+        },
+      ];
+
     default:
       return [];
   }
@@ -1,5 +1,22 @@
 // Template Types
-export type TemplateType = "web" | "node" | "python" | "rust" | "java" | "typescript";
+export type TemplateType =
+  | "web"
+  | "node"
+  | "python"
+  | "rust"
+  | "java"
+  | "typescript"
+  | "go"
+  | "c"
+  | "cpp"
+  | "ruby"
+  | "php"
+  | "perl"
+  | "lua"
+  | "bash"
+  | "kotlin"
+  | "scala"
+  | "groovy";
 
 export interface WebTemplateConfig {
   markup: "html";
@@ -38,6 +55,72 @@ export interface Project {
   createdAt: string;
   updatedAt: string;
   savedPath: string | null;
+  cargo?: CargoOptions | null;
+  python?: PythonOptions | null;
+  node?: NodeOptions | null;
+  cc?: CcOptions | null;
+  run?: RunConfig | null;
+  // Version-managed toolchains to run with instead of the ones on PATH
+  toolchains?: ToolchainPin[] | null;
+  // Registry language id, for languages without a template
+  language?: string | null;
+}
+
+export interface RunConfig {
+  args: string[];
+  env: Record<string, string>;
+  envFile: string | null;
+  workingDir: string | null;
+}
+
+export interface CargoOptions {
+  offline: boolean;
+}
+
+export interface PythonOptions {
+  wheelhouse: string | null;
+}
+
+export type JsRuntime = "node" | "deno" | "bun";
+
+export interface NodeOptions {
+  dependencies: Record<string, string>;
+  offline: boolean;
+  runtime: JsRuntime;
+  // Deno `--allow-*` permissions, e.g. "read"; all of them when null
+  denoPermissions: string[] | null;
+}
+
+export type CcCompiler = "gcc" | "clang";
+
+// C/C++ build options; the compiler defaults to whichever is installed
+export interface CcOptions {
+  compiler: CcCompiler | null;
+  standard: string | null;
+  optimization: "0" | "1" | "2" | "3" | "s" | "z" | "g" | null;
+  flags: string[];
+}
+
+export type ManagedRuntime = "python" | "node" | "rust" | "java" | "kotlin" | "scala" | "groovy";
+
+export type VersionManager = "pyenv" | "conda" | "nvm" | "fnm" | "sdkman" | "rustup";
+
+// e.g. Python 3.11 via pyenv; without a manager, any that has a match
+export interface ToolchainPin {
+  runtime: ManagedRuntime;
+  manager: VersionManager | null;
+  // Toolchain or env name ("nightly", a conda env), or a version prefix ("3.11")
+  version: string;
+}
+
+// Installations found by "list_toolchains"
+export interface ManagedToolchain {
+  manager: VersionManager;
+  runtime: ManagedRuntime;
+  name: string;
+  version: string | null;
+  home: string;
+  binDir: string;
 }
 
 export interface ProjectFile {
@@ -79,5 +162,92 @@ export interface ExecutionResult {
   duration: number;
 }
 
+// Result of "check_project", which compiles or type-checks without running
+export interface CheckResult {
+  success: boolean;
+  diagnostics: Diagnostic[];
+  output: string;
+  durationMs: number;
+}
+
+// Compiler diagnostics, sent with the "execution:diagnostics" event
+export interface Diagnostic {
+  file: string;
+  line: number;
+  column: number;
+  severity: "error" | "warning" | "note" | "help";
+  code: string | null;
+  message: string;
+  spans: DiagnosticSpan[];
+}
+
+export interface DiagnosticSpan {
+  file: string;
+  lineStart: number;
+  columnStart: number;
+  lineEnd: number;
+  columnEnd: number;
+  label: string | null;
+  primary: boolean;
+}
+
+// Compiler output "emit_artifact" can show: Rust asm/LLVM IR/MIR, Java
+// bytecode, C/C++ asm
+export type ArtifactKind = "asm" | "llvmIr" | "mir" | "bytecode";
+
+export interface ArtifactOptions {
+  kind: ArtifactKind;
+  optLevel?: "0" | "1" | "2" | "3" | "s" | "z" | null;
+  // e.g. "native" or "skylake"
+  targetCpu?: string | null;
+}
+
+export interface Artifact {
+  kind: ArtifactKind;
+  text: string;
+  // One entry per line of `text`: the source line it was generated from
+  lines: (SourceLine | null)[];
+}
+
+export interface SourceLine {
+  file: string;
+  line: number;
+}
+
+// Languages from the backend registry, as returned by "list_languages"
+export interface LanguageDefinition {
+  id: string;
+  name: string;
+  extensions: string[];
+  entryFile: string | null;
+  runtimes: RuntimeInfo[];
+  compile: string[] | null;
+  check: string[] | null;
+  run: string[] | null;
+}
+
+export interface RuntimeInfo {
+  name: string;
+  command: string;
+  downloadUrl: string;
+  install: Record<string, string>;
+}
+
+// Installed runtimes, as returned by "list_runtimes" and "refresh_runtimes"
+export interface RuntimeStatus {
+  name: string;
+  command: string;
+  path: string | null;
+  version: string | null;
+  installHint: string | null;
+  downloadUrl: string;
+}
+
+export interface RuntimeInventory {
+  runtimes: RuntimeStatus[];
+  // Language id -> the toolchain it is missing, e.g. "GCC or Clang"
+  unavailable: Record<string, string>;
+}
+
 // Re-export template utilities
 export { TEMPLATE_MAP, type TemplateDefinition } from "./templates";
@@ -22,4 +22,15 @@ export const TEMPLATE_MAP: Record<string, TemplateDefinition> = {
   python: { type: "python" },
   rust: { type: "rust" },
   java: { type: "java" },
+  go: { type: "go" },
+  c: { type: "c" },
+  cpp: { type: "cpp" },
+  ruby: { type: "ruby" },
+  php: { type: "php" },
+  perl: { type: "perl" },
+  lua: { type: "lua" },
+  bash: { type: "bash" },
+  kotlin: { type: "kotlin" },
+  scala: { type: "scala" },
+  groovy: { type: "groovy" },
 };